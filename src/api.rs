@@ -0,0 +1,256 @@
+// `process_killer serve`, the listing and signalling behind the TUI as a small JSON API for
+// dashboards and scripts on other machines, every request needs `Authorization: Bearer <token>`
+use std::{io, net::TcpListener, sync::Mutex, thread};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sysinfo::{PidExt, ProcessExt};
+
+use crate::{
+    config::Config,
+    events::{watch, Event, Subscribers},
+    http::{read_request, respond, serve_connections, Request},
+    process::{parse_signal, send_signal, signal_name, status_label, SignalError},
+    websocket, ProcessInfo, ProcessManager,
+};
+
+const CONTENT_TYPE: &str = "application/json";
+
+// a row of `process_killer list --json` and of `GET /processes`
+#[derive(Serialize)]
+pub struct ProcessRecord<'a> {
+    pid: u32,
+    name: &'a str,
+    cmdline: &'a str,
+    cpu: f32,
+    // resident memory in bytes
+    memory: u64,
+    user: &'a str,
+}
+
+impl<'a> From<&'a ProcessInfo> for ProcessRecord<'a> {
+    fn from(process: &'a ProcessInfo) -> Self {
+        Self {
+            pid: process.pid.as_u32(),
+            name: &process.name,
+            cmdline: &process.cmd,
+            cpu: process.cpu_usage,
+            memory: process.memory,
+            user: &process.user,
+        }
+    }
+}
+
+// `GET /processes/<pid>`, what the details pane shows
+#[derive(Serialize)]
+struct ProcessDetails<'a> {
+    #[serde(flatten)]
+    record: ProcessRecord<'a>,
+    parent: Option<u32>,
+    virtual_memory: u64,
+    status: String,
+    threads: Option<usize>,
+    // seconds since the epoch
+    start_time: u64,
+    // empty when it can't be read
+    exe: String,
+    cwd: String,
+}
+
+// the body of `POST /processes/<pid>/signal`
+#[derive(Deserialize)]
+struct SignalRequest {
+    // a name like `TERM` or `SIGHUP`, or a number
+    signal: String,
+}
+
+// answers the requests until killed, each connection on its own thread, while the events are
+// watched for on another
+pub fn serve(config: &Config, address: &str, token: &str) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    // one request at a time gets to refresh and signal
+    let manager = Mutex::new(ProcessManager::new());
+    let subscribers = Subscribers::default();
+    println!(
+        "serving the API on http://{}, Ctrl-C to stop",
+        listener.local_addr()?
+    );
+
+    let subscribers = &subscribers;
+    thread::scope(|scope| {
+        scope.spawn(|| watch(config, subscribers));
+        serve_connections(&listener, |stream| {
+            let (status, body) = match read_request(&stream) {
+                Ok(request) if !is_authorized(&request, token) => {
+                    error(401, "missing or wrong bearer token")
+                }
+                // kept open, the events are pushed to it from then on. on a thread of its own, an
+                // idle subscriber doesn't take up one of the connections
+                Ok(request) if request.path == "/events" => {
+                    match websocket::accept(&stream, &request) {
+                        Ok(()) => {
+                            scope.spawn(move || subscribers.listen(stream));
+                            return;
+                        }
+                        Err(err) => error(400, &err.to_string()),
+                    }
+                }
+                Ok(request) => {
+                    let mut manager = manager.lock().unwrap_or_else(|err| err.into_inner());
+                    manager.refresh();
+                    handle(config, &manager, &request, subscribers)
+                }
+                Err(err) => error(400, &err.to_string()),
+            };
+            if let Err(err) = respond(&stream, status, CONTENT_TYPE, &body.to_string()) {
+                eprintln!("could not answer a request: {}", err);
+            }
+        });
+    });
+    Ok(())
+}
+
+// compared in constant time, so the token can't be guessed from how long a refusal takes
+fn is_authorized(request: &Request, token: &str) -> bool {
+    let Some(given) = request
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn handle(
+    config: &Config,
+    manager: &ProcessManager,
+    request: &Request,
+    subscribers: &Subscribers,
+) -> (u16, serde_json::Value) {
+    let parts: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    match (request.method.as_str(), parts.as_slice()) {
+        ("GET", ["processes"]) => {
+            let mut processes = manager.processes();
+            processes.sort_by_key(|process| process.pid);
+            let records: Vec<ProcessRecord> = processes.iter().map(ProcessRecord::from).collect();
+            (200, json!(records))
+        }
+        ("GET", ["processes", pid]) => match find(manager, pid) {
+            Ok(info) => (200, json!(details(manager, &info))),
+            Err(err) => err,
+        },
+        ("POST", ["processes", pid, "signal"]) => match find(manager, pid) {
+            Ok(info) => signal(config, manager, &info, &request.body, subscribers),
+            Err(err) => err,
+        },
+        (_, ["processes"] | ["processes", _] | ["processes", _, "signal"]) => {
+            error(405, "method not allowed")
+        }
+        _ => error(404, "unknown path"),
+    }
+}
+
+fn find(manager: &ProcessManager, pid: &str) -> Result<ProcessInfo, (u16, serde_json::Value)> {
+    let pid = pid
+        .parse::<u32>()
+        .map_err(|_| error(400, &format!("{:?} is not a PID", pid)))?;
+    manager
+        .processes()
+        .into_iter()
+        .find(|process| process.pid.as_u32() == pid)
+        .ok_or_else(|| error(404, &format!("no process {}", pid)))
+}
+
+fn details<'a>(manager: &ProcessManager, info: &'a ProcessInfo) -> ProcessDetails<'a> {
+    let process = manager.process(info.pid);
+    let path = |path: Option<&std::path::Path>| {
+        path.map_or_else(String::new, |path| path.display().to_string())
+    };
+    ProcessDetails {
+        record: ProcessRecord::from(info),
+        parent: info.parent.map(|pid| pid.as_u32()),
+        virtual_memory: info.virtual_memory,
+        status: status_label(info.status),
+        threads: info.threads,
+        start_time: info.start_time,
+        exe: path(process.map(|process| process.exe())),
+        cwd: path(process.map(|process| process.cwd())),
+    }
+}
+
+// the same checks as the TUI: protected processes, --read-only and --dry-run
+fn signal(
+    config: &Config,
+    manager: &ProcessManager,
+    info: &ProcessInfo,
+    body: &[u8],
+    subscribers: &Subscribers,
+) -> (u16, serde_json::Value) {
+    let request: SignalRequest = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(err) => return error(400, &format!("expected {{\"signal\": \"TERM\"}}: {}", err)),
+    };
+    let Some(signal) = parse_signal(&request.signal) else {
+        return error(400, &format!("unknown signal {:?}", request.signal));
+    };
+    let Some(process) = manager.process(info.pid) else {
+        return error(404, &format!("no process {}", info.pid));
+    };
+    if config.read_only {
+        return error(403, "read-only mode, no signal is sent");
+    }
+    if config.is_protected(process) {
+        return error(403, &format!("{} {} is protected", info.pid, info.name));
+    }
+
+    // also pushed to the `/events` subscribers, failed or not
+    let killed = |result: String| {
+        subscribers.send(&Event::Killed {
+            pid: info.pid.as_u32(),
+            name: info.name.clone(),
+            signal: signal_name(signal).to_string(),
+            result,
+        })
+    };
+    let reply = |result: &str| {
+        json!({
+            "pid": info.pid.as_u32(),
+            "name": info.name,
+            "signal": signal_name(signal),
+            "result": result,
+        })
+    };
+    if config.dry_run {
+        killed("dry run".to_string());
+        return (200, reply("dry run"));
+    }
+
+    let result = send_signal(process, signal);
+    killed(
+        result
+            .as_ref()
+            .map_or_else(ToString::to_string, |()| "sent".to_string()),
+    );
+    if let Some(audit_log) = &config.audit_log {
+        let logged = result.as_ref().map_err(|err| err.to_string()).copied();
+        if let Err(err) = audit_log.record(process, signal, &logged) {
+            eprintln!("could not write to the audit log: {}", err);
+        }
+    }
+    match result {
+        Ok(()) => (200, reply("sent")),
+        Err(err @ SignalError::PermissionDenied) => error(403, &err.to_string()),
+        Err(err @ SignalError::Gone) => error(404, &err.to_string()),
+        Err(err @ SignalError::Unsupported) => error(400, &err.to_string()),
+        Err(err @ SignalError::Other(_)) => error(500, &err.to_string()),
+    }
+}
+
+fn error(status: u16, message: &str) -> (u16, serde_json::Value) {
+    (status, json!({ "error": message }))
+}
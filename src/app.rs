@@ -0,0 +1,3275 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    fs, io, mem,
+    path::{Path, PathBuf},
+    process::ExitStatus,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crossterm::{
+    event::{self, Event},
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
+use sysinfo::{Pid, PidExt, Process, ProcessExt, ProcessStatus, Signal, SystemExt, Uid};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    widgets::{ListState, TableState},
+    Terminal,
+};
+
+use crate::{
+    cgroup::{process_cgroup, Container},
+    clipboard, collector,
+    columns::{column_layout, Column},
+    config::{data_dir, Config},
+    container::{container_name, control_container, ContainerControl},
+    error::AppError,
+    export::export_csv,
+    files::{open_files, OpenFile},
+    input::{handle_key, handle_mouse, Action, PendingKeys},
+    launch::{reveal, Launch},
+    limits::{resource_limits, ResourceLimit},
+    net::{process_connections, Connection},
+    notify::notify,
+    process::{
+        cpu_affinity, elevated_kill, elevation_tool, format_cpu_list, format_duration,
+        format_timestamp, gcore, nice_value, parse_cpu_list, parse_signal, set_cpu_affinity,
+        set_nice_value, shared_memory, signal_name, split_user_filter, ChildInfo, MemoryKind,
+        ProcessInfo, ProcessManager, SearchFilter, SignalError, SortColumn, SortOrder, StateFilter,
+        ThreadInfo, SIGNALS,
+    },
+    refresh::Refresher,
+    rules::LimitTracker,
+    search_history::SearchHistory,
+    services::{control_service, list_services, Service, ServiceControl},
+    systemd::{control_unit, Unit, UnitControl},
+    ui::ui,
+};
+
+pub(crate) enum InputMode {
+    Normal,
+    Editing,
+    SignalMenu,
+    Confirm,
+    ConfirmCoreDump,
+    Help,
+    Affinity,
+    Elevate,
+    Zombies,
+    Escalate,
+    Services,
+    OpenFiles,
+    Limits,
+    Connections,
+    Threads,
+    Children,
+    Exited,
+    History,
+    Filters,
+    Columns,
+    JumpToPid,
+    Spawn,
+    SignalInput,
+    CoreDump,
+    ScheduleKill,
+    ScheduledKills,
+    Alerts,
+}
+
+// two clicks on the same row within this window open the details pane
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+// how often the process list is refetched in the background
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+// how often the spinner moves while a refresh runs in the background
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+// cpu samples kept per process for the details sparkline, a few minutes at the default interval
+const CPU_HISTORY_LEN: usize = 120;
+
+// how long a process that just appeared stays highlighted
+const SPAWN_HIGHLIGHT: Duration = Duration::from_secs(5);
+
+// how many exited processes are remembered, and for how long
+const EXITED_LEN: usize = 100;
+pub(crate) const EXITED_KEEP: Duration = Duration::from_secs(10 * 60);
+
+// characters the name or command line column moves per key press
+const NAME_SCROLL_STEP: isize = 8;
+
+// the longest a kill can be scheduled ahead, anything longer is taken as a typo
+const MAX_DELAY: Duration = Duration::from_secs(365 * 24 * 3600);
+
+// how long typing has to pause before the table is filtered by the search input
+const LIVE_SEARCH_DELAY: Duration = Duration::from_millis(150);
+
+// as many as Alt+1 to Alt+9 reach
+const MAX_TABS: usize = 9;
+
+// how long a toast stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+// how often signalled processes are checked during the grace period
+const KILL_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+// a message shown in the corner until it expires
+pub(crate) struct Toast {
+    pub(crate) message: String,
+    pub(crate) shown_at: Instant,
+}
+
+// terminated processes watched until they exit or the grace period is over
+struct KillCheck {
+    signal: Signal,
+    pids: Vec<Pid>,
+    deadline: Instant,
+    // a polite kill, the survivors get SIGKILL at the deadline without asking
+    force: bool,
+    // how many were signalled, to tell how many exited on their own
+    sent: usize,
+}
+
+// what the copy actions put on the clipboard, of the selected process
+#[derive(Clone, Copy)]
+pub enum CopyField {
+    Pid,
+    // the name when the command line can't be read
+    Command,
+    Exe,
+    Cwd,
+}
+
+// a process that went over the limit of an [[alerts]] entry, listed until dismissed
+pub(crate) struct FiredAlert {
+    // unix timestamp
+    pub(crate) at: u64,
+    pub(crate) pid: Pid,
+    pub(crate) name: String,
+    // the name of the alert and what went over
+    pub(crate) alert: String,
+    pub(crate) limits: String,
+}
+
+// `V` marks the rows between where it was pressed and the selection as it moves, like vim's
+// visual mode
+pub(crate) struct Visual {
+    anchor: Pid,
+    // the marks from before, kept when the range shrinks back
+    marked: HashSet<Pid>,
+}
+
+// the search, filters and sort of a tab that isn't shown, the shown one's are in `App`
+pub(crate) struct View {
+    state: TableState,
+    scroll_offset: usize,
+    sort_column: SortColumn,
+    sort_order: SortOrder,
+    search_input: String,
+    search_filter: SearchFilter,
+    search_error: Option<String>,
+    user_filter: Option<String>,
+    only_my_processes: bool,
+    state_filter: StateFilter,
+    tree_view: bool,
+    collapsed: HashSet<Pid>,
+    grouped: bool,
+    expanded: HashSet<String>,
+}
+
+// a polite kill set to go off later, with `w`
+pub(crate) struct ScheduledKill {
+    pub(crate) pid: Pid,
+    pub(crate) name: String,
+    // tells the process apart from a later one that got its pid
+    start_time: u64,
+    pub(crate) at: Instant,
+}
+
+// a signal waiting for the user to confirm it
+pub(crate) struct PendingSignal {
+    pub(crate) signal: Signal,
+    pub(crate) pids: Vec<Pid>,
+    // SIGKILL follows for the ones still running after the polite kill timeout
+    pub(crate) force: bool,
+}
+
+// the container whose main process is about to be signalled, stopping it through the
+// engine keeps the restart policy from bringing it right back
+pub(crate) struct PendingContainer {
+    pub(crate) container: Container,
+    // none when the engine could not be asked
+    pub(crate) name: Option<String>,
+}
+
+// a process that was gone at a refresh, as listed by the exited pane
+pub(crate) struct ExitedProcess {
+    pub(crate) pid: Pid,
+    pub(crate) name: String,
+    pub(crate) cmd: String,
+    // when the refresh noticed, it exited during the interval before
+    pub(crate) exited_at: Instant,
+    // the last signal it got from us, none when it exited on its own
+    pub(crate) signal: Option<Signal>,
+    // what it was started with, only remembered for the ones we signalled
+    launch: Option<Launch>,
+}
+
+// something done from the TUI, as listed by the history pane
+pub(crate) struct HistoryEntry {
+    // seconds since the epoch
+    pub(crate) at: u64,
+    // e.g. `SIGTERM`, `nice 5` or `docker stop`
+    pub(crate) action: String,
+    // the pid and name, or the container, unit or service
+    pub(crate) target: String,
+    pub(crate) result: Result<(), String>,
+}
+
+pub struct App {
+    pub(crate) config: Config,
+    pub(crate) manager: ProcessManager,
+    refresher: Refresher,
+    // advanced while a refresh is running
+    pub(crate) spinner: usize,
+    // only the selection is used, scrolling is handled by `scroll_offset`
+    pub(crate) state: TableState,
+    pub(crate) scroll_offset: usize,
+    // rows that fit in the table and where the table was drawn, updated on every draw
+    pub(crate) table_height: usize,
+    pub(crate) table_area: Rect,
+    // row index and time of the last click, to detect double clicks
+    pub(crate) last_click: Option<(usize, Instant)>,
+    pub(crate) processes: Vec<ProcessInfo>,
+    pub(crate) sort_column: SortColumn,
+    pub(crate) sort_order: SortOrder,
+    pub(crate) search_input: String,
+    pub(crate) search_history: SearchHistory,
+    pub(crate) search_filter: SearchFilter,
+    pub(crate) user_filter: Option<String>,
+    pub(crate) only_my_processes: bool,
+    pub(crate) state_filter: StateFilter,
+    // the user running process_killer
+    pub(crate) current_user: Option<Uid>,
+    // set while the search input does not parse, e.g. an invalid regex
+    pub(crate) search_error: Option<String>,
+    // when the search being typed is applied, pushed back by every keystroke
+    live_search_at: Option<Instant>,
+    pub(crate) input_mode: InputMode,
+    // a count or `g` typed in normal mode, waiting for the rest of the sequence
+    pub(crate) pending_keys: PendingKeys,
+    pub(crate) signal_menu_state: ListState,
+    pub(crate) filters_state: ListState,
+    // every table column in display order, with whether it is shown
+    pub(crate) columns: Vec<(Column, bool)>,
+    pub(crate) columns_state: ListState,
+    pub(crate) pending_signal: Option<PendingSignal>,
+    pub(crate) pending_container: Option<PendingContainer>,
+    // the systemd service whose main process is about to be signalled
+    pub(crate) pending_unit: Option<Unit>,
+    // the machine's name when started by --host, so one of a fleet isn't mistaken for another
+    pub(crate) remote_host: Option<String>,
+    // sudo or pkexec, when one is installed
+    pub(crate) elevation_tool: Option<&'static str>,
+    // a signal refused with EPERM, offered to be retried through `elevation_tool`
+    pub(crate) denied: Option<PendingSignal>,
+    // the retry the user agreed to, run by `run_app` since it needs the terminal
+    elevation: Option<PendingSignal>,
+    // set once gcore is chosen and confirmed, the run loop hands it the terminal, with the directory
+    // the core goes to
+    core_dump: Option<(Pid, PathBuf)>,
+    pub(crate) pending_core_dump: Option<(Pid, PathBuf)>,
+    kill_check: Option<KillCheck>,
+    // the zombies a signal was asked for, their parents are offered SIGCHLD or SIGKILL instead
+    pub(crate) zombies: Option<Vec<Pid>>,
+    // processes still alive after the grace period, offered to be killed with SIGKILL
+    pub(crate) survivors: Option<PendingSignal>,
+    // the cpu list being edited in the affinity popup
+    pub(crate) affinity_input: String,
+    // the pid typed after `:`
+    pub(crate) jump_input: String,
+    // the command typed after `!`
+    pub(crate) spawn_input: String,
+    // the signal name or number typed after `#`
+    pub(crate) signal_input: String,
+    // the delay typed after `w`
+    pub(crate) schedule_input: String,
+    // soonest first
+    pub(crate) scheduled_kills: Vec<ScheduledKill>,
+    pub(crate) scheduled_state: TableState,
+    // the windows services pane, reloaded on every tick while it is open
+    pub(crate) services: Vec<Service>,
+    pub(crate) services_state: TableState,
+    // the process shown by the open files or connections pane, reloaded on every tick while open
+    pub(crate) pane_pid: Option<Pid>,
+    pub(crate) open_files: Vec<OpenFile>,
+    pub(crate) open_files_state: TableState,
+    // with how much of it is used, for the limits we can tell
+    pub(crate) limits: Vec<(ResourceLimit, Option<u64>)>,
+    pub(crate) limits_state: TableState,
+    pub(crate) connections: Vec<Connection>,
+    pub(crate) connections_state: TableState,
+    pub(crate) threads: Vec<ThreadInfo>,
+    pub(crate) threads_state: TableState,
+    pub(crate) children: Vec<ChildInfo>,
+    pub(crate) children_state: TableState,
+    // the cpu usage of every process at the last refreshes, oldest first
+    pub(crate) cpu_history: HashMap<Pid, VecDeque<f32>>,
+    // the name and command line of every process at the last refresh, to tell which ones
+    // are new and what the exited ones were
+    known: HashMap<Pid, (String, String)>,
+    // the signals sent, to tell a kill that worked from a crash
+    signalled: HashMap<Pid, Signal>,
+    // what the signalled processes were started with, until they exit
+    launches: HashMap<Pid, Launch>,
+    // how long processes have been over the limits of the config rules and alerts
+    rule_tracker: LimitTracker,
+    alert_tracker: LimitTracker,
+    // newest first
+    pub(crate) alerts: Vec<FiredAlert>,
+    pub(crate) alerts_state: TableState,
+    // newest first
+    pub(crate) exited: VecDeque<ExitedProcess>,
+    pub(crate) exited_state: TableState,
+    // everything done this session, oldest first
+    pub(crate) history: Vec<HistoryEntry>,
+    pub(crate) history_state: TableState,
+    // the processes that appeared over the last `SPAWN_HIGHLIGHT`
+    spawned: HashMap<Pid, Instant>,
+    pub(crate) refresh_interval: Duration,
+    pub(crate) tree_view: bool,
+    pub(crate) collapsed: HashSet<Pid>,
+    // one row per process name, with the names in `expanded` listing their processes below
+    pub(crate) grouped: bool,
+    pub(crate) expanded: HashSet<String>,
+    // tracked by pid so the marks survive refreshes
+    pub(crate) marked: HashSet<Pid>,
+    pub(crate) visual: Option<Visual>,
+    // every tab, none at the shown one
+    pub(crate) tabs: Vec<Option<View>>,
+    pub(crate) tab: usize,
+    pub(crate) show_details: bool,
+    // show the full command line instead of the short name
+    pub(crate) show_cmd: bool,
+    // characters cut off the start of the name or command line column, to read long ones
+    pub(crate) name_scroll: usize,
+    // a meter per cpu core under the system summary
+    pub(crate) show_cpu_meters: bool,
+    pub(crate) memory_kind: MemoryKind,
+    pub(crate) help_scroll: u16,
+    // newest last, dropped after `TOAST_DURATION`
+    pub(crate) toasts: Vec<Toast>,
+    pub(crate) should_quit: bool,
+}
+
+impl App {
+    pub fn new(config: Config) -> Self {
+        let search_history = SearchHistory::load(config.search_history.clone());
+        let columns = column_layout(config.columns.as_deref());
+        let manager = ProcessManager::new();
+        let remote_host = config
+            .show_host
+            .then(|| manager.system().host_name())
+            .flatten();
+        let mut app = Self {
+            config,
+            manager,
+            refresher: Refresher::spawn(),
+            spinner: 0,
+            processes: vec![],
+            state: TableState::default(),
+            scroll_offset: 0,
+            table_height: 0,
+            table_area: Rect::default(),
+            last_click: None,
+            sort_column: SortColumn::Name,
+            sort_order: SortOrder::None,
+            search_input: String::new(),
+            search_history,
+            search_filter: SearchFilter::All,
+            user_filter: None,
+            only_my_processes: false,
+            state_filter: StateFilter::All,
+            current_user: None,
+            search_error: None,
+            live_search_at: None,
+            input_mode: InputMode::Normal,
+            pending_keys: PendingKeys::default(),
+            signal_menu_state: ListState::default(),
+            filters_state: ListState::default(),
+            columns,
+            columns_state: ListState::default(),
+            pending_signal: None,
+            pending_container: None,
+            zombies: None,
+            pending_unit: None,
+            remote_host,
+            elevation_tool: elevation_tool(),
+            denied: None,
+            elevation: None,
+            core_dump: None,
+            pending_core_dump: None,
+            kill_check: None,
+            survivors: None,
+            affinity_input: String::new(),
+            jump_input: String::new(),
+            spawn_input: String::new(),
+            signal_input: String::new(),
+            schedule_input: String::new(),
+            scheduled_kills: vec![],
+            scheduled_state: TableState::default(),
+            services: vec![],
+            services_state: TableState::default(),
+            pane_pid: None,
+            open_files: vec![],
+            open_files_state: TableState::default(),
+            limits: vec![],
+            limits_state: TableState::default(),
+            connections: vec![],
+            connections_state: TableState::default(),
+            threads: vec![],
+            threads_state: TableState::default(),
+            children: vec![],
+            children_state: TableState::default(),
+            cpu_history: HashMap::new(),
+            known: HashMap::new(),
+            signalled: HashMap::new(),
+            launches: HashMap::new(),
+            rule_tracker: LimitTracker::default(),
+            alert_tracker: LimitTracker::default(),
+            alerts: vec![],
+            alerts_state: TableState::default(),
+            exited: VecDeque::new(),
+            exited_state: TableState::default(),
+            history: vec![],
+            history_state: TableState::default(),
+            spawned: HashMap::new(),
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            tree_view: false,
+            collapsed: HashSet::new(),
+            grouped: false,
+            expanded: HashSet::new(),
+            marked: HashSet::new(),
+            visual: None,
+            tabs: vec![None],
+            tab: 0,
+            show_details: false,
+            show_cmd: false,
+            name_scroll: 0,
+            show_cpu_meters: false,
+            memory_kind: MemoryKind::Resident,
+            help_scroll: 0,
+            toasts: vec![],
+            should_quit: false,
+        };
+        app.current_user = app
+            .manager
+            .own_process()
+            .and_then(|process| process.user_id().cloned());
+        // what runs at startup isn't news
+        app.known = app.known_processes();
+        app.attach_collector();
+        app.apply_startup();
+
+        app
+    }
+
+    // the sort, filter and views the config and the command line ask to open with
+    fn apply_startup(&mut self) {
+        let startup = &self.config.startup;
+        if let Some((column, order)) = startup.sort {
+            self.sort_column = column;
+            self.sort_order = order;
+        }
+        self.tree_view = startup.tree_view;
+        self.show_details = startup.show_details;
+        self.show_cmd = startup.show_command_line;
+
+        match startup.filter.clone() {
+            Some(filter) => {
+                self.search_input = filter;
+                if !self.apply_search_input() {
+                    let reason = self.search_error.clone().unwrap_or_default();
+                    self.report(Err(AppError::InvalidFilter(reason)));
+                    self.load_processes();
+                }
+            }
+            None => self.load_processes(),
+        }
+    }
+
+    pub fn switch_sort(&mut self, column: SortColumn) {
+        // switching to another column starts again from ASC
+        if self.sort_column != column {
+            self.sort_column = column;
+            self.sort_order = SortOrder::None;
+        }
+
+        // if sort option is NONE then set to ASC otherwise toggle ASC and DESC
+        self.sort_order = match self.sort_order {
+            SortOrder::Asc => SortOrder::Desc,
+            SortOrder::Desc => SortOrder::Asc,
+            SortOrder::None => SortOrder::Asc,
+        };
+
+        self.load_processes();
+    }
+
+    // moves the sort to the previous/next column, keeping the direction
+    pub fn cycle_sort_column(&mut self, step: isize) {
+        self.sort_column = self.sort_column.cycle(step, self.manager.has_gpu());
+        if let SortOrder::None = self.sort_order {
+            self.sort_order = SortOrder::Asc;
+        }
+        self.load_processes();
+    }
+
+    fn apply_sort(&mut self) {
+        self.sort_rows(0);
+    }
+
+    // sorts the rows from `start` on, the ones before are left in place
+    fn sort_rows(&mut self, start: usize) {
+        let rows = &mut self.processes[start..];
+        let column = self.sort_column;
+        let secondary = self.config.secondary_sort;
+        let memory = self.memory_kind;
+        // the memory column sorts by whichever size it shows
+        let by = move |column: SortColumn, a: &ProcessInfo, b: &ProcessInfo| {
+            if column == SortColumn::Memory {
+                memory.of(a).cmp(&memory.of(b))
+            } else {
+                column.compare(a, b)
+            }
+        };
+        // the secondary column goes the same way, then name and pid keep ties (all the idle
+        // processes at 0% cpu) from swapping places on every refresh
+        let compare = |a: &ProcessInfo, b: &ProcessInfo, reverse: bool| {
+            let order = by(column, a, b)
+                .then_with(|| secondary.map_or(Ordering::Equal, |second| by(second, a, b)));
+            let order = if reverse { order.reverse() } else { order };
+            order
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.pid.cmp(&b.pid))
+        };
+        match self.sort_order {
+            SortOrder::Asc => rows.sort_by(|a, b| compare(a, b, false)),
+            SortOrder::Desc => rows.sort_by(|a, b| compare(a, b, true)),
+            // nothing chosen yet, rank fuzzy matches by score, otherwise keep a stable (name) order
+            SortOrder::None if self.search_filter.is_ranked() => rows.sort_by(|a, b| {
+                b.score
+                    .cmp(&a.score)
+                    .then_with(|| a.name.cmp(&b.name))
+                    .then_with(|| a.pid.cmp(&b.pid))
+            }),
+            SortOrder::None => {
+                rows.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.pid.cmp(&b.pid)))
+            }
+        };
+        // stable, so the pinned rows keep the sort order among themselves
+        rows.sort_by_key(|p| !p.pinned);
+    }
+
+    // the header of a column, with an arrow when the table is sorted by it
+    pub(crate) fn column_header(&self, column: SortColumn) -> String {
+        let title = if column == SortColumn::Memory {
+            self.memory_kind.title()
+        } else {
+            column.title()
+        };
+        match (self.sort_column == column, &self.sort_order) {
+            (true, SortOrder::Asc) => format!("{} ▲", title),
+            (true, SortOrder::Desc) => format!("{} ▼", title),
+            _ => title.to_string(),
+        }
+    }
+
+    pub fn cycle_memory_kind(&mut self) {
+        self.memory_kind = self.memory_kind.next();
+        self.load_processes();
+    }
+
+    pub fn next(&mut self) {
+        if self.processes.is_empty() {
+            self.state.select(None);
+            return;
+        }
+
+        let i = match self.state.selected() {
+            Some(i) if i + 1 < self.processes.len() => i + 1,
+            _ => 0,
+        };
+
+        self.state.select(Some(i))
+    }
+
+    pub fn prev(&mut self) {
+        if self.processes.is_empty() {
+            self.state.select(None);
+            return;
+        }
+
+        let i = match self.state.selected() {
+            Some(0) | None => self.processes.len() - 1,
+            Some(i) => i - 1,
+        };
+
+        self.state.select(Some(i))
+    }
+
+    // moves the selection by `delta` rows without wrapping around
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.processes.is_empty() {
+            return;
+        }
+
+        let last = self.processes.len() as isize - 1;
+        let i = self.state.selected().unwrap_or(0) as isize + delta;
+        self.state.select(Some(i.clamp(0, last) as usize));
+    }
+
+    pub fn page_down(&mut self) {
+        self.move_selection(self.table_height.max(1) as isize);
+    }
+
+    pub fn page_up(&mut self) {
+        self.move_selection(-(self.table_height.max(1) as isize));
+    }
+
+    // `halves` half pages down, or up when negative, like Ctrl-d and Ctrl-u in vim
+    pub fn half_page(&mut self, halves: isize) {
+        let half = (self.table_height / 2).max(1) as isize;
+        self.move_selection(half * halves);
+    }
+
+    // the 1-based row `n`, the last one when there are fewer
+    pub fn go_to_row(&mut self, n: usize) {
+        if !self.processes.is_empty() {
+            let i = n.saturating_sub(1).min(self.processes.len() - 1);
+            self.state.select(Some(i));
+        }
+    }
+
+    pub fn first(&mut self) {
+        if !self.processes.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
+    pub fn last(&mut self) {
+        if !self.processes.is_empty() {
+            self.state.select(Some(self.processes.len() - 1));
+        }
+    }
+
+    pub fn select_parent(&mut self) -> Result<(), AppError> {
+        let process = self
+            .state
+            .selected()
+            .and_then(|i| self.processes.get(i))
+            .ok_or(AppError::NoSelection)?;
+        let parent = process.parent.ok_or(AppError::NoParent(process.pid))?;
+        let i = self
+            .processes
+            .iter()
+            .position(|p| p.pid == parent)
+            .ok_or(AppError::NotListed(parent))?;
+        self.state.select(Some(i));
+        Ok(())
+    }
+
+    pub fn open_jump_to_pid(&mut self) {
+        self.jump_input.clear();
+        self.input_mode = InputMode::JumpToPid;
+    }
+
+    pub fn apply_jump_to_pid(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let result = self.jump_to_pid();
+        self.report(result);
+    }
+
+    // the table scrolls to keep the selection in view
+    fn jump_to_pid(&mut self) -> Result<(), AppError> {
+        let input = self.jump_input.trim();
+        let pid = input
+            .parse()
+            .map(Pid::from_u32)
+            .map_err(|_| AppError::InvalidPid(input.to_string()))?;
+        let i = match self.processes.iter().position(|p| p.pid == pid) {
+            Some(i) => i,
+            None if self.manager.process(pid).is_some() => return Err(AppError::NotListed(pid)),
+            None => return Err(AppError::NoSuchProcess(pid)),
+        };
+        self.state.select(Some(i));
+        Ok(())
+    }
+
+    // the child with the lowest pid, usually the one started first
+    pub fn select_child(&mut self) -> Result<(), AppError> {
+        let pid = self
+            .state
+            .selected()
+            .and_then(|i| self.processes.get(i))
+            .ok_or(AppError::NoSelection)?
+            .pid;
+        let i = self
+            .processes
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.parent == Some(pid))
+            .min_by_key(|(_, p)| p.pid)
+            .map(|(i, _)| i)
+            .ok_or(AppError::NoChildren(pid))?;
+        self.state.select(Some(i));
+        Ok(())
+    }
+
+    // moves the viewport by `delta` rows, dragging the selection along when it falls outside
+    pub fn scroll(&mut self, delta: isize) {
+        let height = self.table_height.max(1);
+        let max_offset = self.processes.len().saturating_sub(height) as isize;
+        self.scroll_offset = (self.scroll_offset as isize + delta).clamp(0, max_offset) as usize;
+
+        if let Some(i) = self.state.selected() {
+            let i = i.clamp(self.scroll_offset, self.scroll_offset + height - 1);
+            self.state
+                .select(Some(i.min(self.processes.len().saturating_sub(1))));
+        }
+    }
+
+    // selects the clicked row, a second click on the same row opens the details pane
+    pub fn click(&mut self, column: u16, row: u16) {
+        let area = self.table_area;
+        // skip the top border and the header
+        let first_row = area.y + 2;
+        if column <= area.x || column >= area.x + area.width.saturating_sub(1) || row < first_row {
+            return;
+        }
+
+        let i = self.scroll_offset + (row - first_row) as usize;
+        if i >= self.processes.len() || i >= self.scroll_offset + self.table_height {
+            return;
+        }
+        self.state.select(Some(i));
+
+        let now = Instant::now();
+        if let Some((last, at)) = self.last_click {
+            if last == i && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL {
+                self.show_details = true;
+                self.last_click = None;
+                return;
+            }
+        }
+        self.last_click = Some((i, now));
+    }
+
+    // keeps the selected row inside the visible window of `table_height` rows
+    pub(crate) fn scroll_to_selection(&mut self) {
+        let height = self.table_height.max(1);
+        if let Some(i) = self.state.selected() {
+            if i < self.scroll_offset {
+                self.scroll_offset = i;
+            } else if i >= self.scroll_offset + height {
+                self.scroll_offset = i + 1 - height;
+            }
+        }
+        self.scroll_offset = self
+            .scroll_offset
+            .min(self.processes.len().saturating_sub(height));
+    }
+
+    pub fn kill(&mut self) -> Result<(), AppError> {
+        let pids = self.selected_pids();
+        if pids.is_empty() {
+            return Err(AppError::NoSelection);
+        }
+        self.request_signal(Signal::Kill, pids)
+    }
+
+    // moves the nice value of the targets by `delta`, lowering it usually needs root
+    pub fn renice(&mut self, delta: i32) -> Result<(), AppError> {
+        let mut failed = vec![];
+        for pid in self.targets() {
+            let Some(nice) = nice_value(pid) else {
+                continue;
+            };
+            let value = (nice + delta).clamp(-20, 19);
+            let target = self.describe(pid);
+            let result = set_nice_value(pid, value);
+            self.log(
+                format!("nice {}", value),
+                target,
+                result.as_ref().map(|_| ()).map_err(|err| err.to_string()),
+            );
+            if let Err(err) = result {
+                failed.push((pid, err));
+            }
+        }
+        self.load_processes();
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Renice(failed))
+        }
+    }
+
+    pub fn open_affinity_input(&mut self) -> Result<(), AppError> {
+        let pid = self.selected_process().ok_or(AppError::NoSelection)?.pid();
+        let cpus = cpu_affinity(pid).map_err(|err| AppError::Affinity {
+            pid,
+            reason: err.to_string(),
+        })?;
+
+        self.affinity_input = format_cpu_list(&cpus);
+        self.input_mode = InputMode::Affinity;
+        Ok(())
+    }
+
+    pub fn apply_affinity(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let result = self.set_affinity();
+        self.report(result);
+    }
+
+    fn set_affinity(&mut self) -> Result<(), AppError> {
+        let pid = self.selected_process().ok_or(AppError::NoSelection)?.pid();
+        let result = parse_cpu_list(&self.affinity_input)
+            .and_then(|cpus| set_cpu_affinity(pid, &cpus).map_err(|err| err.to_string()));
+        let target = self.describe(pid);
+        self.log(
+            format!("affinity {}", self.affinity_input),
+            target,
+            result.clone(),
+        );
+        result.map_err(|reason| AppError::Affinity { pid, reason })
+    }
+
+    // `1234 node`, for the history
+    fn describe(&self, pid: Pid) -> String {
+        match self.manager.process(pid) {
+            Some(process) => format!("{} {}", pid, process.name()),
+            None => pid.to_string(),
+        }
+    }
+
+    fn log(&mut self, action: String, target: String, result: Result<(), String>) {
+        let at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        self.history.push(HistoryEntry {
+            at,
+            action,
+            target,
+            result,
+        });
+    }
+
+    // in dry run mode logs `action` instead of doing it, true when it is to be skipped
+    fn skip_dry_run(&mut self, action: &str, target: &str) -> bool {
+        if !self.config.dry_run {
+            return false;
+        }
+        self.log(format!("{} (dry run)", action), target.to_string(), Ok(()));
+        self.toast(format!("Dry run, {} {} not done", action, target));
+        true
+    }
+
+    pub fn toggle_dry_run(&mut self) {
+        self.config.dry_run = !self.config.dry_run;
+        self.toast(if self.config.dry_run {
+            "Dry run on, signals are only logged".to_string()
+        } else {
+            "Dry run off".to_string()
+        });
+    }
+
+    // appends a signal to the audit log, when one is configured
+    fn audit(&mut self, pid: Pid, signal: Signal, result: &Result<(), String>) {
+        let (Some(audit_log), Some(process)) = (&self.config.audit_log, self.manager.process(pid))
+        else {
+            return;
+        };
+        if let Err(err) = audit_log.record(process, signal, result) {
+            self.report(Err(AppError::AuditLog(err.to_string())));
+        }
+    }
+
+    pub fn open_history(&mut self) -> Result<(), AppError> {
+        // the newest entry is listed first
+        self.history_state
+            .select((!self.history.is_empty()).then_some(0));
+        self.input_mode = InputMode::History;
+        Ok(())
+    }
+
+    pub fn next_history(&mut self) {
+        cycle_selection(&mut self.history_state, self.history.len(), 1);
+    }
+
+    pub fn prev_history(&mut self) {
+        cycle_selection(&mut self.history_state, self.history.len(), -1);
+    }
+
+    // shows a failed action as a toast
+    pub(crate) fn report(&mut self, result: Result<(), AppError>) {
+        if let Err(err) = result {
+            self.toast(err.to_string());
+        }
+    }
+
+    pub(crate) fn toast(&mut self, message: String) {
+        self.toasts.push(Toast {
+            message,
+            shown_at: Instant::now(),
+        });
+    }
+
+    // writes the rows as shown, filtered and sorted, to a csv file in the export directory
+    pub fn export(&mut self) -> Result<(), AppError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        // `2026-01-31 12:00:00 UTC` as `2026-01-31_12-00-00`
+        let stamp = format_timestamp(now)
+            .trim_end_matches(" UTC")
+            .replace(' ', "_")
+            .replace(':', "-");
+        let name = format!("processes-{}.csv", stamp);
+        let Some(dir) = self.config.export_dir.clone() else {
+            return Err(AppError::Export {
+                path: PathBuf::from(name),
+                reason: "no data directory, set export_dir in the config".to_string(),
+            });
+        };
+        let path = dir.join(name);
+        fs::create_dir_all(&dir)
+            .and_then(|_| export_csv(&path, &self.processes, self.memory_kind))
+            .map_err(|err| AppError::Export {
+                path: path.clone(),
+                reason: err.to_string(),
+            })?;
+        // a relative export_dir is shown from the root, the toast is all there is to find it
+        let path = fs::canonicalize(&path).unwrap_or(path);
+        self.toast(format!(
+            "Exported {} processes to {}",
+            self.processes.len(),
+            path.display()
+        ));
+        Ok(())
+    }
+
+    // drops the expired toasts, returns how long until the next one expires
+    pub(crate) fn expire_toasts(&mut self) -> Option<Duration> {
+        self.toasts
+            .retain(|toast| toast.shown_at.elapsed() < TOAST_DURATION);
+        self.toasts
+            .first()
+            .map(|toast| TOAST_DURATION.saturating_sub(toast.shown_at.elapsed()))
+    }
+
+    fn is_pinned(&self, name: &str) -> bool {
+        self.config
+            .pinned
+            .iter()
+            .any(|pattern| name.contains(pattern.as_str()))
+    }
+
+    // unpins every pattern the selected name matches, or pins the name itself
+    pub fn toggle_pin(&mut self) {
+        let Some(process) = self.state.selected().and_then(|i| self.processes.get(i)) else {
+            return;
+        };
+
+        let name = process.name.clone();
+        if self.is_pinned(&name) {
+            self.config
+                .pinned
+                .retain(|pattern| !name.contains(pattern.as_str()));
+        } else {
+            self.config.pinned.push(name);
+        }
+        self.load_processes();
+    }
+
+    // a name's row marks or unmarks all its processes
+    pub fn toggle_mark(&mut self) {
+        let pids = self.selected_pids();
+        if pids.iter().all(|pid| self.marked.contains(pid)) {
+            for pid in &pids {
+                self.marked.remove(pid);
+            }
+        } else {
+            self.marked.extend(pids);
+        }
+    }
+
+    // marks every process in the table, e.g. all the runners a search found
+    pub fn mark_all(&mut self) {
+        let pids = self.shown_pids();
+        let count = pids.len();
+        self.marked.extend(pids);
+        self.toast(format!("Marked {} process(es)", count));
+    }
+
+    // marks the unmarked processes in the table and unmarks the others, the marks of the
+    // processes filtered out stay as they are
+    pub fn invert_marks(&mut self) {
+        for pid in self.shown_pids() {
+            if !self.marked.remove(&pid) {
+                self.marked.insert(pid);
+            }
+        }
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.visual = None;
+        self.marked.clear();
+    }
+
+    // Ctrl-t, a tab with the startup sort and no search
+    pub fn new_tab(&mut self) {
+        if self.tabs.len() == MAX_TABS {
+            self.toast(format!("At most {} tabs can be open", MAX_TABS));
+            return;
+        }
+        let (sort_column, sort_order) = self
+            .config
+            .startup
+            .sort
+            .unwrap_or((SortColumn::Name, SortOrder::None));
+        self.tabs.push(Some(View {
+            state: TableState::default(),
+            scroll_offset: 0,
+            sort_column,
+            sort_order,
+            search_input: String::new(),
+            search_filter: SearchFilter::All,
+            search_error: None,
+            user_filter: None,
+            only_my_processes: false,
+            state_filter: StateFilter::All,
+            tree_view: self.config.startup.tree_view,
+            collapsed: HashSet::new(),
+            grouped: false,
+            expanded: HashSet::new(),
+        }));
+        self.switch_tab(self.tabs.len() - 1);
+    }
+
+    // Ctrl-w, shows the tab after it, or the one before when it was the last
+    pub fn close_tab(&mut self) {
+        if self.tabs.len() == 1 {
+            self.toast("The last tab can't be closed, q quits".to_string());
+            return;
+        }
+        self.tabs.remove(self.tab);
+        let index = self.tab.min(self.tabs.len() - 1);
+        if let Some(mut view) = self.tabs[index].take() {
+            self.swap_view(&mut view);
+        }
+        self.tab = index;
+        self.visual = None;
+        // the listening pids of a port search went stale while it was hidden
+        self.search_filter.refresh();
+        self.load_processes();
+    }
+
+    // Alt+<n>, the marks are shared by the tabs
+    pub fn switch_tab(&mut self, index: usize) {
+        if index == self.tab {
+            return;
+        }
+        let Some(mut view) = self.tabs.get_mut(index).and_then(Option::take) else {
+            self.toast(format!("There is no tab {}", index + 1));
+            return;
+        };
+        self.swap_view(&mut view);
+        self.tabs[self.tab] = Some(view);
+        self.tab = index;
+        self.visual = None;
+        self.search_filter.refresh();
+        self.load_processes();
+    }
+
+    fn swap_view(&mut self, view: &mut View) {
+        mem::swap(&mut self.state, &mut view.state);
+        mem::swap(&mut self.scroll_offset, &mut view.scroll_offset);
+        mem::swap(&mut self.sort_column, &mut view.sort_column);
+        mem::swap(&mut self.sort_order, &mut view.sort_order);
+        mem::swap(&mut self.search_input, &mut view.search_input);
+        mem::swap(&mut self.search_filter, &mut view.search_filter);
+        mem::swap(&mut self.search_error, &mut view.search_error);
+        mem::swap(&mut self.user_filter, &mut view.user_filter);
+        mem::swap(&mut self.only_my_processes, &mut view.only_my_processes);
+        mem::swap(&mut self.state_filter, &mut view.state_filter);
+        mem::swap(&mut self.tree_view, &mut view.tree_view);
+        mem::swap(&mut self.collapsed, &mut view.collapsed);
+        mem::swap(&mut self.grouped, &mut view.grouped);
+        mem::swap(&mut self.expanded, &mut view.expanded);
+    }
+
+    // the search of every tab, for the status bar
+    pub(crate) fn tab_labels(&self) -> Vec<String> {
+        self.tabs
+            .iter()
+            .map(|view| {
+                let search = view
+                    .as_ref()
+                    .map_or(&self.search_input, |view| &view.search_input);
+                if search.is_empty() {
+                    "all".to_string()
+                } else {
+                    search.clone()
+                }
+            })
+            .collect()
+    }
+
+    // Shift+Up/Down, marks the selected row and the one moved to
+    pub fn mark_and_move(&mut self, delta: isize) {
+        self.marked.extend(self.selected_pids());
+        self.move_selection(delta);
+        self.marked.extend(self.selected_pids());
+    }
+
+    // `V` starts marking a range from the selected row, and again keeps the marks and stops
+    pub fn toggle_visual(&mut self) {
+        if self.visual.take().is_some() {
+            return;
+        }
+        let Some(process) = self.state.selected().and_then(|i| self.processes.get(i)) else {
+            return;
+        };
+        self.visual = Some(Visual {
+            anchor: process.pid,
+            marked: self.marked.clone(),
+        });
+        self.update_visual();
+    }
+
+    // after the selection moved, marks the rows between the anchor and it
+    pub(crate) fn update_visual(&mut self) {
+        let Some(visual) = &self.visual else {
+            return;
+        };
+        // filtered out or gone, the marks stay
+        let anchor = self.processes.iter().position(|p| p.pid == visual.anchor);
+        let (Some(anchor), Some(selected)) = (anchor, self.state.selected()) else {
+            self.visual = None;
+            return;
+        };
+
+        let mut marked = visual.marked.clone();
+        for process in &self.processes[anchor.min(selected)..=anchor.max(selected)] {
+            marked.extend(row_pids(process));
+        }
+        self.marked = marked;
+    }
+
+    // every process in the table, with all the processes of a name in the grouped view
+    fn shown_pids(&self) -> Vec<Pid> {
+        self.processes.iter().flat_map(row_pids).collect()
+    }
+
+    // the selected process, or every process of the selected name in the grouped view
+    fn selected_pids(&self) -> Vec<Pid> {
+        self.state
+            .selected()
+            .and_then(|i| self.processes.get(i))
+            .map_or_else(Vec::new, row_pids)
+    }
+
+    // the processes an action applies to: every marked process, or just the selected one
+    pub(crate) fn targets(&self) -> Vec<Pid> {
+        if !self.marked.is_empty() {
+            return self.marked.iter().copied().collect();
+        }
+        self.selected_pids()
+    }
+
+    pub fn open_schedule_kill(&mut self) -> Result<(), AppError> {
+        let process = self.selected_process().ok_or(AppError::NoSelection)?;
+        if self.config.is_protected(process) {
+            let target = format!("{} {}", process.pid(), process.name());
+            return Err(AppError::Protected(vec![target]));
+        }
+        self.schedule_input.clear();
+        self.input_mode = InputMode::ScheduleKill;
+        Ok(())
+    }
+
+    pub fn apply_schedule_kill(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let result = self.schedule_kill();
+        self.report(result);
+    }
+
+    fn schedule_kill(&mut self) -> Result<(), AppError> {
+        let input = self.schedule_input.trim();
+        let invalid = || AppError::InvalidDelay(input.to_string());
+        let delay = parse_delay(input).ok_or_else(invalid)?;
+        let at = Instant::now().checked_add(delay).ok_or_else(invalid)?;
+        let process = self.selected_process().ok_or(AppError::NoSelection)?;
+        let kill = ScheduledKill {
+            pid: process.pid(),
+            name: process.name().to_string(),
+            start_time: process.start_time(),
+            at,
+        };
+        self.toast(format!(
+            "{} {} will be killed in {}",
+            kill.pid,
+            kill.name,
+            format_duration(delay.as_secs())
+        ));
+        let i = self
+            .scheduled_kills
+            .partition_point(|other| other.at <= kill.at);
+        self.scheduled_kills.insert(i, kill);
+        Ok(())
+    }
+
+    // the kills that are due go off as polite kills, without asking again
+    fn run_scheduled_kills(&mut self) {
+        let now = Instant::now();
+        let due = self.scheduled_kills.partition_point(|kill| kill.at <= now);
+        for kill in self.scheduled_kills.drain(..due).collect::<Vec<_>>() {
+            self.manager.refresh_pids(&[kill.pid]);
+            let running = self
+                .manager
+                .process(kill.pid)
+                .is_some_and(|process| process.start_time() == kill.start_time);
+            if !running {
+                self.toast(format!(
+                    "Scheduled kill of {} {} skipped, it has already exited",
+                    kill.pid, kill.name
+                ));
+                continue;
+            }
+            self.toast(format!("Scheduled kill of {} {}", kill.pid, kill.name));
+            let result = self.send(Signal::Term, &[kill.pid], true);
+            self.report(result);
+        }
+    }
+
+    pub fn open_scheduled_kills(&mut self) -> Result<(), AppError> {
+        if self.scheduled_kills.is_empty() {
+            return Err(AppError::NoScheduledKills);
+        }
+        self.scheduled_state.select(Some(0));
+        self.input_mode = InputMode::ScheduledKills;
+        Ok(())
+    }
+
+    pub fn next_scheduled_kill(&mut self) {
+        cycle_selection(&mut self.scheduled_state, self.scheduled_kills.len(), 1);
+    }
+
+    pub fn prev_scheduled_kill(&mut self) {
+        cycle_selection(&mut self.scheduled_state, self.scheduled_kills.len(), -1);
+    }
+
+    pub fn cancel_scheduled_kill(&mut self) {
+        let Some(i) = self.scheduled_state.selected() else {
+            return;
+        };
+        if i >= self.scheduled_kills.len() {
+            return;
+        }
+        let kill = self.scheduled_kills.remove(i);
+        self.toast(format!("Cancelled the kill of {} {}", kill.pid, kill.name));
+        if self.scheduled_kills.is_empty() {
+            self.exit_input_mode();
+        } else {
+            self.scheduled_state
+                .select(Some(i.min(self.scheduled_kills.len() - 1)));
+        }
+    }
+
+    // SIGTERM, then SIGKILL for whatever still runs after the polite kill timeout
+    pub fn polite_kill(&mut self) -> Result<(), AppError> {
+        self.request(Signal::Term, self.targets(), true)
+    }
+
+    // sends right away or asks for confirmation first, depending on the config
+    pub fn request_signal(&mut self, signal: Signal, pids: Vec<Pid>) -> Result<(), AppError> {
+        self.request(signal, pids, false)
+    }
+
+    fn request(&mut self, signal: Signal, mut pids: Vec<Pid>, force: bool) -> Result<(), AppError> {
+        let requested = pids.first().copied();
+        let mut protected = vec![];
+        let mut zombies = vec![];
+        pids.retain(|pid| match self.manager.process(*pid) {
+            Some(process) if self.config.is_protected(process) => {
+                protected.push(format!("{} {}", pid, process.name()));
+                false
+            }
+            // dead already, no signal does anything to it
+            Some(process) if process.status() == ProcessStatus::Zombie => {
+                zombies.push(*pid);
+                false
+            }
+            Some(_) => true,
+            None => false,
+        });
+
+        if !protected.is_empty() && pids.is_empty() {
+            return Err(AppError::Protected(protected));
+        }
+        if !zombies.is_empty() && pids.is_empty() {
+            self.zombies = Some(zombies);
+            self.input_mode = InputMode::Zombies;
+            return Ok(());
+        }
+        if pids.is_empty() {
+            return Err(requested.map_or(AppError::NoSelection, AppError::ProcessGone));
+        }
+
+        // a suspended process is easily resumed, no need to ask
+        let reversible = matches!(signal, Signal::Stop | Signal::Continue);
+        if self.config.confirm_kill && !reversible {
+            self.pending_container = self.main_container(&pids);
+            if self.pending_container.is_none() {
+                self.pending_unit = main_unit(&pids);
+            }
+            self.pending_signal = Some(PendingSignal {
+                signal,
+                pids,
+                force,
+            });
+            self.input_mode = InputMode::Confirm;
+        } else {
+            self.send(signal, &pids, force)?;
+        }
+
+        if !protected.is_empty() {
+            Err(AppError::Protected(protected))
+        } else if !zombies.is_empty() {
+            Err(AppError::ZombiesSkipped(zombies.len()))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn confirm_pending_signal(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.pending_container = None;
+        self.pending_unit = None;
+        if let Some(pending) = self.pending_signal.take() {
+            let result = self.send(pending.signal, &pending.pids, pending.force);
+            self.report(result);
+        }
+    }
+
+    pub fn cancel_pending_signal(&mut self) {
+        self.pending_signal = None;
+        self.pending_container = None;
+        self.pending_unit = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    // the container `pids` is the main process of, its parent is the runtime's shim outside of it
+    fn main_container(&self, pids: &[Pid]) -> Option<PendingContainer> {
+        let [pid] = pids else {
+            return None;
+        };
+        let container = process_cgroup(*pid).ok()?.container()?;
+        let parent = self.manager.process(*pid)?.parent();
+        let parent_container = parent
+            .and_then(|parent| process_cgroup(parent).ok())
+            .and_then(|cgroup| cgroup.container());
+        if parent_container.is_some_and(|parent| parent.id == container.id) {
+            return None;
+        }
+
+        Some(PendingContainer {
+            name: container_name(&container).ok(),
+            container,
+        })
+    }
+
+    // stops or kills the container instead of sending the pending signal
+    pub fn control_pending_container(&mut self, control: ContainerControl) {
+        let Some(pending) = self.pending_container.take() else {
+            return;
+        };
+        self.pending_signal = None;
+        self.input_mode = InputMode::Normal;
+
+        let name = pending
+            .name
+            .unwrap_or_else(|| pending.container.short_id().to_string());
+        let action = format!("{} {}", pending.container.runtime, control.label());
+        if self.skip_dry_run(&action, &name) {
+            return;
+        }
+        let result = control_container(&pending.container, control);
+        self.log(
+            action,
+            name.clone(),
+            result.as_ref().map_err(|err| err.to_string()).copied(),
+        );
+        let result = result.map_err(|err| AppError::ContainerControl {
+            name,
+            control,
+            reason: err.to_string(),
+        });
+        self.refetch_process();
+        self.report(result);
+    }
+
+    // stops or restarts the unit instead of sending the pending signal
+    pub fn control_pending_unit(&mut self, control: UnitControl) {
+        let Some(unit) = self.pending_unit.take() else {
+            return;
+        };
+        self.pending_signal = None;
+        self.input_mode = InputMode::Normal;
+
+        let action = format!("systemctl {}", control.label());
+        if self.skip_dry_run(&action, &unit.name) {
+            return;
+        }
+        let result = control_unit(&unit, control);
+        self.log(
+            action,
+            unit.name.clone(),
+            result.as_ref().map_err(|err| err.to_string()).copied(),
+        );
+        let result = result.map_err(|err| AppError::UnitControl {
+            name: unit.name,
+            control,
+            reason: err.to_string(),
+        });
+        self.refetch_process();
+        self.report(result);
+    }
+
+    // `S` in the confirmation stops whatever runs the process, a container or a unit
+    pub fn stop_pending_owner(&mut self) {
+        if self.pending_container.is_some() {
+            self.control_pending_container(ContainerControl::Stop);
+        } else {
+            self.control_pending_unit(UnitControl::Stop);
+        }
+    }
+
+    fn send_signal(&mut self, signal: Signal, pids: &[Pid]) -> Result<(), AppError> {
+        self.send(signal, pids, false)
+    }
+
+    // `force` follows up with SIGKILL on its own instead of offering it
+    fn send(&mut self, signal: Signal, pids: &[Pid], force: bool) -> Result<(), AppError> {
+        if self.config.dry_run {
+            for pid in pids {
+                let target = self.describe(*pid);
+                self.log(format!("{} (dry run)", signal_name(signal)), target, Ok(()));
+            }
+            self.marked.retain(|pid| !pids.contains(pid));
+            self.toast(format!(
+                "Dry run, {} not sent to {} process(es)",
+                signal_name(signal),
+                pids.len()
+            ));
+            return Ok(());
+        }
+
+        let mut sent = vec![];
+        let mut failed = vec![];
+        let mut denied = vec![];
+        for pid in pids {
+            let target = self.describe(*pid);
+            let result = self.manager.signal(*pid, signal);
+            let logged = result.as_ref().map_err(|err| err.to_string()).copied();
+            self.audit(*pid, signal, &logged);
+            self.log(signal_name(signal), target, logged);
+            match result {
+                Ok(()) => sent.push(*pid),
+                Err(SignalError::Gone) => {}
+                Err(SignalError::PermissionDenied) => denied.push(*pid),
+                Err(SignalError::Other(_)) => failed.push(*pid),
+                Err(SignalError::Unsupported) => return Err(AppError::SignalUnsupported(signal)),
+            }
+        }
+        self.remember_signalled(&sent, signal);
+        self.marked.retain(|pid| !pids.contains(pid));
+        self.manager.refresh_pids(pids);
+        self.load_processes();
+
+        // a process may ignore or take its time with the polite signals
+        let polite = matches!(signal, Signal::Term | Signal::Interrupt | Signal::Hangup);
+        let grace_period = if force {
+            self.config.polite_kill_timeout
+        } else {
+            self.config.kill_grace_period
+        };
+        if polite && !sent.is_empty() && (force || !grace_period.is_zero()) {
+            self.kill_check = Some(KillCheck {
+                signal,
+                sent: sent.len(),
+                pids: sent,
+                deadline: Instant::now() + grace_period,
+                force,
+            });
+        }
+
+        if !denied.is_empty() {
+            if self.elevation_tool.is_none() {
+                return Err(AppError::PermissionDenied {
+                    signal,
+                    pids: denied,
+                });
+            }
+            self.denied = Some(PendingSignal {
+                signal,
+                pids: denied,
+                force: false,
+            });
+            self.input_mode = InputMode::Elevate;
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::SignalFailed {
+                signal,
+                pids: failed,
+            })
+        }
+    }
+
+    // the signal tells a kill from a crash once they exit, and what they were started with lets
+    // them be started again
+    fn remember_signalled(&mut self, pids: &[Pid], signal: Signal) {
+        // stopping and resuming don't end a process
+        if matches!(signal, Signal::Stop | Signal::Continue) {
+            return;
+        }
+        for pid in pids {
+            self.signalled.insert(*pid, signal);
+            if let Some(launch) = self.manager.process(*pid).and_then(Launch::of) {
+                self.launches.insert(*pid, launch);
+            }
+        }
+    }
+
+    // drops the processes that exited, once the grace period is over the rest are offered SIGKILL
+    fn check_kills(&mut self) {
+        let Some(mut check) = self.kill_check.take() else {
+            return;
+        };
+
+        self.manager.refresh_pids(&check.pids);
+        let manager = &self.manager;
+        // a zombie is dead already, it just has not been reaped
+        check.pids.retain(|pid| {
+            manager
+                .process(*pid)
+                .is_some_and(|p| p.status() != ProcessStatus::Zombie)
+        });
+
+        let expired = Instant::now() >= check.deadline;
+        if check.pids.is_empty() {
+            self.load_processes();
+            if check.force {
+                self.toast(format!(
+                    "{} process(es) exited after {}",
+                    check.sent,
+                    signal_name(check.signal)
+                ));
+            }
+        } else if expired && check.force {
+            let exited = check.sent - check.pids.len();
+            self.toast(format!(
+                "{} exited after {}, {} still running after {}s got SIGKILL",
+                exited,
+                signal_name(check.signal),
+                check.pids.len(),
+                self.config.polite_kill_timeout.as_secs()
+            ));
+            let result = self.send_signal(Signal::Kill, &check.pids);
+            self.report(result);
+        } else if expired && matches!(self.input_mode, InputMode::Normal) {
+            self.survivors = Some(PendingSignal {
+                signal: check.signal,
+                pids: check.pids,
+                force: false,
+            });
+            self.input_mode = InputMode::Escalate;
+        } else {
+            self.kill_check = Some(check);
+        }
+    }
+
+    pub fn escalate(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if let Some(survivors) = self.survivors.take() {
+            let result = self.send_signal(Signal::Kill, &survivors.pids);
+            self.report(result);
+        }
+    }
+
+    pub fn dismiss_survivors(&mut self) {
+        self.survivors = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    // the parents that have to reap the pending zombies
+    pub(crate) fn zombie_parents(&self) -> Vec<Pid> {
+        let mut parents: Vec<Pid> = self
+            .zombies
+            .iter()
+            .flatten()
+            .filter_map(|pid| self.manager.process(*pid)?.parent())
+            .filter(|parent| self.manager.process(*parent).is_some())
+            .collect();
+        parents.sort();
+        parents.dedup();
+        parents
+    }
+
+    // a parent that missed the SIGCHLD of its child's exit may reap it when it gets another one
+    pub fn signal_zombie_parents(&mut self) {
+        let parents = self.zombie_parents();
+        self.input_mode = InputMode::Normal;
+        let Some(zombies) = self.zombies.take() else {
+            return;
+        };
+        let result = if parents.is_empty() {
+            Err(AppError::NoParent(zombies[0]))
+        } else {
+            // protected parents are left alone and it is confirmed like any other signal
+            self.request_signal(Signal::Child, parents)
+        };
+        self.report(result);
+    }
+
+    // once the parent is gone init adopts the zombies and reaps them
+    pub fn kill_zombie_parents(&mut self) {
+        let parents = self.zombie_parents();
+        self.input_mode = InputMode::Normal;
+        let Some(zombies) = self.zombies.take() else {
+            return;
+        };
+        let result = if parents.is_empty() {
+            Err(AppError::NoParent(zombies[0]))
+        } else {
+            self.request_signal(Signal::Kill, parents)
+        };
+        self.report(result);
+    }
+
+    pub fn dismiss_zombies(&mut self) {
+        self.zombies = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn confirm_elevation(&mut self) {
+        self.elevation = self.denied.take();
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn cancel_elevation(&mut self) {
+        self.denied = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn finish_elevation(&mut self, pending: PendingSignal, status: io::Result<ExitStatus>) {
+        let result = match status {
+            Ok(status) if status.success() => {
+                self.remember_signalled(&pending.pids, pending.signal);
+                Ok(())
+            }
+            Ok(status) => Err(AppError::Elevation(status.to_string())),
+            Err(err) => Err(AppError::Elevation(err.to_string())),
+        };
+        let action = format!(
+            "{} via {}",
+            signal_name(pending.signal),
+            self.elevation_tool.unwrap_or("elevation")
+        );
+        for pid in &pending.pids {
+            let target = self.describe(*pid);
+            let logged = result.as_ref().map_err(|err| err.to_string()).copied();
+            self.audit(*pid, pending.signal, &logged);
+            self.log(action.clone(), target, logged);
+        }
+        self.report(result);
+        self.manager.refresh_pids(&pending.pids);
+        self.load_processes();
+    }
+
+    pub fn open_core_dump(&mut self) -> Result<(), AppError> {
+        self.selected_process().ok_or(AppError::NoSelection)?;
+        self.input_mode = InputMode::CoreDump;
+        Ok(())
+    }
+
+    // SIGQUIT and SIGABRT go through the confirmation like any signal
+    pub fn core_dump_signal(&mut self, signal: Signal) {
+        self.input_mode = InputMode::Normal;
+        let result = match self.selected_process() {
+            Some(process) => self.request_signal(signal, vec![process.pid()]),
+            None => Err(AppError::NoSelection),
+        };
+        self.report(result);
+    }
+
+    pub fn core_dump_gcore(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let result = self.request_gcore();
+        self.report(result);
+    }
+
+    // gcore stops the process while it reads its memory, so it is checked like a signal
+    fn request_gcore(&mut self) -> Result<(), AppError> {
+        let process = self.selected_process().ok_or(AppError::NoSelection)?;
+        let pid = process.pid();
+        if self.config.is_protected(process) {
+            return Err(AppError::Protected(vec![format!(
+                "{} {}",
+                pid,
+                process.name()
+            )]));
+        }
+        let dir = data_dir()
+            .map(|dir| dir.join("cores"))
+            .ok_or_else(|| AppError::CoreDump {
+                pid,
+                reason: "no home directory to write it to".to_string(),
+            })?;
+        let target = self.describe(pid);
+        if self.skip_dry_run("gcore", &target) {
+            return Ok(());
+        }
+        if self.config.confirm_kill {
+            self.pending_core_dump = Some((pid, dir));
+            self.input_mode = InputMode::ConfirmCoreDump;
+        } else {
+            self.core_dump = Some((pid, dir));
+        }
+        Ok(())
+    }
+
+    pub fn confirm_core_dump(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.core_dump = self.pending_core_dump.take();
+    }
+
+    pub fn cancel_core_dump(&mut self) {
+        self.pending_core_dump = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn finish_core_dump(&mut self, pid: Pid, dir: &Path, status: io::Result<ExitStatus>) {
+        let result = match status {
+            Ok(status) if status.success() => Ok(()),
+            Ok(status) => Err(status.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+        let target = self.describe(pid);
+        self.log("gcore".to_string(), target, result.clone());
+        match result {
+            Ok(()) => self.toast(format!(
+                "Wrote {}",
+                dir.join(format!("core.{}", pid)).display()
+            )),
+            Err(reason) => self.report(Err(AppError::CoreDump { pid, reason })),
+        }
+    }
+
+    pub fn open_services(&mut self) -> Result<(), AppError> {
+        self.load_services()?;
+        if self.services_state.selected().is_none() && !self.services.is_empty() {
+            self.services_state.select(Some(0));
+        }
+        self.input_mode = InputMode::Services;
+        Ok(())
+    }
+
+    fn load_services(&mut self) -> Result<(), AppError> {
+        self.services = list_services().map_err(|err| AppError::Services(err.to_string()))?;
+        if let Some(i) = self.services_state.selected() {
+            self.services_state.select(match self.services.len() {
+                0 => None,
+                len => Some(i.min(len - 1)),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn next_service(&mut self) {
+        cycle_selection(&mut self.services_state, self.services.len(), 1);
+    }
+
+    pub fn prev_service(&mut self) {
+        cycle_selection(&mut self.services_state, self.services.len(), -1);
+    }
+
+    // stops, starts or restarts the selected service, a restart blocks until it has stopped
+    pub fn control_selected_service(&mut self, control: ServiceControl) {
+        let result = self.control_service(control);
+        self.report(result);
+    }
+
+    fn control_service(&mut self, control: ServiceControl) -> Result<(), AppError> {
+        if self.config.read_only {
+            return Err(AppError::ReadOnly);
+        }
+        let service = self
+            .services_state
+            .selected()
+            .and_then(|i| self.services.get(i))
+            .ok_or(AppError::NoSelection)?;
+
+        let name = service.name.clone();
+        let action = format!("service {}", control.label());
+        if self.skip_dry_run(&action, &name) {
+            return Ok(());
+        }
+        let result = control_service(&name, control);
+        self.log(
+            action,
+            name.clone(),
+            result.as_ref().map_err(|err| err.to_string()).copied(),
+        );
+        result.map_err(|err| AppError::ServiceControl {
+            name,
+            control,
+            reason: err.to_string(),
+        })?;
+        self.load_services()?;
+        // the hosted process may have exited or a new one started
+        self.refetch_process();
+        Ok(())
+    }
+
+    pub fn open_open_files(&mut self) -> Result<(), AppError> {
+        let pid = self.selected_process().ok_or(AppError::NoSelection)?.pid();
+        self.pane_pid = Some(pid);
+        self.open_files_state.select(None);
+        self.load_open_files()?;
+        if !self.open_files.is_empty() {
+            self.open_files_state.select(Some(0));
+        }
+        self.input_mode = InputMode::OpenFiles;
+        Ok(())
+    }
+
+    fn load_open_files(&mut self) -> Result<(), AppError> {
+        let Some(pid) = self.pane_pid else {
+            return Ok(());
+        };
+        self.open_files = open_files(pid).map_err(|err| AppError::OpenFiles {
+            pid,
+            reason: err.to_string(),
+        })?;
+        if let Some(i) = self.open_files_state.selected() {
+            self.open_files_state.select(match self.open_files.len() {
+                0 => None,
+                len => Some(i.min(len - 1)),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn next_open_file(&mut self) {
+        cycle_selection(&mut self.open_files_state, self.open_files.len(), 1);
+    }
+
+    pub fn prev_open_file(&mut self) {
+        cycle_selection(&mut self.open_files_state, self.open_files.len(), -1);
+    }
+
+    pub fn open_limits(&mut self) -> Result<(), AppError> {
+        let pid = self.selected_process().ok_or(AppError::NoSelection)?.pid();
+        self.pane_pid = Some(pid);
+        self.limits_state.select(None);
+        self.load_limits()?;
+        if !self.limits.is_empty() {
+            self.limits_state.select(Some(0));
+        }
+        self.input_mode = InputMode::Limits;
+        Ok(())
+    }
+
+    fn load_limits(&mut self) -> Result<(), AppError> {
+        let Some(pid) = self.pane_pid else {
+            return Ok(());
+        };
+        let limits = resource_limits(pid).map_err(|err| AppError::Limits {
+            pid,
+            reason: err.to_string(),
+        })?;
+        let process = self.manager.process(pid);
+        self.limits = limits
+            .into_iter()
+            .map(|limit| {
+                let used = match limit.name.as_str() {
+                    "Max open files" => open_files(pid).ok().map(|files| files.len() as u64),
+                    "Max address space" => process.map(|process| process.virtual_memory()),
+                    "Max resident set" => process.map(|process| process.memory()),
+                    _ => None,
+                };
+                (limit, used)
+            })
+            .collect();
+        if let Some(i) = self.limits_state.selected() {
+            self.limits_state.select(match self.limits.len() {
+                0 => None,
+                len => Some(i.min(len - 1)),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn next_limit(&mut self) {
+        cycle_selection(&mut self.limits_state, self.limits.len(), 1);
+    }
+
+    pub fn prev_limit(&mut self) {
+        cycle_selection(&mut self.limits_state, self.limits.len(), -1);
+    }
+
+    pub fn open_connections(&mut self) -> Result<(), AppError> {
+        let pid = self.selected_process().ok_or(AppError::NoSelection)?.pid();
+        self.pane_pid = Some(pid);
+        self.connections_state.select(None);
+        self.load_connections()?;
+        if !self.connections.is_empty() {
+            self.connections_state.select(Some(0));
+        }
+        self.input_mode = InputMode::Connections;
+        Ok(())
+    }
+
+    fn load_connections(&mut self) -> Result<(), AppError> {
+        let Some(pid) = self.pane_pid else {
+            return Ok(());
+        };
+        self.connections = process_connections(pid).map_err(|err| AppError::Connections {
+            pid,
+            reason: err.to_string(),
+        })?;
+        if let Some(i) = self.connections_state.selected() {
+            self.connections_state.select(match self.connections.len() {
+                0 => None,
+                len => Some(i.min(len - 1)),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn next_connection(&mut self) {
+        cycle_selection(&mut self.connections_state, self.connections.len(), 1);
+    }
+
+    pub fn prev_connection(&mut self) {
+        cycle_selection(&mut self.connections_state, self.connections.len(), -1);
+    }
+
+    pub fn open_threads(&mut self) -> Result<(), AppError> {
+        let pid = self.selected_process().ok_or(AppError::NoSelection)?.pid();
+        self.pane_pid = Some(pid);
+        self.threads_state.select(None);
+        self.load_threads();
+        if !self.threads.is_empty() {
+            self.threads_state.select(Some(0));
+        }
+        self.input_mode = InputMode::Threads;
+        Ok(())
+    }
+
+    fn load_threads(&mut self) {
+        let Some(pid) = self.pane_pid else {
+            return;
+        };
+        self.threads = self.manager.threads(pid);
+        if let Some(i) = self.threads_state.selected() {
+            self.threads_state.select(match self.threads.len() {
+                0 => None,
+                len => Some(i.min(len - 1)),
+            });
+        }
+    }
+
+    pub fn next_thread(&mut self) {
+        cycle_selection(&mut self.threads_state, self.threads.len(), 1);
+    }
+
+    pub fn prev_thread(&mut self) {
+        cycle_selection(&mut self.threads_state, self.threads.len(), -1);
+    }
+
+    pub fn open_children(&mut self) -> Result<(), AppError> {
+        let pid = self.selected_process().ok_or(AppError::NoSelection)?.pid();
+        self.pane_pid = Some(pid);
+        self.children_state.select(None);
+        self.load_children();
+        if !self.children.is_empty() {
+            self.children_state.select(Some(0));
+        }
+        self.input_mode = InputMode::Children;
+        Ok(())
+    }
+
+    fn load_children(&mut self) {
+        let Some(pid) = self.pane_pid else {
+            return;
+        };
+        self.children = self.manager.descendants(pid);
+        if let Some(i) = self.children_state.selected() {
+            self.children_state.select(match self.children.len() {
+                0 => None,
+                len => Some(i.min(len - 1)),
+            });
+        }
+    }
+
+    pub fn next_child(&mut self) {
+        cycle_selection(&mut self.children_state, self.children.len(), 1);
+    }
+
+    pub fn prev_child(&mut self) {
+        cycle_selection(&mut self.children_state, self.children.len(), -1);
+    }
+
+    // kills every marked process, or just the selected one when nothing is marked
+    pub fn kill_marked(&mut self) -> Result<(), AppError> {
+        self.request_signal(Signal::Kill, self.targets())
+    }
+
+    // kills every process sharing the selected process's name, filtered out or not
+    pub fn kill_all_by_name(&mut self) -> Result<(), AppError> {
+        let process = self
+            .state
+            .selected()
+            .and_then(|i| self.processes.get(i))
+            .ok_or(AppError::NoSelection)?;
+
+        let name = process.name.clone();
+        let pids = self
+            .manager
+            .find_by_name(&name, true)
+            .iter()
+            .map(|p| p.pid())
+            .collect();
+        self.request_signal(Signal::Kill, pids)
+    }
+
+    // the pid, or the command line (the name for kernel threads, which have none)
+    pub fn copy_selected(&mut self, field: CopyField) -> Result<(), AppError> {
+        let process = self
+            .state
+            .selected()
+            .and_then(|i| self.processes.get(i))
+            .ok_or(AppError::NoSelection)?;
+        let (what, text) = match field {
+            CopyField::Pid => ("PID", process.pid.to_string()),
+            CopyField::Command if process.cmd.is_empty() => ("the name of", process.name.clone()),
+            CopyField::Command => ("the command line of", process.cmd.clone()),
+            CopyField::Exe => (
+                "the executable of",
+                self.process_path(process.pid, true)?.display().to_string(),
+            ),
+            CopyField::Cwd => (
+                "the working directory of",
+                self.process_path(process.pid, false)?.display().to_string(),
+            ),
+        };
+        let message = format!("Copied {} {}", what, process.pid);
+
+        clipboard::copy(&text).map_err(|err| AppError::Clipboard(err.to_string()))?;
+        self.toast(message);
+        Ok(())
+    }
+
+    // the executable, or the working directory, of a process we may read them of
+    fn process_path(&self, pid: Pid, exe: bool) -> Result<PathBuf, AppError> {
+        let process = self
+            .manager
+            .process(pid)
+            .ok_or(AppError::ProcessGone(pid))?;
+        let path = if exe { process.exe() } else { process.cwd() };
+        if path.as_os_str().is_empty() {
+            return Err(AppError::UnknownPath { pid, exe });
+        }
+        Ok(path.to_path_buf())
+    }
+
+    // opens the directory of the selected process's executable in the file manager
+    pub fn reveal_exe(&mut self) -> Result<(), AppError> {
+        let pid = self
+            .state
+            .selected()
+            .and_then(|i| self.processes.get(i))
+            .ok_or(AppError::NoSelection)?
+            .pid;
+        let exe = self.process_path(pid, true)?;
+        // an executable replaced by an update is still shown at its old path
+        let exe = match exe.to_str().and_then(|exe| exe.strip_suffix(" (deleted)")) {
+            Some(path) => PathBuf::from(path),
+            None => exe,
+        };
+        reveal(&exe).map_err(|err| AppError::Reveal {
+            path: exe.clone(),
+            reason: err.to_string(),
+        })?;
+        self.toast(format!("Opened the directory of {}", exe.display()));
+        Ok(())
+    }
+
+    pub fn toggle_details(&mut self) {
+        self.show_details = !self.show_details;
+    }
+
+    // runs an action, a failure is shown as a toast
+    pub fn perform(&mut self, action: Action) {
+        if action.is_destructive() && self.config.read_only {
+            self.report(Err(AppError::ReadOnly));
+            return;
+        }
+        let result = match action {
+            Action::SelectParent => self.collapse_or_select_parent(),
+            Action::SelectChild => self.expand_or_select_child(),
+            Action::Kill => self.kill(),
+            Action::PoliteKill => self.polite_kill(),
+            Action::ScheduleKill => self.open_schedule_kill(),
+            Action::ShowScheduledKills => self.open_scheduled_kills(),
+            Action::Respawn => self.respawn_last(),
+            Action::CoreDump => self.open_core_dump(),
+            Action::ToggleDryRun => {
+                self.toggle_dry_run();
+                Ok(())
+            }
+            Action::Spawn => {
+                self.open_spawn();
+                Ok(())
+            }
+            Action::KillMarked => self.kill_marked(),
+            Action::KillAllByName => self.kill_all_by_name(),
+            Action::Suspend => self.request_signal(Signal::Stop, self.targets()),
+            Action::Resume => self.request_signal(Signal::Continue, self.targets()),
+            Action::LowerPriority => self.renice(1),
+            Action::RaisePriority => self.renice(-1),
+            Action::SetAffinity => self.open_affinity_input(),
+            Action::ToggleServices => self.open_services(),
+            Action::ShowOpenFiles => self.open_open_files(),
+            Action::ShowLimits => self.open_limits(),
+            Action::ShowConnections => self.open_connections(),
+            Action::ShowThreads => self.open_threads(),
+            Action::ShowChildren => self.open_children(),
+            Action::ShowExited => self.open_exited(),
+            Action::ShowHistory => self.open_history(),
+            Action::SavedFilters => self.open_filters(),
+            Action::Export => self.export(),
+            Action::CopyPid => self.copy_selected(CopyField::Pid),
+            Action::CopyCommand => self.copy_selected(CopyField::Command),
+            Action::CopyExe => self.copy_selected(CopyField::Exe),
+            Action::CopyCwd => self.copy_selected(CopyField::Cwd),
+            Action::RevealExe => self.reveal_exe(),
+            action => {
+                self.perform_infallible(action);
+                Ok(())
+            }
+        };
+        self.report(result);
+    }
+
+    fn perform_infallible(&mut self, action: Action) {
+        match action {
+            Action::Next => self.next(),
+            Action::Prev => self.prev(),
+            Action::PageDown => self.page_down(),
+            Action::PageUp => self.page_up(),
+            Action::First => self.first(),
+            Action::Last => self.last(),
+            Action::JumpToPid => self.open_jump_to_pid(),
+            Action::SignalMenu => self.open_signal_menu(),
+            Action::SignalPrompt => self.open_signal_input(),
+            Action::ToggleMark => self.toggle_mark(),
+            Action::MarkAll => self.mark_all(),
+            Action::InvertMarks => self.invert_marks(),
+            Action::ClearMarks => self.clear_marks(),
+            Action::VisualMark => self.toggle_visual(),
+            Action::TogglePin => self.toggle_pin(),
+            Action::SortByName => self.switch_sort(SortColumn::Name),
+            Action::SortByMemory => self.switch_sort(SortColumn::Memory),
+            Action::NextSortColumn => self.cycle_sort_column(1),
+            Action::PrevSortColumn => self.cycle_sort_column(-1),
+            Action::ReverseSort => self.switch_sort(self.sort_column),
+            Action::Search => self.enter_input_mode(),
+            Action::ToggleMyProcesses => self.toggle_only_my_processes(),
+            Action::CycleStateFilter => self.cycle_state_filter(),
+            Action::ToggleTree => self.toggle_tree_view(),
+            Action::ToggleGroups => self.toggle_groups(),
+            Action::ToggleCollapseAll => self.toggle_collapse_all(),
+            Action::ToggleCollapse => self.toggle_collapse(),
+            Action::ToggleDetails => self.toggle_details(),
+            Action::ToggleCmd => self.toggle_cmd(),
+            Action::ScrollLeft => self.scroll_name(-NAME_SCROLL_STEP),
+            Action::ScrollRight => self.scroll_name(NAME_SCROLL_STEP),
+            Action::ToggleCpuMeters => self.show_cpu_meters = !self.show_cpu_meters,
+            Action::CycleMemoryKind => self.cycle_memory_kind(),
+            Action::Refresh => self.refetch_process(),
+            Action::IncreaseRefreshInterval => self.increase_refresh_interval(),
+            Action::DecreaseRefreshInterval => self.decrease_refresh_interval(),
+            Action::ChooseColumns => self.open_columns(),
+            Action::Help => self.open_help(),
+            Action::Quit => self.should_quit = true,
+            // the fallible ones are handled by `perform`
+            _ => {}
+        }
+    }
+
+    pub fn open_help(&mut self) {
+        self.help_scroll = 0;
+        self.input_mode = InputMode::Help;
+    }
+
+    pub fn scroll_help(&mut self, delta: i16) {
+        self.help_scroll = self.help_scroll.saturating_add_signed(delta);
+    }
+
+    pub fn toggle_cmd(&mut self) {
+        self.show_cmd = !self.show_cmd;
+        self.name_scroll = 0;
+    }
+
+    // scrolls the name or command line column by `delta` characters, up to the longest one
+    pub fn scroll_name(&mut self, delta: isize) {
+        let longest = self
+            .processes
+            .iter()
+            .map(|p| {
+                if self.show_cmd {
+                    p.cmd.chars().count()
+                } else {
+                    p.name.chars().count()
+                }
+            })
+            .max()
+            .unwrap_or(0);
+        let scroll = (self.name_scroll as isize + delta).max(0) as usize;
+        self.name_scroll = scroll.min(longest.saturating_sub(1));
+    }
+
+    pub(crate) fn selected_process(&self) -> Option<&Process> {
+        let info = self.state.selected().and_then(|i| self.processes.get(i))?;
+        self.manager.process(info.pid)
+    }
+
+    pub fn open_signal_menu(&mut self) {
+        if self.targets().is_empty() {
+            return;
+        }
+        self.signal_menu_state.select(Some(0));
+        self.input_mode = InputMode::SignalMenu;
+    }
+
+    pub fn next_signal(&mut self) {
+        let i = self
+            .signal_menu_state
+            .selected()
+            .map_or(0, |i| (i + 1) % SIGNALS.len());
+        self.signal_menu_state.select(Some(i));
+    }
+
+    pub fn prev_signal(&mut self) {
+        let i = self
+            .signal_menu_state
+            .selected()
+            .map_or(0, |i| (i + SIGNALS.len() - 1) % SIGNALS.len());
+        self.signal_menu_state.select(Some(i));
+    }
+
+    pub fn send_chosen_signal(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if let Some(i) = self.signal_menu_state.selected() {
+            let result = self.request_signal(SIGNALS[i].0, self.targets());
+            self.report(result);
+        }
+    }
+
+    pub fn open_signal_input(&mut self) {
+        if self.targets().is_empty() {
+            return;
+        }
+        self.signal_input.clear();
+        self.input_mode = InputMode::SignalInput;
+    }
+
+    pub fn apply_signal_input(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let input = self.signal_input.trim().to_string();
+        if input.is_empty() {
+            return;
+        }
+        let result = match parse_signal(&input) {
+            Some(signal) => self.request_signal(signal, self.targets()),
+            None => Err(AppError::UnknownSignal(input)),
+        };
+        self.report(result);
+    }
+
+    pub fn open_filters(&mut self) -> Result<(), AppError> {
+        if self.config.filters.is_empty() {
+            return Err(AppError::NoSavedFilters);
+        }
+        self.filters_state.select(Some(0));
+        self.input_mode = InputMode::Filters;
+        Ok(())
+    }
+
+    pub fn next_filter(&mut self) {
+        let len = self.config.filters.len();
+        let i = self.filters_state.selected().map_or(0, |i| (i + 1) % len);
+        self.filters_state.select(Some(i));
+    }
+
+    pub fn prev_filter(&mut self) {
+        let len = self.config.filters.len();
+        let i = self
+            .filters_state
+            .selected()
+            .map_or(0, |i| (i + len - 1) % len);
+        self.filters_state.select(Some(i));
+    }
+
+    // searches for the chosen filter as if it had been typed
+    pub fn apply_chosen_filter(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let Some((_, input)) = self
+            .filters_state
+            .selected()
+            .and_then(|i| self.config.filters.get(i))
+        else {
+            return;
+        };
+        let previous = std::mem::replace(&mut self.search_input, input.clone());
+        self.search();
+        // the table is still filtered by the previous input
+        if let Some(err) = self.search_error.take() {
+            self.search_input = previous;
+            self.report(Err(AppError::InvalidFilter(err)));
+        }
+    }
+
+    pub fn open_columns(&mut self) {
+        self.columns_state.select(Some(0));
+        self.input_mode = InputMode::Columns;
+    }
+
+    pub fn next_column(&mut self) {
+        cycle_list_selection(&mut self.columns_state, self.columns.len(), 1);
+    }
+
+    pub fn prev_column(&mut self) {
+        cycle_list_selection(&mut self.columns_state, self.columns.len(), -1);
+    }
+
+    // hides or shows the chosen column, the last one shown stays
+    pub fn toggle_column(&mut self) {
+        let Some(i) = self.columns_state.selected() else {
+            return;
+        };
+        let shown = self.columns.iter().filter(|(_, shown)| *shown).count();
+        if let Some((_, visible)) = self.columns.get_mut(i) {
+            if !*visible || shown > 1 {
+                *visible = !*visible;
+            }
+        }
+    }
+
+    // moves the chosen column `step` places to the left (-1) or right (1) in the table
+    pub fn move_column(&mut self, step: isize) {
+        let Some(i) = self.columns_state.selected() else {
+            return;
+        };
+        let j = i as isize + step;
+        if j < 0 || j as usize >= self.columns.len() {
+            return;
+        }
+        self.columns.swap(i, j as usize);
+        self.columns_state.select(Some(j as usize));
+    }
+
+    // closes the chooser, writing the columns to the config file when they changed
+    pub fn close_columns(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if self.columns == column_layout(self.config.columns.as_deref()) {
+            return;
+        }
+        let shown = self
+            .columns
+            .iter()
+            .filter(|(_, shown)| *shown)
+            .map(|(column, _)| *column)
+            .collect();
+        let result = self
+            .config
+            .save_columns(shown)
+            .map_err(|err| AppError::SaveConfig(err.to_string()));
+        self.report(result);
+    }
+
+    // starts a full refresh in the background, the table is updated once it is done
+    pub fn refetch_process(&mut self) {
+        self.refresher.request();
+    }
+
+    pub fn is_refreshing(&self) -> bool {
+        self.refresher.is_refreshing()
+    }
+
+    // starts the cpu history from what `process_killer daemon` sampled, when it runs
+    fn attach_collector(&mut self) {
+        let Some(snapshot) = collector::fetch() else {
+            return;
+        };
+        for sample in snapshot.processes {
+            let pid = Pid::from_u32(sample.pid);
+            let same = self
+                .manager
+                .process(pid)
+                .is_some_and(|process| process.start_time() == sample.start_time);
+            if same {
+                let skip = sample.cpu.len().saturating_sub(CPU_HISTORY_LEN);
+                self.cpu_history
+                    .insert(pid, sample.cpu.into_iter().skip(skip).collect());
+            }
+        }
+        self.toast(format!(
+            "CPU history of {} processes from the collector, sampled every {}s",
+            self.cpu_history.len(),
+            snapshot.interval_secs
+        ));
+    }
+
+    fn record_cpu_history(&mut self) {
+        let processes = self.manager.system().processes();
+        self.cpu_history
+            .retain(|pid, _| processes.contains_key(pid));
+        for (pid, process) in processes {
+            let history = self.cpu_history.entry(*pid).or_default();
+            if history.len() == CPU_HISTORY_LEN {
+                history.pop_front();
+            }
+            history.push_back(process.cpu_usage());
+        }
+    }
+
+    fn known_processes(&self) -> HashMap<Pid, (String, String)> {
+        self.manager
+            .system()
+            .processes()
+            .iter()
+            .map(|(pid, process)| (*pid, (process.name().to_string(), process.cmd().join(" "))))
+            .collect()
+    }
+
+    // compares the refreshed processes with the ones before, for the new and exited ones
+    fn track_processes(&mut self) {
+        let known = self.known_processes();
+        let now = Instant::now();
+        for pid in known.keys().filter(|pid| !self.known.contains_key(pid)) {
+            self.spawned.insert(*pid, now);
+        }
+        self.spawned
+            .retain(|pid, at| known.contains_key(pid) && at.elapsed() < SPAWN_HIGHLIGHT);
+
+        let previous = std::mem::replace(&mut self.known, known);
+        for (pid, (name, cmd)) in previous {
+            if self.known.contains_key(&pid) {
+                continue;
+            }
+            self.exited.push_front(ExitedProcess {
+                pid,
+                name,
+                cmd,
+                exited_at: now,
+                signal: self.signalled.remove(&pid),
+                launch: self.launches.remove(&pid),
+            });
+        }
+        self.exited.truncate(EXITED_LEN);
+        while self
+            .exited
+            .back()
+            .is_some_and(|exited| exited.exited_at.elapsed() > EXITED_KEEP)
+        {
+            self.exited.pop_back();
+        }
+        if let Some(i) = self.exited_state.selected() {
+            self.exited_state.select(match self.exited.len() {
+                0 => None,
+                len => Some(i.min(len - 1)),
+            });
+        }
+    }
+
+    // the newest killed process that exited, Enter in the exited pane picks another one
+    fn respawn_last(&mut self) -> Result<(), AppError> {
+        let i = self
+            .exited
+            .iter()
+            .position(|exited| exited.launch.is_some())
+            .ok_or(AppError::NothingToRespawn)?;
+        self.respawn(i)
+    }
+
+    pub fn respawn_selected_exited(&mut self) {
+        let result = match self.exited_state.selected() {
+            _ if self.config.read_only => Err(AppError::ReadOnly),
+            Some(i) => self.respawn(i),
+            None => Err(AppError::NothingToRespawn),
+        };
+        self.report(result);
+    }
+
+    fn respawn(&mut self, i: usize) -> Result<(), AppError> {
+        let exited = &self.exited[i];
+        let launch = exited.launch.as_ref().ok_or(AppError::NotRespawnable {
+            pid: exited.pid,
+            name: exited.name.clone(),
+        })?;
+        let target = format!("{} {}", exited.pid, exited.name);
+        let name = exited.name.clone();
+        let result = launch.spawn();
+        let logged = result.as_ref().map(|_| ()).map_err(|err| err.to_string());
+        self.log("respawn".to_string(), target, logged);
+        let pid = result.map_err(|err| AppError::Respawn {
+            name: name.clone(),
+            reason: err.to_string(),
+        })?;
+        self.toast(format!("Started {} again as {}", name, pid));
+        self.refetch_process();
+        Ok(())
+    }
+
+    pub fn open_spawn(&mut self) {
+        self.spawn_input.clear();
+        self.input_mode = InputMode::Spawn;
+    }
+
+    pub fn apply_spawn(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let command = self.spawn_input.trim().to_string();
+        if command.is_empty() {
+            return;
+        }
+        let result = Launch::shell(&command).spawn();
+        let logged = result.as_ref().map(|_| ()).map_err(|err| err.to_string());
+        self.log("spawn".to_string(), command.clone(), logged);
+        match result {
+            Ok(pid) => {
+                self.toast(format!("Started {} as {}", command, pid));
+                self.refetch_process();
+            }
+            Err(err) => self.report(Err(AppError::Spawn {
+                command,
+                reason: err.to_string(),
+            })),
+        }
+    }
+
+    pub fn open_exited(&mut self) -> Result<(), AppError> {
+        self.exited_state
+            .select((!self.exited.is_empty()).then_some(0));
+        self.input_mode = InputMode::Exited;
+        Ok(())
+    }
+
+    pub fn next_exited(&mut self) {
+        cycle_selection(&mut self.exited_state, self.exited.len(), 1);
+    }
+
+    pub fn prev_exited(&mut self) {
+        cycle_selection(&mut self.exited_state, self.exited.len(), -1);
+    }
+
+    // picks up a finished background refresh
+    pub fn poll_refresh(&mut self) {
+        if self.refresher.poll(&mut self.manager) {
+            let manager = &self.manager;
+            self.marked.retain(|pid| manager.process(*pid).is_some());
+            self.search_filter.refresh();
+            self.record_cpu_history();
+            self.track_processes();
+            // built once, reading the cgroup and nice value of every process isn't free
+            let rows = self.manager.processes();
+            self.apply_rules(&rows);
+            self.check_alerts(&rows);
+            self.load_rows(rows);
+            match self.input_mode {
+                InputMode::Threads => self.load_threads(),
+                InputMode::Children => self.load_children(),
+                _ => {}
+            }
+        }
+    }
+
+    // signals the processes that stayed over a rule's limit for long enough, the protected ones
+    // and this one are left alone
+    fn apply_rules(&mut self, processes: &[ProcessInfo]) {
+        if self.config.rules.is_empty() || self.config.read_only {
+            return;
+        }
+        let limits = self.config.rules.iter().map(|rule| &rule.limit);
+        let due = self
+            .rule_tracker
+            .due(limits, processes, Instant::now(), true);
+
+        let own_pid = Pid::from_u32(std::process::id());
+        let mut sent = vec![];
+        for (i, pid) in due {
+            let Some(process) = self.manager.process(pid) else {
+                continue;
+            };
+            if pid == own_pid
+                || self.config.is_protected(process)
+                || process.status() == ProcessStatus::Zombie
+            {
+                continue;
+            }
+            let rule = &self.config.rules[i];
+            let (signal, name, limits) =
+                (rule.signal, rule.limit.name.clone(), rule.limit.describe());
+            let target = self.describe(pid);
+            if self.config.dry_run {
+                self.log(
+                    format!("{} (rule {}, dry run)", signal_name(signal), name),
+                    target.clone(),
+                    Ok(()),
+                );
+                self.toast(format!(
+                    "Rule {}: dry run, {} not sent to {}, {}",
+                    name,
+                    signal_name(signal),
+                    target,
+                    limits
+                ));
+                continue;
+            }
+            let result = self.manager.signal(pid, signal);
+            let logged = result.as_ref().map_err(|err| err.to_string()).copied();
+            self.audit(pid, signal, &logged);
+            self.log(
+                format!("{} (rule {})", signal_name(signal), name),
+                target.clone(),
+                logged.clone(),
+            );
+            match logged {
+                Ok(()) => {
+                    sent.push(pid);
+                    self.remember_signalled(&[pid], signal);
+                    self.toast(format!(
+                        "Rule {}: sent {} to {}, {}",
+                        name,
+                        signal_name(signal),
+                        target,
+                        limits
+                    ));
+                }
+                Err(err) => self.toast(format!("Rule {}: {} for {}", name, err, target)),
+            }
+        }
+        if !sent.is_empty() {
+            self.manager.refresh_pids(&sent);
+        }
+    }
+
+    // lists the processes that went over an alert's limit, the popup shows them once back to the
+    // table so typing elsewhere isn't cut off
+    fn check_alerts(&mut self, processes: &[ProcessInfo]) {
+        if !self.config.alerts.is_empty() {
+            let limits = self.config.alerts.iter().map(|alert| &alert.limit);
+            let due = self
+                .alert_tracker
+                .due(limits, processes, Instant::now(), false);
+            let at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs());
+
+            for (i, pid) in due {
+                let Some(process) = processes.iter().find(|process| process.pid == pid) else {
+                    continue;
+                };
+                let alert = &self.config.alerts[i];
+                let fired = FiredAlert {
+                    at,
+                    pid,
+                    name: process.name.clone(),
+                    alert: alert.limit.name.clone(),
+                    limits: alert.limit.describe(),
+                };
+                if alert.desktop {
+                    let summary = format!("{} {} is over its limit", pid, fired.name);
+                    let body = format!("{}: {}", fired.alert, fired.limits);
+                    let result =
+                        notify(&summary, &body).map_err(|err| AppError::Notify(err.to_string()));
+                    self.report(result);
+                }
+                self.alerts.insert(0, fired);
+            }
+        }
+
+        if !self.alerts.is_empty() && matches!(self.input_mode, InputMode::Normal) {
+            self.alerts_state.select(Some(0));
+            self.input_mode = InputMode::Alerts;
+        }
+    }
+
+    pub fn next_alert(&mut self) {
+        cycle_selection(&mut self.alerts_state, self.alerts.len(), 1);
+    }
+
+    pub fn prev_alert(&mut self) {
+        cycle_selection(&mut self.alerts_state, self.alerts.len(), -1);
+    }
+
+    // closing forgets them, the popup comes back with the next alert
+    pub fn dismiss_alerts(&mut self) {
+        self.alerts.clear();
+        self.exit_input_mode();
+    }
+
+    // selects the process of the alert in the table, when it is still listed
+    pub fn select_alert(&mut self) {
+        let pid = self
+            .alerts_state
+            .selected()
+            .and_then(|i| self.alerts.get(i))
+            .map(|alert| alert.pid);
+        self.dismiss_alerts();
+        let Some(pid) = pid else {
+            return;
+        };
+        let result = match self.processes.iter().position(|p| p.pid == pid) {
+            Some(i) => {
+                self.state.select(Some(i));
+                Ok(())
+            }
+            None if self.manager.process(pid).is_some() => Err(AppError::NotListed(pid)),
+            None => Err(AppError::ProcessGone(pid)),
+        };
+        self.report(result);
+    }
+
+    // rebuilds the table rows from the last system snapshot, keeping the current search and sort
+    fn load_processes(&mut self) {
+        let rows = self.manager.processes();
+        self.load_rows(rows);
+    }
+
+    // the processes a rule killed since the rows were built are left out
+    fn load_rows(&mut self, rows: Vec<ProcessInfo>) {
+        let selected = self.state.selected();
+        let selected_pid = selected.and_then(|i| self.processes.get(i)).map(|p| p.pid);
+        self.processes.clear();
+        for mut process in rows {
+            if self.manager.process(process.pid).is_none() {
+                continue;
+            }
+            if !self.matches_user(&process) || !self.state_filter.matches(process.status) {
+                continue;
+            }
+            if let Some(score) = self.search_filter.score(&process) {
+                if self.memory_kind == MemoryKind::Shared {
+                    process.shared_memory = shared_memory(process.pid);
+                }
+                process.score = score;
+                process.pinned = self.is_pinned(&process.name);
+                process.spawned = self.spawned.contains_key(&process.pid);
+                self.processes.push(process);
+            }
+        }
+
+        if self.grouped {
+            self.build_groups();
+        } else {
+            self.apply_sort();
+        }
+        if self.tree_view {
+            self.build_tree();
+        }
+
+        // the cursor stays on the same process wherever it moved to, once it is gone or
+        // filtered out on the row that took its place
+        if let Some(i) = selected {
+            let i = selected_pid
+                .and_then(|pid| self.processes.iter().position(|p| p.pid == pid))
+                .unwrap_or(i);
+            if self.processes.is_empty() {
+                self.state.select(None);
+            } else {
+                self.state.select(Some(i.min(self.processes.len() - 1)));
+            }
+        }
+    }
+
+    // reorders the (already sorted) rows depth first under their parents, siblings keep the sort order
+    fn build_tree(&mut self) {
+        let pids: HashSet<Pid> = self.processes.iter().map(|p| p.pid).collect();
+        let mut children: HashMap<Pid, Vec<ProcessInfo>> = HashMap::new();
+        let mut roots = vec![];
+        for process in self.processes.drain(..) {
+            match process.parent {
+                Some(parent) if pids.contains(&parent) => {
+                    children.entry(parent).or_default().push(process)
+                }
+                _ => roots.push(process),
+            }
+        }
+
+        let mut stack: Vec<(ProcessInfo, usize)> =
+            roots.into_iter().rev().map(|p| (p, 0)).collect();
+        while let Some((mut process, depth)) = stack.pop() {
+            let kids = children.remove(&process.pid).unwrap_or_default();
+            process.depth = depth;
+            process.has_children = !kids.is_empty();
+            if !self.collapsed.contains(&process.pid) {
+                stack.extend(kids.into_iter().rev().map(|p| (p, depth + 1)));
+            }
+            self.processes.push(process);
+        }
+    }
+
+    // sorts by the added up usage of each name, an expanded name lists its processes below it
+    fn build_groups(&mut self) {
+        let mut names: Vec<String> = vec![];
+        let mut members: HashMap<String, Vec<ProcessInfo>> = HashMap::new();
+        for process in self.processes.drain(..) {
+            if !members.contains_key(&process.name) {
+                names.push(process.name.clone());
+            }
+            members
+                .entry(process.name.clone())
+                .or_default()
+                .push(process);
+        }
+        // a name with a single process is shown as that process
+        self.processes = names
+            .iter()
+            .map(|name| match members[name].as_slice() {
+                [process] => process.clone(),
+                processes => ProcessInfo::group(processes),
+            })
+            .collect();
+        self.apply_sort();
+
+        let groups = std::mem::take(&mut self.processes);
+        for group in groups {
+            let expanded = group.members.is_empty() || !self.expanded.contains(&group.name);
+            let name = group.name.clone();
+            self.processes.push(group);
+            if expanded {
+                continue;
+            }
+            let start = self.processes.len();
+            self.processes
+                .extend(members.remove(&name).unwrap_or_default());
+            self.sort_rows(start);
+            for process in &mut self.processes[start..] {
+                process.depth = 1;
+            }
+        }
+    }
+
+    pub fn toggle_tree_view(&mut self) {
+        self.tree_view = !self.tree_view;
+        self.grouped = false;
+        self.load_processes();
+    }
+
+    pub fn toggle_groups(&mut self) {
+        self.grouped = !self.grouped;
+        self.tree_view = false;
+        self.load_processes();
+    }
+
+    pub fn toggle_collapse(&mut self) {
+        let Some(process) = self.state.selected().and_then(|i| self.processes.get(i)) else {
+            return;
+        };
+
+        if self.grouped {
+            // from a process listed under its name as well, which then goes back to the name
+            let name = process.name.clone();
+            if !self.expanded.remove(&name) {
+                self.expanded.insert(name.clone());
+            }
+            self.load_processes();
+            if let Some(i) = self
+                .processes
+                .iter()
+                .position(|p| !p.members.is_empty() && p.name == name)
+            {
+                self.state.select(Some(i));
+            }
+            return;
+        }
+        if !self.tree_view || !process.has_children {
+            return;
+        }
+
+        let pid = process.pid;
+        if !self.collapsed.remove(&pid) {
+            self.collapsed.insert(pid);
+        }
+        self.load_processes();
+    }
+
+    // whether the selected row is folded, none when it has nothing to fold, a process listed
+    // under its name counts as its name's row
+    fn selected_fold(&self) -> Option<bool> {
+        let process = self.state.selected().and_then(|i| self.processes.get(i))?;
+        if self.grouped {
+            let is_group = !process.members.is_empty();
+            return (is_group || process.depth > 0).then(|| !self.expanded.contains(&process.name));
+        }
+        (self.tree_view && process.has_children).then(|| self.collapsed.contains(&process.pid))
+    }
+
+    // like in a file tree, Left folds an open subtree or name before going to the parent
+    pub fn collapse_or_select_parent(&mut self) -> Result<(), AppError> {
+        match self.selected_fold() {
+            Some(false) => {
+                self.toggle_collapse();
+                Ok(())
+            }
+            _ => self.select_parent(),
+        }
+    }
+
+    // and Right unfolds it before going to the first child
+    pub fn expand_or_select_child(&mut self) -> Result<(), AppError> {
+        match self.selected_fold() {
+            Some(true) => {
+                self.toggle_collapse();
+                Ok(())
+            }
+            _ => self.select_child(),
+        }
+    }
+
+    // folds every subtree down to the roots, or every name, and unfolds them all once any is folded
+    pub fn toggle_collapse_all(&mut self) {
+        if self.grouped {
+            if self.expanded.is_empty() {
+                let names = self.processes.iter().filter(|p| !p.members.is_empty());
+                self.expanded = names.map(|p| p.name.clone()).collect();
+            } else {
+                self.expanded.clear();
+            }
+        } else if self.tree_view {
+            if self.collapsed.is_empty() {
+                let processes = self.manager.processes();
+                self.collapsed = processes.iter().filter_map(|p| p.parent).collect();
+            } else {
+                self.collapsed.clear();
+            }
+        } else {
+            return;
+        }
+        self.load_processes();
+    }
+
+    pub fn on_tick(&mut self) {
+        self.refetch_process();
+        let result = match self.input_mode {
+            InputMode::Services => self.load_services(),
+            InputMode::OpenFiles => self.load_open_files(),
+            InputMode::Limits => self.load_limits(),
+            InputMode::Connections => self.load_connections(),
+            _ => Ok(()),
+        };
+        self.report(result);
+    }
+
+    pub fn increase_refresh_interval(&mut self) {
+        self.refresh_interval =
+            (self.refresh_interval + Duration::from_secs(1)).min(MAX_REFRESH_INTERVAL);
+    }
+
+    pub fn decrease_refresh_interval(&mut self) {
+        self.refresh_interval = self
+            .refresh_interval
+            .saturating_sub(Duration::from_secs(1))
+            .max(MIN_REFRESH_INTERVAL);
+    }
+
+    pub fn enter_input_mode(&mut self) {
+        self.input_mode = InputMode::Editing;
+    }
+
+    pub fn exit_input_mode(&mut self) {
+        self.search_history.reset();
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn matches_user(&self, process: &ProcessInfo) -> bool {
+        if self.only_my_processes && process.user_id != self.current_user {
+            return false;
+        }
+
+        match &self.user_filter {
+            Some(user) => &process.user == user,
+            None => true,
+        }
+    }
+
+    pub fn cycle_state_filter(&mut self) {
+        self.state_filter = self.state_filter.next();
+        self.load_processes();
+    }
+
+    pub fn toggle_only_my_processes(&mut self) {
+        self.only_my_processes = !self.only_my_processes;
+        self.load_processes();
+    }
+
+    // applies the search input and remembers it, unless it doesn't parse
+    pub fn search(&mut self) {
+        self.live_search_at = None;
+        if self.apply_search_input() {
+            let saved = self.search_history.push(&self.search_input);
+            self.report(saved.map_err(|err| AppError::SearchHistory(err.to_string())));
+        }
+    }
+
+    // filters the table by the search input, false when it doesn't parse
+    fn apply_search_input(&mut self) -> bool {
+        let (user, query) = split_user_filter(&self.search_input);
+        match SearchFilter::parse(query, self.config.search_case) {
+            Ok(mut filter) => {
+                filter.refresh();
+                self.user_filter = user;
+                self.search_filter = filter;
+                self.search_error = None;
+                self.load_processes();
+                // select the listener right away, so it is a single key away from being killed
+                if self.search_filter.is_port() && !self.processes.is_empty() {
+                    self.state.select(Some(0));
+                }
+                true
+            }
+            Err(err) => {
+                self.search_error = Some(err.to_string());
+                false
+            }
+        }
+    }
+
+    // the table follows the search input once typing pauses, without adding to the history
+    pub fn live_search(&mut self) {
+        if self.live_search_at.is_some_and(|at| at <= Instant::now()) {
+            self.live_search_at = None;
+            self.apply_search_input();
+        }
+    }
+
+    // replaces the input with an older query, `older` false goes back towards the newest
+    pub fn recall_search(&mut self, older: bool) {
+        let recalled = if older {
+            self.search_history.older(&self.search_input)
+        } else {
+            self.search_history.newer()
+        };
+        if let Some(query) = recalled {
+            self.search_input = query.to_string();
+            self.validate_search_input();
+        }
+    }
+
+    // called on every keystroke so a broken regex is reported while typing
+    pub fn validate_search_input(&mut self) {
+        let (_, query) = split_user_filter(&self.search_input);
+        self.search_error = SearchFilter::parse(query, self.config.search_case)
+            .err()
+            .map(|err| err.to_string());
+        self.live_search_at = Some(Instant::now() + LIVE_SEARCH_DELAY);
+    }
+}
+
+// the process of a row, or all the processes of a name's row in the grouped view
+fn row_pids(process: &ProcessInfo) -> Vec<Pid> {
+    if process.members.is_empty() {
+        vec![process.pid]
+    } else {
+        process.members.clone()
+    }
+}
+
+// the systemd service `pids` is the main process of, killing it may just get it restarted
+fn main_unit(pids: &[Pid]) -> Option<Unit> {
+    let [pid] = pids else {
+        return None;
+    };
+    let unit = process_cgroup(*pid).ok()?.unit()?;
+    (unit.main_pid() == Some(*pid)).then_some(unit)
+}
+
+// moves the selection of a pane by one row, wrapping around
+fn cycle_selection(state: &mut TableState, len: usize, step: isize) {
+    if len == 0 {
+        return;
+    }
+    let i = state
+        .selected()
+        .map_or(0, |i| (i as isize + step).rem_euclid(len as isize) as usize);
+    state.select(Some(i));
+}
+
+// `90s`, `30m` or `2h`, a bare number is minutes, up to MAX_DELAY
+fn parse_delay(input: &str) -> Option<Duration> {
+    let (number, unit) = match input.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => input.split_at(i),
+        None => (input, "m"),
+    };
+    let number: u64 = number.parse().ok()?;
+    let factor = match unit.trim() {
+        "s" | "sec" => 1,
+        "m" | "min" => 60,
+        "h" => 3600,
+        _ => return None,
+    };
+    let delay = Duration::from_secs(number.checked_mul(factor)?);
+    (!delay.is_zero() && delay <= MAX_DELAY).then_some(delay)
+}
+
+// moves the selection of a popup list by one row, wrapping around
+fn cycle_list_selection(state: &mut ListState, len: usize, step: isize) {
+    if len == 0 {
+        return;
+    }
+    let i = state
+        .selected()
+        .map_or(0, |i| (i as isize + step).rem_euclid(len as isize) as usize);
+    state.select(Some(i));
+}
+
+pub fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<()> {
+    let mut last_tick = Instant::now();
+    loop {
+        let next_expiry = app.expire_toasts();
+        terminal.draw(|f| ui(f, app))?;
+
+        // wait for input only until the next refresh is due, the spinner moves or a toast expires
+        let mut timeout = app
+            .refresh_interval
+            .checked_sub(last_tick.elapsed())
+            .unwrap_or(Duration::ZERO);
+        if app.is_refreshing() {
+            timeout = timeout.min(SPINNER_INTERVAL);
+        }
+        if let Some(expiry) = next_expiry {
+            timeout = timeout.min(expiry);
+        }
+        if app.kill_check.is_some() {
+            timeout = timeout.min(KILL_CHECK_INTERVAL);
+        }
+        if let Some(at) = app.live_search_at {
+            timeout = timeout.min(at.saturating_duration_since(Instant::now()));
+        }
+        if let Some(kill) = app.scheduled_kills.first() {
+            timeout = timeout.min(kill.at.saturating_duration_since(Instant::now()));
+        }
+
+        if event::poll(timeout)? {
+            match event::read()? {
+                Event::Key(key) => {
+                    handle_key(app, key);
+                    if app.should_quit {
+                        return Ok(());
+                    }
+                }
+                Event::Mouse(mouse) => handle_mouse(app, mouse),
+                // resizes the buffers so the next draw lays everything out for the new size
+                Event::Resize(_, _) => terminal.autoresize()?,
+                _ => {}
+            }
+
+            if let Some(pending) = app.elevation.take() {
+                let status = run_elevated(terminal, app.elevation_tool, &pending);
+                app.finish_elevation(pending, status);
+            }
+            if let Some((pid, dir)) = app.core_dump.take() {
+                let status = hand_over_terminal(terminal, || gcore(pid, &dir));
+                app.finish_core_dump(pid, &dir, status);
+            }
+        } else if app.is_refreshing() {
+            app.spinner += 1;
+        }
+
+        app.poll_refresh();
+        app.check_kills();
+        app.live_search();
+        app.run_scheduled_kills();
+
+        if last_tick.elapsed() >= app.refresh_interval {
+            app.on_tick();
+            last_tick = Instant::now();
+        }
+    }
+}
+
+// hands the terminal over so sudo can ask for a password, then takes it back
+fn run_elevated<B: Backend>(
+    terminal: &mut Terminal<B>,
+    tool: Option<&str>,
+    pending: &PendingSignal,
+) -> io::Result<ExitStatus> {
+    let tool = tool.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no sudo or pkexec"))?;
+    hand_over_terminal(terminal, || {
+        elevated_kill(tool, pending.signal, &pending.pids)
+    })
+}
+
+// runs a tool that writes to or reads from the terminal, then takes the terminal back
+fn hand_over_terminal<B: Backend>(
+    terminal: &mut Terminal<B>,
+    run: impl FnOnce() -> io::Result<ExitStatus>,
+) -> io::Result<ExitStatus> {
+    terminal.clear()?;
+    terminal.show_cursor()?;
+    disable_raw_mode()?;
+    let status = run();
+    enable_raw_mode()?;
+    terminal.clear()?;
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_delays() {
+        let secs = |secs| Some(Duration::from_secs(secs));
+        assert_eq!(parse_delay("90s"), secs(90));
+        assert_eq!(parse_delay("90 sec"), secs(90));
+        assert_eq!(parse_delay("30m"), secs(30 * 60));
+        assert_eq!(parse_delay("5min"), secs(5 * 60));
+        assert_eq!(parse_delay("2h"), secs(2 * 3600));
+        // a bare number is minutes
+        assert_eq!(parse_delay("15"), secs(15 * 60));
+        assert_eq!(parse_delay(""), None);
+        assert_eq!(parse_delay("0"), None);
+        assert_eq!(parse_delay("0s"), None);
+        assert_eq!(parse_delay("h"), None);
+        assert_eq!(parse_delay("-5m"), None);
+        assert_eq!(parse_delay("1.5h"), None);
+        assert_eq!(parse_delay("3d"), None);
+        assert_eq!(parse_delay("99999999999999999999h"), None);
+        assert_eq!(parse_delay("9999999999999999h"), None);
+        // would overflow the Instant it is added to
+        assert_eq!(parse_delay("18446744073709551615s"), None);
+        assert_eq!(parse_delay("8760h"), secs(365 * 24 * 3600));
+        assert_eq!(parse_delay("8761h"), None);
+    }
+}
@@ -0,0 +1,194 @@
+// an append only record of every signal sent, one JSON object per line, and/or an entry in the
+// syslog or the systemd journal
+use std::{
+    env,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+use sysinfo::{PidExt, Process, ProcessExt, Signal};
+
+use crate::process::signal_name;
+
+// a line of the audit log
+#[derive(Serialize)]
+struct AuditRecord<'a> {
+    // seconds since the epoch
+    timestamp: u64,
+    // who ran process_killer, the one behind sudo when run through it
+    user: &'a str,
+    pid: u32,
+    name: &'a str,
+    cmdline: &'a str,
+    signal: String,
+    // "sent", or why it failed
+    result: String,
+}
+
+// where else every signal is logged, for the host's auditing
+#[derive(Clone, Copy)]
+pub enum SystemLog {
+    Syslog,
+    // with the pid, name, signal and so on as fields of their own
+    Journal,
+}
+
+impl SystemLog {
+    pub(crate) const NAMES: [&'static str; 2] = ["syslog", "journal"];
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "syslog" => Some(SystemLog::Syslog),
+            "journal" => Some(SystemLog::Journal),
+            _ => None,
+        }
+    }
+}
+
+pub struct AuditLog {
+    // the JSON lines file
+    path: Option<PathBuf>,
+    system: Option<SystemLog>,
+    user: String,
+}
+
+impl AuditLog {
+    // none when nothing is to be logged
+    pub fn new(path: Option<PathBuf>, system: Option<SystemLog>) -> Option<Self> {
+        (path.is_some() || system.is_some()).then(|| Self {
+            path,
+            system,
+            user: invoking_user(),
+        })
+    }
+
+    pub fn record(
+        &self,
+        process: &Process,
+        signal: Signal,
+        result: &Result<(), String>,
+    ) -> io::Result<()> {
+        let record = AuditRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs()),
+            user: &self.user,
+            pid: process.pid().as_u32(),
+            name: process.name(),
+            cmdline: &process.cmd().join(" "),
+            signal: signal_name(signal),
+            result: match result {
+                Ok(()) => "sent".to_string(),
+                Err(err) => err.clone(),
+            },
+        };
+        // the file is still written when the system log can't be, and the other way around
+        let logged = match self.system {
+            Some(system) => system_log(system, &record),
+            None => Ok(()),
+        };
+        if let Some(path) = &self.path {
+            let mut line = serde_json::to_string(&record)?;
+            line.push('\n');
+
+            // a single write, so the lines of two instances sharing the log don't interleave
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            file.write_all(line.as_bytes())?;
+        }
+        logged
+    }
+}
+
+// e.g. `alice sent SIGTERM to 4242 node`
+fn describe(record: &AuditRecord) -> String {
+    let what = format!("{} to {} {}", record.signal, record.pid, record.name);
+    if record.result == "sent" {
+        format!("{} sent {}", record.user, what)
+    } else {
+        format!("{} failed to send {}: {}", record.user, what, record.result)
+    }
+}
+
+#[cfg(unix)]
+fn system_log(system: SystemLog, record: &AuditRecord) -> io::Result<()> {
+    use std::os::unix::net::UnixDatagram;
+
+    // notice, a failed signal is a warning
+    let priority = if record.result == "sent" { 5 } else { 4 };
+    let socket = UnixDatagram::unbound()?;
+    match system {
+        SystemLog::Syslog => {
+            // the auth facility, where the host's auditing looks for what operators did
+            let message = format!(
+                "<{}>process_killer[{}]: {} (cmdline: {})",
+                4 * 8 + priority,
+                std::process::id(),
+                describe(record),
+                record.cmdline
+            );
+            let path = if cfg!(target_os = "macos") {
+                "/var/run/syslog"
+            } else {
+                "/dev/log"
+            };
+            socket.send_to(message.as_bytes(), path)?;
+        }
+        SystemLog::Journal => {
+            let fields = [
+                ("MESSAGE", describe(record)),
+                ("PRIORITY", priority.to_string()),
+                ("SYSLOG_IDENTIFIER", "process_killer".to_string()),
+                ("OPERATOR", record.user.to_string()),
+                ("SIGNAL", record.signal.clone()),
+                ("TARGET_PID", record.pid.to_string()),
+                ("TARGET_NAME", record.name.to_string()),
+                ("TARGET_CMDLINE", record.cmdline.to_string()),
+                ("RESULT", record.result.clone()),
+            ];
+            // the journal's native protocol, a value with a newline is sent with its length
+            let mut message = vec![];
+            for (field, value) in fields {
+                message.extend_from_slice(field.as_bytes());
+                if value.contains('\n') {
+                    message.push(b'\n');
+                    message.extend_from_slice(&(value.len() as u64).to_le_bytes());
+                } else {
+                    message.push(b'=');
+                }
+                message.extend_from_slice(value.as_bytes());
+                message.push(b'\n');
+            }
+            socket.send_to(&message, "/run/systemd/journal/socket")?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn system_log(_system: SystemLog, _record: &AuditRecord) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "syslog and the journal are only available on unix",
+    ))
+}
+
+fn invoking_user() -> String {
+    ["SUDO_USER", "USER", "USERNAME"]
+        .iter()
+        .find_map(|name| env::var(name).ok().filter(|user| !user.is_empty()))
+        .unwrap_or_else(fallback_user)
+}
+
+// the uid, e.g. under cron where no user variable is set
+#[cfg(unix)]
+fn fallback_user() -> String {
+    unsafe { libc::getuid() }.to_string()
+}
+
+#[cfg(not(unix))]
+fn fallback_user() -> String {
+    "unknown".to_string()
+}
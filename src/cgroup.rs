@@ -0,0 +1,125 @@
+// the cgroup a process runs in, it tells containers and the systemd slices apart
+use std::{fs, io};
+
+use sysinfo::{Pid, PidExt};
+
+use crate::systemd::Unit;
+
+// the scope name prefixes container runtimes use, e.g. `docker-<id>.scope`
+const RUNTIMES: [(&str, &str); 4] = [
+    ("docker-", "docker"),
+    ("libpod-", "podman"),
+    ("crio-", "cri-o"),
+    ("cri-containerd-", "containerd"),
+];
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cgroup {
+    // e.g. `/system.slice/docker-<id>.scope` or `/user.slice/user-1000.slice/session-2.scope`
+    pub path: String,
+}
+
+#[derive(Clone)]
+pub struct Container {
+    pub runtime: &'static str,
+    // the full 64 character id
+    pub id: String,
+}
+
+impl Container {
+    // the 12 characters `docker ps` shows
+    pub fn short_id(&self) -> &str {
+        &self.id[..12]
+    }
+}
+
+impl Cgroup {
+    // the container the process runs in, from the scope or directory its runtime created
+    pub fn container(&self) -> Option<Container> {
+        let components: Vec<&str> = self.path.split('/').filter(|c| !c.is_empty()).collect();
+        for (i, component) in components.iter().enumerate().rev() {
+            let name = component.strip_suffix(".scope").unwrap_or(component);
+            for (prefix, runtime) in RUNTIMES {
+                if let Some(id) = name.strip_prefix(prefix).filter(|id| is_container_id(id)) {
+                    return Some(Container {
+                        runtime,
+                        id: id.to_string(),
+                    });
+                }
+            }
+            // the cgroupfs driver uses plain `/docker/<id>`, kubernetes `/kubepods/.../<id>`
+            if is_container_id(name) {
+                let runtime = if i > 0 && components[i - 1] == "docker" {
+                    "docker"
+                } else {
+                    "container"
+                };
+                return Some(Container {
+                    runtime,
+                    id: name.to_string(),
+                });
+            }
+        }
+        None
+    }
+
+    // the innermost service or scope, units below `user@<uid>.service` belong to the user's manager
+    pub fn unit(&self) -> Option<Unit> {
+        let components: Vec<&str> = self.path.split('/').filter(|c| !c.is_empty()).collect();
+        let i = components
+            .iter()
+            .rposition(|c| c.ends_with(".service") || c.ends_with(".scope"))?;
+        let user = components[..i]
+            .iter()
+            .any(|c| c.starts_with("user@") && c.ends_with(".service"));
+        Some(Unit {
+            name: components[i].to_string(),
+            user,
+        })
+    }
+
+    // what the table shows, `docker:<short id>` or the innermost slice, scope or service
+    pub fn label(&self) -> String {
+        if let Some(container) = self.container() {
+            return format!("{}:{}", container.runtime, container.short_id());
+        }
+        match self.path.rsplit('/').find(|c| !c.is_empty()) {
+            Some(name) => name.to_string(),
+            None => "/".to_string(),
+        }
+    }
+}
+
+fn is_container_id(id: &str) -> bool {
+    id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+// reads /proc/<pid>/cgroup, on a hybrid setup the systemd hierarchy is used
+pub fn process_cgroup(pid: Pid) -> io::Result<Cgroup> {
+    if cfg!(not(target_os = "linux")) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "cgroups are only available on linux",
+        ));
+    }
+
+    let content = fs::read_to_string(format!("/proc/{}/cgroup", pid.as_u32()))?;
+    // `<id>:<controllers>:<path>`, the unified hierarchy has id 0 and no controllers
+    let lines: Vec<(&str, &str)> = content
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(':')?;
+            rest.split_once(':')
+        })
+        .collect();
+    let (_, path) = lines
+        .iter()
+        .find(|(controllers, _)| *controllers == "name=systemd")
+        .or_else(|| lines.iter().find(|(controllers, _)| controllers.is_empty()))
+        .or_else(|| lines.first())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "empty cgroup file"))?;
+
+    Ok(Cgroup {
+        path: path.to_string(),
+    })
+}
@@ -0,0 +1,153 @@
+// `process_killer daemon` keeps sampling the cpu usage of every process in the background, the TUI
+// asks it over a unix socket on start so the CPU history of the details view is there right away.
+// only the history comes from here, the TUI still scans the processes for its table itself
+use std::{
+    collections::{HashMap, VecDeque},
+    env, io,
+    path::PathBuf,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+// as many samples as the TUI keeps
+const HISTORY_LEN: usize = 120;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Sample {
+    pub(crate) pid: u32,
+    // tells the process apart from a later one that got its pid
+    pub(crate) start_time: u64,
+    // oldest first
+    pub(crate) cpu: Vec<f32>,
+}
+
+// what a client is sent, then the connection is closed
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Snapshot {
+    pub(crate) interval_secs: u64,
+    pub(crate) processes: Vec<Sample>,
+}
+
+// by pid and start time
+type History = HashMap<(u32, u64), VecDeque<f32>>;
+
+// in the runtime directory, or the state directory without one
+pub fn socket_path() -> Option<PathBuf> {
+    let dir = env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))?;
+    Some(dir.join("process_killer").join("collector.sock"))
+}
+
+#[cfg(unix)]
+pub fn run_daemon(interval: Duration) -> io::Result<()> {
+    use std::{
+        fs,
+        io::Write,
+        os::unix::net::{UnixListener, UnixStream},
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    use sysinfo::{PidExt, ProcessExt, SystemExt};
+
+    use crate::ProcessManager;
+
+    let path = socket_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no runtime directory found"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // a socket left behind by a daemon that was killed is taken over
+    if path.exists() {
+        if UnixStream::connect(&path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("a collector already runs on {}", path.display()),
+            ));
+        }
+        fs::remove_file(&path)?;
+    }
+    let listener = UnixListener::bind(&path)?;
+    println!(
+        "collecting every {}s on {}, Ctrl-C to stop",
+        interval.as_secs(),
+        path.display()
+    );
+
+    let history = Arc::new(Mutex::new(History::new()));
+    let shared = Arc::clone(&history);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            let snapshot = {
+                let history = shared.lock().unwrap_or_else(|err| err.into_inner());
+                Snapshot {
+                    interval_secs: interval.as_secs(),
+                    processes: history
+                        .iter()
+                        .map(|((pid, start_time), cpu)| Sample {
+                            pid: *pid,
+                            start_time: *start_time,
+                            cpu: cpu.iter().copied().collect(),
+                        })
+                        .collect(),
+                }
+            };
+            // the client gave up waiting, it does without the history
+            if let Ok(json) = serde_json::to_vec(&snapshot) {
+                let _ = stream.write_all(&json);
+            }
+        }
+    });
+
+    let mut manager = ProcessManager::new();
+    loop {
+        thread::sleep(interval);
+        manager.refresh();
+        let processes = manager.system().processes();
+        let mut history = history.lock().unwrap_or_else(|err| err.into_inner());
+        history.retain(|(pid, start_time), _| {
+            processes
+                .get(&sysinfo::Pid::from_u32(*pid))
+                .is_some_and(|process| process.start_time() == *start_time)
+        });
+        for (pid, process) in processes {
+            let samples = history
+                .entry((pid.as_u32(), process.start_time()))
+                .or_default();
+            if samples.len() == HISTORY_LEN {
+                samples.pop_front();
+            }
+            samples.push_back(process.cpu_usage());
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn run_daemon(_interval: Duration) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the collector is only available on unix",
+    ))
+}
+
+// none when no daemon runs, the TUI then starts from scratch
+#[cfg(unix)]
+pub(crate) fn fetch() -> Option<Snapshot> {
+    use std::{io::Read, os::unix::net::UnixStream};
+
+    let mut stream = UnixStream::connect(socket_path()?).ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(1))).ok()?;
+    let mut json = vec![];
+    stream.read_to_end(&mut json).ok()?;
+    serde_json::from_slice(&json).ok()
+}
+
+#[cfg(not(unix))]
+pub(crate) fn fetch() -> Option<Snapshot> {
+    None
+}
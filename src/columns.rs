@@ -0,0 +1,138 @@
+// the columns of the process table, which ones are shown and in what order is configurable
+use tui::layout::Constraint;
+
+use crate::process::SortColumn;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Column {
+    // the row number
+    Index,
+    Pid,
+    Ppid,
+    Cpu,
+    Memory,
+    DiskRead,
+    DiskWrite,
+    GpuMemory,
+    GpuUsage,
+    Threads,
+    User,
+    Cgroup,
+    Nice,
+    State,
+    Age,
+    // the name, or the command line while it is toggled on
+    Name,
+}
+
+impl Column {
+    // the default order, every column is shown by default
+    pub(crate) const ALL: [Column; 16] = [
+        Column::Index,
+        Column::Pid,
+        Column::Ppid,
+        Column::Cpu,
+        Column::Memory,
+        Column::DiskRead,
+        Column::DiskWrite,
+        Column::GpuMemory,
+        Column::GpuUsage,
+        Column::Threads,
+        Column::User,
+        Column::Cgroup,
+        Column::Nice,
+        Column::State,
+        Column::Age,
+        Column::Name,
+    ];
+
+    // the key used for the column in the config file
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Column::Index => "index",
+            Column::Pid => "pid",
+            Column::Ppid => "ppid",
+            Column::Cpu => "cpu",
+            Column::Memory => "memory",
+            Column::DiskRead => "disk_read",
+            Column::DiskWrite => "disk_write",
+            Column::GpuMemory => "gpu_memory",
+            Column::GpuUsage => "gpu_usage",
+            Column::Threads => "threads",
+            Column::User => "user",
+            Column::Cgroup => "cgroup",
+            Column::Nice => "nice",
+            Column::State => "state",
+            Column::Age => "age",
+            Column::Name => "name",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Column> {
+        Column::ALL.into_iter().find(|column| column.name() == name)
+    }
+
+    // the sort the header shows an arrow for, none for the ones the table can't be sorted by
+    pub(crate) fn sort_column(&self) -> Option<SortColumn> {
+        match self {
+            Column::Index | Column::State => None,
+            Column::Pid => Some(SortColumn::Pid),
+            Column::Ppid => Some(SortColumn::Ppid),
+            Column::Cpu => Some(SortColumn::Cpu),
+            Column::Memory => Some(SortColumn::Memory),
+            Column::DiskRead => Some(SortColumn::DiskRead),
+            Column::DiskWrite => Some(SortColumn::DiskWrite),
+            Column::GpuMemory => Some(SortColumn::GpuMemory),
+            Column::GpuUsage => Some(SortColumn::GpuUsage),
+            Column::Threads => Some(SortColumn::Threads),
+            Column::User => Some(SortColumn::User),
+            Column::Cgroup => Some(SortColumn::Cgroup),
+            Column::Nice => Some(SortColumn::Nice),
+            Column::Age => Some(SortColumn::Age),
+            Column::Name => Some(SortColumn::Name),
+        }
+    }
+
+    pub(crate) fn title(&self) -> &'static str {
+        match self {
+            Column::Index => "S.N.",
+            Column::State => "State",
+            column => column.sort_column().map_or("", |sort| sort.title()),
+        }
+    }
+
+    pub(crate) fn width(&self) -> Constraint {
+        match self {
+            Column::Index => Constraint::Percentage(10),
+            Column::Pid => Constraint::Length(20),
+            Column::Ppid | Column::Cpu | Column::Threads | Column::Age => Constraint::Length(8),
+            Column::Memory | Column::GpuMemory | Column::State => Constraint::Length(10),
+            Column::DiskRead | Column::DiskWrite | Column::User => Constraint::Length(12),
+            Column::GpuUsage | Column::Nice => Constraint::Length(6),
+            Column::Cgroup => Constraint::Length(20),
+            Column::Name => Constraint::Min(70),
+        }
+    }
+
+    // only shown when there is a gpu to report on
+    pub(crate) fn is_gpu(&self) -> bool {
+        matches!(self, Column::GpuMemory | Column::GpuUsage)
+    }
+}
+
+// every column in display order, with whether it is shown, from the names in the config
+pub(crate) fn column_layout(shown: Option<&[Column]>) -> Vec<(Column, bool)> {
+    let Some(shown) = shown else {
+        return Column::ALL.iter().map(|column| (*column, true)).collect();
+    };
+
+    // the hidden ones follow in their default order, so they can be turned on again
+    let mut layout: Vec<(Column, bool)> = shown.iter().map(|column| (*column, true)).collect();
+    layout.extend(
+        Column::ALL
+            .iter()
+            .filter(|column| !shown.contains(column))
+            .map(|column| (*column, false)),
+    );
+    layout
+}
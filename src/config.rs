@@ -0,0 +1,681 @@
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use regex::Regex;
+use serde::Deserialize;
+use sysinfo::{PidExt, Process, ProcessExt, Signal};
+use tui::style::{Color, Modifier, Style};
+
+use crate::{
+    audit::{AuditLog, SystemLog},
+    columns::Column,
+    filter_expr::parse_memory,
+    input::{parse_key, Action, KeyMap},
+    process::{parse_signal, SearchCase, SortColumn, SortOrder},
+    rules::{Alert, Limit, Rule},
+    search_history::default_history_path,
+};
+
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub(crate) header: Style,
+    pub(crate) selection: Style,
+    // marked rows, the search input while typing and other things that need attention
+    pub(crate) highlight: Style,
+    // zombies, errors and the kill confirmation
+    pub(crate) warning: Style,
+    // the instructions and the status bar
+    pub(crate) status: Style,
+    // labels and headings
+    pub(crate) accent: Style,
+}
+
+impl Theme {
+    const BUILT_IN: [&'static str; 4] = ["dark", "light", "solarized", "monochrome"];
+
+    fn built_in(name: &str) -> Option<Theme> {
+        let fg = |color| Style::default().fg(color);
+
+        let theme = match name {
+            "dark" => Theme {
+                header: fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                selection: Style::default().bg(Color::Red),
+                highlight: fg(Color::Yellow),
+                warning: fg(Color::Magenta),
+                status: fg(Color::Green),
+                accent: fg(Color::Cyan),
+            },
+            "light" => Theme {
+                header: fg(Color::Blue).add_modifier(Modifier::BOLD),
+                selection: Style::default().bg(Color::LightBlue).fg(Color::Black),
+                highlight: fg(Color::Rgb(175, 95, 0)),
+                warning: fg(Color::Red),
+                status: fg(Color::Rgb(0, 95, 0)),
+                accent: fg(Color::Blue),
+            },
+            "solarized" => Theme {
+                header: fg(Color::Rgb(38, 139, 210)).add_modifier(Modifier::BOLD),
+                selection: Style::default()
+                    .bg(Color::Rgb(7, 54, 66))
+                    .fg(Color::Rgb(238, 232, 213)),
+                highlight: fg(Color::Rgb(181, 137, 0)),
+                warning: fg(Color::Rgb(211, 54, 130)),
+                status: fg(Color::Rgb(133, 153, 0)),
+                accent: fg(Color::Rgb(42, 161, 152)),
+            },
+            "monochrome" => Theme {
+                header: Style::default().add_modifier(Modifier::BOLD),
+                selection: Style::default().add_modifier(Modifier::REVERSED),
+                highlight: Style::default().add_modifier(Modifier::BOLD),
+                warning: Style::default().add_modifier(Modifier::UNDERLINED),
+                status: Style::default(),
+                accent: Style::default().add_modifier(Modifier::BOLD),
+            },
+            _ => return None,
+        };
+
+        Some(theme)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::built_in("dark").unwrap()
+    }
+}
+
+// a user defined theme from the config file, unset colors come from `base`
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeFile {
+    base: Option<String>,
+    header: Option<String>,
+    selection: Option<String>,
+    highlight: Option<String>,
+    warning: Option<String>,
+    status: Option<String>,
+    accent: Option<String>,
+}
+
+impl ThemeFile {
+    fn resolve(&self) -> Result<Theme, String> {
+        let base = self.base.as_deref().unwrap_or("dark");
+        let mut theme =
+            Theme::built_in(base).ok_or_else(|| format!("unknown base theme '{}'", base))?;
+
+        let color = |value: &Option<String>| -> Result<Option<Color>, String> {
+            value
+                .as_deref()
+                .map(|value| parse_color(value).ok_or_else(|| format!("unknown color '{}'", value)))
+                .transpose()
+        };
+
+        // colors replace the foreground, except for the selection which is a background
+        if let Some(c) = color(&self.header)? {
+            theme.header = theme.header.fg(c);
+        }
+        if let Some(c) = color(&self.selection)? {
+            theme.selection = Style::default().bg(c);
+        }
+        if let Some(c) = color(&self.highlight)? {
+            theme.highlight = Style::default().fg(c);
+        }
+        if let Some(c) = color(&self.warning)? {
+            theme.warning = Style::default().fg(c);
+        }
+        if let Some(c) = color(&self.status)? {
+            theme.status = Style::default().fg(c);
+        }
+        if let Some(c) = color(&self.accent)? {
+            theme.accent = Style::default().fg(c);
+        }
+
+        Ok(theme)
+    }
+}
+
+// a named terminal color ("red", "lightblue", ...) or "#rrggbb"
+fn parse_color(value: &str) -> Option<Color> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+
+    let color = match value.to_lowercase().replace(['_', '-', ' '], "").as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    };
+
+    Some(color)
+}
+
+// an entry of the protect list, a pid or an exact process name
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Protected {
+    Pid(u32),
+    Name(String),
+}
+
+pub struct Config {
+    // ask for y/n before any signal is sent
+    pub confirm_kill: bool,
+    // signals, and the containers, units and services stopped instead, are only logged
+    pub dry_run: bool,
+    // nothing is signalled, reniced, started or stopped, the TUI is only a monitor
+    pub read_only: bool,
+    // started by --host on another machine, the table's title then starts with our hostname
+    pub show_host: bool,
+    pub(crate) keymap: KeyMap,
+    pub(crate) theme: Theme,
+    // processes that are never signalled
+    protected: Vec<Protected>,
+    // name patterns kept at the top of the table
+    pub(crate) pinned: Vec<String>,
+    // how long a terminated process gets to exit before SIGKILL is offered, zero to not check
+    pub(crate) kill_grace_period: Duration,
+    // how long a polite kill waits for SIGTERM before sending SIGKILL
+    pub(crate) polite_kill_timeout: Duration,
+    // every signal sent is appended to the file and/or the system log when set
+    pub audit_log: Option<AuditLog>,
+    // where the search history is kept across sessions, none to forget it on quit
+    pub(crate) search_history: Option<PathBuf>,
+    // where `E` writes the table, none when there is no data directory either
+    pub(crate) export_dir: Option<PathBuf>,
+    // named search inputs offered by the filter picker, by name
+    pub(crate) filters: Vec<(String, String)>,
+    // the table columns shown, in order, none for all of them
+    pub(crate) columns: Option<Vec<Column>>,
+    pub(crate) search_case: SearchCase,
+    // breaks the ties of the sort column, before the name and pid
+    pub(crate) secondary_sort: Option<SortColumn>,
+    pub startup: Startup,
+    // processes signalled on their own once over a limit for long enough
+    pub(crate) rules: Vec<Rule>,
+    // processes over a limit for long enough are shown in a popup
+    pub(crate) alerts: Vec<Alert>,
+    // the processes exported by --metrics-port, all of them when empty
+    pub(crate) metrics: Vec<Regex>,
+    // the bearer token of `process_killer serve`
+    pub api_token: Option<String>,
+    // the file read, the column chooser writes its choice back to it
+    path: Option<PathBuf>,
+}
+
+impl Config {
+    pub fn is_protected(&self, process: &Process) -> bool {
+        self.protected.iter().any(|entry| match entry {
+            Protected::Pid(pid) => process.pid().as_u32() == *pid,
+            Protected::Name(name) => process.name() == name,
+        })
+    }
+}
+
+fn parse_sort_column(name: &str) -> Result<SortColumn, String> {
+    SortColumn::from_name(name).ok_or_else(|| {
+        format!(
+            "unknown sort column '{}', expected one of {}",
+            name,
+            SortColumn::names().join(", ")
+        )
+    })
+}
+
+// how the TUI opens, from [startup] in the config and the command line flags
+#[derive(Default)]
+pub struct Startup {
+    // none for the name order
+    pub sort: Option<(SortColumn, SortOrder)>,
+    // a search input applied right away
+    pub filter: Option<String>,
+    pub tree_view: bool,
+    pub show_details: bool,
+    pub show_command_line: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct StartupFile {
+    sort: Option<String>,
+    // "asc" or "desc"
+    sort_order: Option<String>,
+    filter: Option<String>,
+    tree: bool,
+    details: bool,
+    command_line: bool,
+}
+
+impl StartupFile {
+    fn resolve(self) -> Result<Startup, String> {
+        let order = match self.sort_order.as_deref() {
+            None => SortOrder::Asc,
+            Some(name) => SortOrder::from_name(name)
+                .ok_or_else(|| format!("unknown sort_order '{}', expected asc or desc", name))?,
+        };
+        let sort = self.sort.as_deref().map(parse_sort_column).transpose()?;
+
+        Ok(Startup {
+            sort: sort.map(|column| (column, order)),
+            filter: self.filter,
+            tree_view: self.tree,
+            show_details: self.details,
+            show_command_line: self.command_line,
+        })
+    }
+}
+
+// the thresholds of a [[rules]] or [[alerts]] entry, `kind` tells which in the errors
+fn parse_limit(
+    kind: &str,
+    name: Option<String>,
+    pattern: Option<String>,
+    cpu_above: Option<f32>,
+    memory_above: Option<&str>,
+    duration: u64,
+) -> Result<Limit, String> {
+    let name = name
+        .or_else(|| pattern.clone())
+        .unwrap_or_else(|| "any process".to_string());
+    let pattern = pattern
+        .map(|pattern| {
+            Regex::new(&pattern)
+                .map_err(|err| format!("invalid match of {} '{}': {}", kind, name, err))
+        })
+        .transpose()?;
+    let memory_above = memory_above
+        .map(|value| {
+            parse_memory(value)
+                .ok_or_else(|| format!("invalid memory_above '{}' of {} '{}'", value, kind, name))
+        })
+        .transpose()?;
+    if cpu_above.is_none() && memory_above.is_none() {
+        return Err(format!(
+            "{} '{}' needs cpu_above or memory_above",
+            kind, name
+        ));
+    }
+
+    Ok(Limit {
+        name,
+        pattern,
+        cpu_above,
+        memory_above,
+        duration: Duration::from_secs(duration),
+    })
+}
+
+// a [[rules]] entry, e.g. `match = "chrome.*renderer"`, `memory_above = "2G"`, `for = 60`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleFile {
+    name: Option<String>,
+    #[serde(rename = "match")]
+    pattern: String,
+    // in percent
+    cpu_above: Option<f32>,
+    // a size like "2G" or "512M"
+    memory_above: Option<String>,
+    // in seconds
+    #[serde(rename = "for", default)]
+    duration: u64,
+    // SIGTERM when unset
+    signal: Option<String>,
+}
+
+impl RuleFile {
+    fn resolve(self) -> Result<Rule, String> {
+        let limit = parse_limit(
+            "rule",
+            self.name,
+            Some(self.pattern),
+            self.cpu_above,
+            self.memory_above.as_deref(),
+            self.duration,
+        )?;
+        let signal = match self.signal.as_deref() {
+            None => Signal::Term,
+            Some(signal) => parse_signal(signal)
+                .ok_or_else(|| format!("unknown signal '{}' of rule '{}'", signal, limit.name))?,
+        };
+        Ok(Rule { limit, signal })
+    }
+}
+
+// an [[alerts]] entry, like a rule without the signal and with a match that is optional
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AlertFile {
+    name: Option<String>,
+    #[serde(rename = "match")]
+    pattern: Option<String>,
+    cpu_above: Option<f32>,
+    memory_above: Option<String>,
+    #[serde(rename = "for", default)]
+    duration: u64,
+    // through notify-send, or osascript on macOS
+    #[serde(default)]
+    desktop: bool,
+}
+
+impl AlertFile {
+    fn resolve(self) -> Result<Alert, String> {
+        let limit = parse_limit(
+            "alert",
+            self.name,
+            self.pattern,
+            self.cpu_above,
+            self.memory_above.as_deref(),
+            self.duration,
+        )?;
+        Ok(Alert {
+            limit,
+            desktop: self.desktop,
+        })
+    }
+}
+
+// the on disk format of the config file, everything is optional
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    confirm_kill: Option<bool>,
+    dry_run: bool,
+    read_only: bool,
+    // one of the built in themes or a key of `themes`
+    theme: Option<String>,
+    themes: HashMap<String, ThemeFile>,
+    protected: Vec<Protected>,
+    pinned: Vec<String>,
+    // in seconds
+    kill_grace_period: Option<u64>,
+    // in seconds
+    polite_kill_timeout: Option<u64>,
+    audit_log: Option<PathBuf>,
+    // "syslog" or "journal"
+    system_log: Option<String>,
+    save_search_history: Option<bool>,
+    export_dir: Option<PathBuf>,
+    // name to a search input, e.g. `dev = "/node|cargo|webpack"`
+    filters: HashMap<String, String>,
+    columns: Option<Vec<String>>,
+    // "smart", "insensitive" or "sensitive"
+    search_case: Option<String>,
+    secondary_sort: Option<String>,
+    startup: StartupFile,
+    rules: Vec<RuleFile>,
+    alerts: Vec<AlertFile>,
+    // regexes tried on the name and the command line
+    metrics: Vec<String>,
+    api_token: Option<String>,
+    // action name to one key or a list of keys
+    keys: HashMap<String, KeyList>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(key) => vec![key],
+            KeyList::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+// ~/.config/process_killer/config.toml, or the platform equivalent
+fn default_config_path() -> Option<PathBuf> {
+    let config_dir = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+
+    config_dir.map(|dir| dir.join("process_killer").join("config.toml"))
+}
+
+// ~/.local/share/process_killer, or the platform equivalent, for the files written on request
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    let data_dir = if cfg!(windows) {
+        env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+
+    data_dir.map(|dir| dir.join("process_killer"))
+}
+
+impl Config {
+    // reads the config file at `path`, or at the default location
+    pub fn load(path: Option<PathBuf>) -> Result<Self, Box<dyn Error>> {
+        let path = path.or_else(default_config_path);
+        let file: ConfigFile = match &path {
+            Some(path) if path.exists() => {
+                let content = fs::read_to_string(path)?;
+                toml::from_str(&content)
+                    .map_err(|err| format!("invalid config {}: {}", path.display(), err))?
+            }
+            _ => ConfigFile::default(),
+        };
+
+        let mut keymap = KeyMap::default();
+        for (name, keys) in &file.keys {
+            let action =
+                Action::from_name(name).ok_or_else(|| format!("unknown action '{}'", name))?;
+            let codes = keys
+                .keys()
+                .into_iter()
+                .map(|key| parse_key(key).ok_or_else(|| format!("unknown key '{}'", key)))
+                .collect::<Result<Vec<_>, _>>()?;
+            keymap.bind(action, &codes);
+        }
+
+        let theme = match file.theme.as_deref() {
+            None => Theme::default(),
+            Some(name) => match file.themes.get(name) {
+                Some(custom) => custom.resolve()?,
+                None => Theme::built_in(name).ok_or_else(|| {
+                    format!(
+                        "unknown theme '{}', expected one of {} or a [themes.{}] table",
+                        name,
+                        Theme::BUILT_IN.join(", "),
+                        name
+                    )
+                })?,
+            },
+        };
+
+        let mut filters: Vec<(String, String)> = file.filters.into_iter().collect();
+        filters.sort();
+
+        let columns = file
+            .columns
+            .map(|names| {
+                names
+                    .iter()
+                    .map(|name| {
+                        Column::from_name(name).ok_or_else(|| format!("unknown column '{}'", name))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+
+        let system_log = match file.system_log.as_deref() {
+            None => None,
+            Some(name) => Some(SystemLog::from_name(name).ok_or_else(|| {
+                format!(
+                    "unknown system_log '{}', expected one of {}",
+                    name,
+                    SystemLog::NAMES.join(", ")
+                )
+            })?),
+        };
+
+        let search_case = match file.search_case.as_deref() {
+            None => SearchCase::default(),
+            Some(name) => SearchCase::from_name(name).ok_or_else(|| {
+                format!(
+                    "unknown search_case '{}', expected one of {}",
+                    name,
+                    SearchCase::NAMES.join(", ")
+                )
+            })?,
+        };
+
+        let startup = file.startup.resolve()?;
+        let rules = file
+            .rules
+            .into_iter()
+            .map(RuleFile::resolve)
+            .collect::<Result<Vec<_>, _>>()?;
+        let metrics = file
+            .metrics
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .map_err(|err| format!("invalid metrics pattern '{}': {}", pattern, err))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let alerts = file
+            .alerts
+            .into_iter()
+            .map(AlertFile::resolve)
+            .collect::<Result<Vec<_>, _>>()?;
+        let secondary_sort = file
+            .secondary_sort
+            .as_deref()
+            .map(parse_sort_column)
+            .transpose()?;
+
+        Ok(Self {
+            confirm_kill: file.confirm_kill.unwrap_or(true),
+            dry_run: file.dry_run,
+            read_only: file.read_only,
+            show_host: false,
+            keymap,
+            theme,
+            protected: file.protected,
+            pinned: file.pinned,
+            kill_grace_period: Duration::from_secs(file.kill_grace_period.unwrap_or(3)),
+            polite_kill_timeout: Duration::from_secs(file.polite_kill_timeout.unwrap_or(5)),
+            audit_log: AuditLog::new(file.audit_log, system_log),
+            search_history: file
+                .save_search_history
+                .unwrap_or(false)
+                .then(default_history_path)
+                .flatten(),
+            export_dir: file
+                .export_dir
+                .or_else(|| data_dir().map(|dir| dir.join("exports"))),
+            filters,
+            columns,
+            search_case,
+            secondary_sort,
+            startup,
+            rules,
+            alerts,
+            metrics,
+            api_token: file.api_token,
+            path,
+        })
+    }
+
+    // writes `columns = [...]` to the config file, replacing the line that was there
+    pub(crate) fn save_columns(&mut self, columns: Vec<Column>) -> io::Result<()> {
+        let path = self
+            .path
+            .as_deref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory found"))?;
+        let names: Vec<String> = columns
+            .iter()
+            .map(|column| format!("\"{}\"", column.name()))
+            .collect();
+        let line = format!("columns = [{}]", names.join(", "));
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+        write_top_level(path, &content, "columns", &line)?;
+        self.columns = Some(columns);
+        Ok(())
+    }
+}
+
+// replaces the (possibly multi line) top level `key = ...` in the file, or adds it at the top,
+// so the rest of the file and its comments are left alone
+fn write_top_level(path: &Path, content: &str, key: &str, line: &str) -> io::Result<()> {
+    let lines: Vec<&str> = content.lines().collect();
+    // top level keys end at the first table
+    let end = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .unwrap_or(lines.len());
+    let start = lines[..end].iter().position(|l| {
+        l.trim_start()
+            .strip_prefix(key)
+            .is_some_and(|rest| rest.trim_start().starts_with('='))
+    });
+
+    let mut updated: Vec<&str> = vec![];
+    match start {
+        Some(start) => {
+            // an array may go on over the next lines until its brackets are balanced
+            let mut depth = 0;
+            let mut stop = start;
+            for (i, l) in lines.iter().enumerate().skip(start) {
+                depth += l.matches('[').count() as isize - l.matches(']').count() as isize;
+                stop = i;
+                if depth <= 0 {
+                    break;
+                }
+            }
+            updated.extend(&lines[..start]);
+            updated.push(line);
+            updated.extend(&lines[stop + 1..]);
+        }
+        None => {
+            updated.push(line);
+            updated.extend(&lines);
+        }
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, updated.join("\n") + "\n")
+}
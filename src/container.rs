@@ -0,0 +1,172 @@
+// docker and podman containers, stopped through their engine so the restart policy and
+// the rest of the container are taken care of
+use std::io;
+
+use crate::{cgroup::Container, process::in_path};
+
+#[derive(Clone, Copy, Debug)]
+pub enum ContainerControl {
+    Stop,
+    Kill,
+}
+
+impl ContainerControl {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContainerControl::Stop => "stop",
+            ContainerControl::Kill => "kill",
+        }
+    }
+}
+
+// the name `docker ps` shows, without the leading slash the api puts in front of it
+pub fn container_name(container: &Container) -> io::Result<String> {
+    let body = match api_request(
+        container,
+        "GET",
+        &format!("/containers/{}/json", container.id),
+    ) {
+        Ok(body) => body,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let output = cli(
+                container,
+                &["inspect", "--format", "{{.Name}}", &container.id],
+            )?;
+            return Ok(output.trim().trim_start_matches('/').to_string());
+        }
+        Err(err) => return Err(err),
+    };
+
+    let json: serde_json::Value = serde_json::from_str(&body)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    json["Name"]
+        .as_str()
+        .map(|name| name.trim_start_matches('/').to_string())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no name in the response"))
+}
+
+// blocks until the engine is done, a stop waits up to 10 seconds before it kills
+pub fn control_container(container: &Container, control: ContainerControl) -> io::Result<()> {
+    let path = format!("/containers/{}/{}", container.id, control.label());
+    match api_request(container, "POST", &path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            cli(container, &[control.label(), &container.id]).map(|_| ())
+        }
+        result => result.map(|_| ()),
+    }
+}
+
+// the engine's unix socket, a rootless podman has its own one
+#[cfg(unix)]
+fn socket_paths(runtime: &str) -> Vec<std::path::PathBuf> {
+    use std::{env, path::PathBuf};
+
+    match runtime {
+        "docker" => {
+            let mut paths = vec![];
+            if let Some(path) = env::var("DOCKER_HOST")
+                .ok()
+                .and_then(|host| host.strip_prefix("unix://").map(PathBuf::from))
+            {
+                paths.push(path);
+            }
+            paths.push(PathBuf::from("/var/run/docker.sock"));
+            paths
+        }
+        "podman" => {
+            let mut paths = vec![];
+            if let Ok(dir) = env::var("XDG_RUNTIME_DIR") {
+                paths.push(PathBuf::from(dir).join("podman/podman.sock"));
+            }
+            paths.push(PathBuf::from("/run/podman/podman.sock"));
+            paths
+        }
+        _ => vec![],
+    }
+}
+
+// a plain http/1.0 request, so the engine closes the connection once it has answered.
+// fails with NotFound when there is no socket to talk to
+#[cfg(unix)]
+fn api_request(container: &Container, method: &str, path: &str) -> io::Result<String> {
+    use std::{
+        io::{Read, Write},
+        os::unix::net::UnixStream,
+        time::Duration,
+    };
+
+    if !matches!(container.runtime, "docker" | "podman") {
+        return Err(unsupported(container));
+    }
+    let socket = socket_paths(container.runtime)
+        .into_iter()
+        .find(|path| path.exists())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no engine socket"))?;
+
+    let mut stream = UnixStream::connect(socket)?;
+    // longer than the 10 seconds a stop may take
+    stream.set_read_timeout(Some(Duration::from_secs(15)))?;
+    write!(
+        stream,
+        "{} {} HTTP/1.0\r\nHost: localhost\r\nContent-Length: 0\r\n\r\n",
+        method, path
+    )?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+    let status: u16 = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed response"))?;
+    // 304 is a container that was stopped already
+    if (200..300).contains(&status) || status == 304 {
+        return Ok(body.to_string());
+    }
+
+    let message = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|json| json["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("the engine answered {}", status));
+    Err(io::Error::other(message))
+}
+
+#[cfg(not(unix))]
+fn api_request(container: &Container, _method: &str, _path: &str) -> io::Result<String> {
+    Err(unsupported(container))
+}
+
+// `docker` or `podman` itself, for when the engine's socket is somewhere else
+fn cli(container: &Container, args: &[&str]) -> io::Result<String> {
+    if !matches!(container.runtime, "docker" | "podman") {
+        return Err(unsupported(container));
+    }
+    if !in_path(container.runtime) {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "neither the {0} socket nor the {0} command were found",
+                container.runtime
+            ),
+        ));
+    }
+    let output = std::process::Command::new(container.runtime)
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(stderr.trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn unsupported(container: &Container) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "{} containers can't be controlled, only docker and podman ones",
+            container.runtime
+        ),
+    )
+}
@@ -0,0 +1,283 @@
+use std::{error::Error, fmt, io, path::PathBuf};
+
+use sysinfo::{Pid, Signal};
+
+use crate::{
+    container::ContainerControl, process::signal_name, services::ServiceControl,
+    systemd::UnitControl,
+};
+
+// everything a user action can fail with, shown as a toast instead of panicking
+#[derive(Debug)]
+pub enum AppError {
+    // the action needs a selected row
+    NoSelection,
+    // the process exited before the action reached it
+    ProcessGone(Pid),
+    NoParent(Pid),
+    // left out of a signal sent to live processes
+    ZombiesSkipped(usize),
+    NoChildren(Pid),
+    // the process exists but the search or user filter hides it
+    NotListed(Pid),
+    // "pid name" of every protected process the action skipped
+    Protected(Vec<String>),
+    SignalUnsupported(Signal),
+    SignalFailed {
+        signal: Signal,
+        pids: Vec<Pid>,
+    },
+    // the processes belong to another user and there is no sudo or pkexec to retry with
+    PermissionDenied {
+        signal: Signal,
+        pids: Vec<Pid>,
+    },
+    // the retry through sudo or pkexec failed
+    Elevation(String),
+    // the signal was sent but could not be written to the audit log
+    AuditLog(String),
+    Renice(Vec<(Pid, io::Error)>),
+    Affinity {
+        pid: Pid,
+        reason: String,
+    },
+    CoreDump {
+        pid: Pid,
+        reason: String,
+    },
+    Limits {
+        pid: Pid,
+        reason: String,
+    },
+    OpenFiles {
+        pid: Pid,
+        reason: String,
+    },
+    Connections {
+        pid: Pid,
+        reason: String,
+    },
+    // the services could not be listed, e.g. when not on windows
+    Services(String),
+    ServiceControl {
+        name: String,
+        control: ServiceControl,
+        reason: String,
+    },
+    ContainerControl {
+        name: String,
+        control: ContainerControl,
+        reason: String,
+    },
+    UnitControl {
+        name: String,
+        control: UnitControl,
+        reason: String,
+    },
+    Export {
+        path: PathBuf,
+        reason: String,
+    },
+    Clipboard(String),
+    // an alert went off but the desktop notification could not be shown
+    Notify(String),
+    // the search was applied but the history file could not be written
+    SearchHistory(String),
+    // the filter picker was opened without a [filters] table in the config
+    NoSavedFilters,
+    // a saved filter does not parse, e.g. an invalid regex
+    InvalidFilter(String),
+    // the column choice could not be written to the config file
+    SaveConfig(String),
+    // what was typed into the jump to pid prompt is not a number
+    InvalidPid(String),
+    UnknownSignal(String),
+    // started with --read-only
+    ReadOnly,
+    // the delay of a scheduled kill, e.g. `30m`
+    InvalidDelay(String),
+    NoScheduledKills,
+    // nothing killed from here has exited yet
+    NothingToRespawn,
+    // it exited on its own, or its command line couldn't be read
+    NotRespawnable {
+        pid: Pid,
+        name: String,
+    },
+    Respawn {
+        name: String,
+        reason: String,
+    },
+    Spawn {
+        command: String,
+        reason: String,
+    },
+    // the executable or working directory of another user's process, without root
+    UnknownPath {
+        pid: Pid,
+        exe: bool,
+    },
+    Reveal {
+        path: PathBuf,
+        reason: String,
+    },
+    NoSuchProcess(Pid),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppError::NoSelection => write!(f, "No process selected"),
+            AppError::ProcessGone(pid) => write!(f, "Process {} has already exited", pid),
+            AppError::NoParent(pid) => write!(f, "Process {} has no parent", pid),
+            AppError::ZombiesSkipped(count) => write!(
+                f,
+                "Skipped {} zombie process(es), select one on its own to reap it",
+                count
+            ),
+            AppError::NoChildren(pid) => write!(f, "Process {} has no listed children", pid),
+            AppError::InvalidPid(input) => write!(f, "{:?} is not a PID", input),
+            AppError::ReadOnly => write!(f, "Read-only mode, processes can't be changed"),
+            AppError::UnknownSignal(input) => write!(
+                f,
+                "{:?} is not a signal, expected a number or a name like USR1 or HUP",
+                input
+            ),
+            AppError::InvalidDelay(input) => {
+                write!(
+                    f,
+                    "{:?} is not a delay, expected e.g. 90s, 30m or 2h, at most a year",
+                    input
+                )
+            }
+            AppError::NoScheduledKills => write!(f, "No kills are scheduled"),
+            AppError::NothingToRespawn => {
+                write!(f, "No process killed from here has exited yet")
+            }
+            AppError::NotRespawnable { pid, name } => write!(
+                f,
+                "Can't start {} {} again, only the processes killed from here are remembered",
+                pid, name
+            ),
+            AppError::Respawn { name, reason } => {
+                write!(f, "Could not start {} again: {}", name, reason)
+            }
+            AppError::Spawn { command, reason } => {
+                write!(f, "Could not start {}: {}", command, reason)
+            }
+            AppError::UnknownPath { pid, exe } => write!(
+                f,
+                "The {} of {} can't be read, run process_killer as root",
+                if *exe {
+                    "executable"
+                } else {
+                    "working directory"
+                },
+                pid
+            ),
+            AppError::Reveal { path, reason } => {
+                write!(
+                    f,
+                    "Could not open the directory of {}: {}",
+                    path.display(),
+                    reason
+                )
+            }
+            AppError::NoSuchProcess(pid) => write!(f, "There is no process {}", pid),
+            AppError::NotListed(pid) => write!(f, "Process {} is filtered out of the table", pid),
+            AppError::Protected(processes) => write!(
+                f,
+                "Refusing to signal protected process(es): {}",
+                processes.join(", ")
+            ),
+            AppError::SignalUnsupported(signal) => write!(
+                f,
+                "{} is not supported on this platform",
+                signal_name(*signal)
+            ),
+            AppError::SignalFailed { signal, pids } => write!(
+                f,
+                "Could not send {} to {}",
+                signal_name(*signal),
+                join(pids.iter().map(|pid| pid.to_string()))
+            ),
+            AppError::PermissionDenied { signal, pids } => write!(
+                f,
+                "Permission denied sending {} to {}, run process_killer as root",
+                signal_name(*signal),
+                join(pids.iter().map(|pid| pid.to_string()))
+            ),
+            AppError::Elevation(reason) => write!(f, "Could not retry as root: {}", reason),
+            AppError::AuditLog(reason) => {
+                write!(f, "Could not write to the audit log: {}", reason)
+            }
+            AppError::Renice(failed) => write!(
+                f,
+                "Could not renice {}",
+                join(failed.iter().map(|(pid, err)| format!("{}: {}", pid, err)))
+            ),
+            AppError::Affinity { pid, reason } => {
+                write!(
+                    f,
+                    "Could not change the CPU affinity of {}: {}",
+                    pid, reason
+                )
+            }
+            AppError::CoreDump { pid, reason } => {
+                write!(f, "Could not dump the core of {}: {}", pid, reason)
+            }
+            AppError::Limits { pid, reason } => {
+                write!(f, "Could not read the limits of {}: {}", pid, reason)
+            }
+            AppError::OpenFiles { pid, reason } => {
+                write!(f, "Could not list the open files of {}: {}", pid, reason)
+            }
+            AppError::Connections { pid, reason } => {
+                write!(f, "Could not list the connections of {}: {}", pid, reason)
+            }
+            AppError::Services(reason) => write!(f, "Could not list the services: {}", reason),
+            AppError::ServiceControl {
+                name,
+                control,
+                reason,
+            } => write!(f, "Could not {} {}: {}", control.label(), name, reason),
+            AppError::UnitControl {
+                name,
+                control,
+                reason,
+            } => write!(f, "Could not {} {}: {}", control.label(), name, reason),
+            AppError::Export { path, reason } => {
+                write!(f, "Could not export to {}: {}", path.display(), reason)
+            }
+            AppError::Clipboard(reason) => write!(f, "Could not copy: {}", reason),
+            AppError::Notify(reason) => {
+                write!(f, "Could not send the desktop notification: {}", reason)
+            }
+            AppError::NoSavedFilters => {
+                write!(f, "No saved filters, add them to [filters] in the config")
+            }
+            AppError::InvalidFilter(reason) => write!(f, "Invalid saved filter: {}", reason),
+            AppError::SaveConfig(reason) => write!(f, "Could not save the config: {}", reason),
+            AppError::SearchHistory(reason) => {
+                write!(f, "Could not save the search history: {}", reason)
+            }
+            AppError::ContainerControl {
+                name,
+                control,
+                reason,
+            } => write!(
+                f,
+                "Could not {} container {}: {}",
+                control.label(),
+                name,
+                reason
+            ),
+        }
+    }
+}
+
+impl Error for AppError {}
+
+fn join(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(", ")
+}
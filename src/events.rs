@@ -0,0 +1,223 @@
+// `GET /events` of `process_killer serve`, a WebSocket pushing what happens to the processes as
+// JSON messages, for dashboards that would otherwise poll `/processes`
+use std::{
+    collections::{HashMap, HashSet},
+    net::{Shutdown, TcpStream},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, SyncSender},
+        Mutex, MutexGuard,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+use sysinfo::PidExt;
+
+use crate::{
+    config::Config,
+    rules::LimitTracker,
+    websocket::{read_frame, write_frame, OPCODE_CLOSE, OPCODE_PING, OPCODE_PONG, OPCODE_TEXT},
+    ProcessInfo, ProcessManager,
+};
+
+// how often the processes are compared with the ones before
+const INTERVAL: Duration = Duration::from_secs(2);
+// a subscriber that doesn't take a message within this is dropped
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+// the ones above are closed right after the handshake, apart from the API's own connections
+const MAX_SUBSCRIBERS: usize = 32;
+// events waiting to be written to a subscriber, one that falls further behind is dropped
+const QUEUE_LEN: usize = 64;
+
+// the opcode and payload of a frame to write
+type Frame = (u8, Vec<u8>);
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum Event {
+    Spawned {
+        pid: u32,
+        name: String,
+        cmdline: String,
+        user: String,
+    },
+    Exited {
+        pid: u32,
+        name: String,
+    },
+    // a signal sent through the API, `result` is `sent`, `dry run` or why it failed
+    Killed {
+        pid: u32,
+        name: String,
+        signal: String,
+        result: String,
+    },
+    // a process over one of the [[alerts]] of the config for its duration
+    Threshold {
+        pid: u32,
+        name: String,
+        alert: String,
+        limit: String,
+        cpu: f32,
+        memory: u64,
+    },
+}
+
+// every message, with the seconds since the epoch
+#[derive(Serialize)]
+struct Message<'a> {
+    time: u64,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+// the queues of the subscribers, by an id of the connection
+#[derive(Default)]
+pub(crate) struct Subscribers {
+    queues: Mutex<Vec<(u64, SyncSender<Frame>)>>,
+    next_id: AtomicU64,
+}
+
+impl Subscribers {
+    // takes the events until the client closes the connection or goes away, answering its pings.
+    // each subscriber is written to by a thread of its own, a stalled one doesn't hold up the rest
+    pub(crate) fn listen(&self, stream: TcpStream) {
+        let Ok(mut writer) = stream.try_clone() else {
+            return;
+        };
+        if writer.set_write_timeout(Some(WRITE_TIMEOUT)).is_err()
+            || stream.set_read_timeout(None).is_err()
+        {
+            return;
+        }
+        let (queue, frames) = mpsc::sync_channel::<Frame>(QUEUE_LEN);
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        {
+            let mut queues = self.lock();
+            if queues.len() >= MAX_SUBSCRIBERS {
+                drop(queues);
+                // 1013, try again later
+                let _ = write_frame(&mut writer, OPCODE_CLOSE, &1013u16.to_be_bytes());
+                let _ = stream.shutdown(Shutdown::Both);
+                return;
+            }
+            queues.push((id, queue));
+        }
+
+        thread::scope(|scope| {
+            // ends once the queue is dropped and drained, or the client stopped reading
+            scope.spawn(move || {
+                for (opcode, payload) in frames {
+                    if write_frame(&mut writer, opcode, &payload).is_err() {
+                        break;
+                    }
+                }
+                // wakes up the read below
+                let _ = writer.shutdown(Shutdown::Both);
+            });
+
+            let mut reader = &stream;
+            loop {
+                match read_frame(&mut reader) {
+                    Ok((OPCODE_PING, payload)) => self.queue(id, (OPCODE_PONG, payload)),
+                    // echoing the status code, then the client closes the connection
+                    Ok((OPCODE_CLOSE, payload)) => {
+                        let code = payload[..payload.len().min(2)].to_vec();
+                        self.queue(id, (OPCODE_CLOSE, code));
+                        break;
+                    }
+                    // the events only go one way, what else the client sends is ignored
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+            self.lock().retain(|(subscriber, _)| *subscriber != id);
+        });
+        let _ = stream.shutdown(Shutdown::Both);
+    }
+
+    fn queue(&self, id: u64, frame: Frame) {
+        if let Some((_, queue)) = self.lock().iter().find(|(subscriber, _)| *subscriber == id) {
+            let _ = queue.try_send(frame);
+        }
+    }
+
+    // never waits on a client, the ones that went away or fell QUEUE_LEN events behind are dropped
+    pub(crate) fn send(&self, event: &Event) {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let Ok(json) = serde_json::to_vec(&Message { time, event }) else {
+            return;
+        };
+        self.lock()
+            .retain(|(_, queue)| queue.try_send((OPCODE_TEXT, json.clone())).is_ok());
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<(u64, SyncSender<Frame>)>> {
+        self.queues.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+// compares the processes every INTERVAL for the spawned and exited ones and the alerts, until
+// killed
+pub(crate) fn watch(config: &Config, subscribers: &Subscribers) {
+    let mut manager = ProcessManager::new();
+    // by pid and start time, a pid taken over by a new process is an exit and a spawn
+    let key = |process: &ProcessInfo| (process.pid, process.start_time);
+    let mut known: HashMap<_, ProcessInfo> = manager
+        .processes()
+        .into_iter()
+        .map(|process| (key(&process), process))
+        .collect();
+    let mut tracker = LimitTracker::default();
+
+    loop {
+        thread::sleep(INTERVAL);
+        manager.refresh();
+        let processes = manager.processes();
+        let mut events = vec![];
+
+        for process in processes.iter().filter(|p| !known.contains_key(&key(p))) {
+            events.push(Event::Spawned {
+                pid: process.pid.as_u32(),
+                name: process.name.clone(),
+                cmdline: process.cmd.clone(),
+                user: process.user.clone(),
+            });
+        }
+        let running: HashSet<_> = processes.iter().map(key).collect();
+        for (_, process) in known.iter().filter(|(k, _)| !running.contains(k)) {
+            events.push(Event::Exited {
+                pid: process.pid.as_u32(),
+                name: process.name.clone(),
+            });
+        }
+
+        let limits = config.alerts.iter().map(|alert| &alert.limit);
+        for (i, pid) in tracker.due(limits, &processes, Instant::now(), false) {
+            let Some(process) = processes.iter().find(|process| process.pid == pid) else {
+                continue;
+            };
+            let limit = &config.alerts[i].limit;
+            events.push(Event::Threshold {
+                pid: pid.as_u32(),
+                name: process.name.clone(),
+                alert: limit.name.clone(),
+                limit: limit.describe(),
+                cpu: process.cpu_usage,
+                memory: process.memory,
+            });
+        }
+
+        for event in &events {
+            subscribers.send(event);
+        }
+        known = processes
+            .into_iter()
+            .map(|process| (key(&process), process))
+            .collect();
+    }
+}
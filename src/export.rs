@@ -0,0 +1,67 @@
+// the table as a CSV file, e.g. to attach a snapshot to a ticket
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::process::{status_label, MemoryKind, ProcessInfo};
+
+// quotes a field when it holds a separator, a quote or a line break
+fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// the rows in the order given, sizes in bytes and the age in seconds so they sort in a spreadsheet
+pub fn export_csv(path: &Path, processes: &[ProcessInfo], memory: MemoryKind) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let header = [
+        "pid",
+        "ppid",
+        "name",
+        "cpu",
+        &memory.title().to_lowercase(),
+        "disk_read_per_sec",
+        "disk_write_per_sec",
+        "gpu_memory",
+        "gpu_usage",
+        "threads",
+        "user",
+        "cgroup",
+        "nice",
+        "state",
+        "age",
+        "command",
+    ];
+    writeln!(out, "{}", header.join(","))?;
+
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    for p in processes {
+        let fields = [
+            p.pid.to_string(),
+            optional(p.parent.map(|ppid| ppid.to_string())),
+            p.name.clone(),
+            format!("{:.1}", p.cpu_usage),
+            optional(memory.of(p).map(|bytes| bytes.to_string())),
+            p.disk_read.to_string(),
+            p.disk_write.to_string(),
+            optional(p.gpu_memory.map(|bytes| bytes.to_string())),
+            optional(p.gpu_usage.map(|usage| format!("{:.1}", usage))),
+            optional(p.threads.map(|n| n.to_string())),
+            p.user.clone(),
+            optional(p.cgroup.as_ref().map(|cgroup| cgroup.path.clone())),
+            optional(p.nice.map(|n| n.to_string())),
+            status_label(p.status),
+            p.run_time.to_string(),
+            p.cmd.clone(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|value| field(value)).collect();
+        writeln!(out, "{}", fields.join(","))?;
+    }
+
+    out.flush()
+}
@@ -0,0 +1,94 @@
+// what a process has open, like lsof -p
+use std::{fs, io};
+
+use sysinfo::{Pid, PidExt};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum FileKind {
+    File,
+    Socket,
+    Pipe,
+    // eventfd, epoll, inotify and the like
+    AnonInode,
+    Other,
+}
+
+impl FileKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileKind::File => "file",
+            FileKind::Socket => "socket",
+            FileKind::Pipe => "pipe",
+            FileKind::AnonInode => "anon",
+            FileKind::Other => "other",
+        }
+    }
+}
+
+pub struct OpenFile {
+    pub fd: u32,
+    pub kind: FileKind,
+    // the path, or e.g. `socket:[1234]` for what has none
+    pub target: String,
+}
+
+impl OpenFile {
+    // the inode of a socket, to find it in /proc/net
+    pub fn socket_inode(&self) -> Option<u64> {
+        match self.kind {
+            FileKind::Socket => self
+                .target
+                .strip_prefix("socket:[")?
+                .strip_suffix(']')?
+                .parse()
+                .ok(),
+            _ => None,
+        }
+    }
+}
+
+fn file_kind(target: &str) -> FileKind {
+    if target.starts_with("socket:") {
+        FileKind::Socket
+    } else if target.starts_with("pipe:") {
+        FileKind::Pipe
+    } else if target.starts_with("anon_inode:") {
+        FileKind::AnonInode
+    } else if target.starts_with('/') {
+        FileKind::File
+    } else {
+        FileKind::Other
+    }
+}
+
+// the open file descriptors of `pid` sorted by number, reading another user's needs root
+pub fn open_files(pid: Pid) -> io::Result<Vec<OpenFile>> {
+    if cfg!(not(target_os = "linux")) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "open files are only available on linux",
+        ));
+    }
+
+    let mut files = vec![];
+    for entry in fs::read_dir(format!("/proc/{}/fd", pid.as_u32()))? {
+        let entry = entry?;
+        let Some(fd) = entry.file_name().to_str().and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+        // closed since the directory was read
+        let Ok(target) = fs::read_link(entry.path()) else {
+            continue;
+        };
+
+        let target = target.to_string_lossy().into_owned();
+        files.push(OpenFile {
+            fd,
+            kind: file_kind(&target),
+            target,
+        });
+    }
+
+    files.sort_by_key(|file| file.fd);
+    Ok(files)
+}
@@ -0,0 +1,413 @@
+// boolean search expressions like `node & (cpu>50 | mem>1G)`, evaluated against every process
+// on each refresh
+use crate::process::{ProcessInfo, SearchCase, Substring};
+
+pub(crate) enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    // a word contained in the name or the command line
+    Text(Substring),
+    Compare(Comparison),
+}
+
+impl Expr {
+    pub(crate) fn matches(&self, process: &ProcessInfo) -> bool {
+        match self {
+            Expr::And(left, right) => left.matches(process) && right.matches(process),
+            Expr::Or(left, right) => left.matches(process) || right.matches(process),
+            Expr::Not(expr) => !expr.matches(process),
+            Expr::Text(text) => text.matches(&format!("{} {}", process.name, process.cmd)),
+            Expr::Compare(comparison) => comparison.matches(process),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Cpu,
+    Memory,
+    Pid,
+    Ppid,
+    Threads,
+    Nice,
+    Age,
+    User,
+    Name,
+}
+
+impl Field {
+    // longer names first, so `memory` isn't read as `mem` followed by `ory`
+    const NAMES: [(&'static str, Field); 10] = [
+        ("memory", Field::Memory),
+        ("mem", Field::Memory),
+        ("cpu", Field::Cpu),
+        ("ppid", Field::Ppid),
+        ("pid", Field::Pid),
+        ("threads", Field::Threads),
+        ("nice", Field::Nice),
+        ("age", Field::Age),
+        ("user", Field::User),
+        ("name", Field::Name),
+    ];
+
+    // `cpu>50` into the field and `>50`, none when the word isn't a comparison
+    fn split(word: &str) -> Option<(Field, &str)> {
+        Field::NAMES.iter().find_map(|(name, field)| {
+            let rest = word.strip_prefix(name)?;
+            rest.starts_with(['<', '>', '=', '!'])
+                .then_some((*field, rest))
+        })
+    }
+
+    fn is_text(&self) -> bool {
+        matches!(self, Field::User | Field::Name)
+    }
+
+    // the number compared against, none when the process doesn't report it
+    fn number(&self, process: &ProcessInfo) -> Option<f64> {
+        match self {
+            Field::Cpu => Some(process.cpu_usage as f64),
+            Field::Memory => Some(process.memory as f64),
+            Field::Pid => Some(process.pid.to_string().parse().ok()?),
+            Field::Ppid => Some(process.parent?.to_string().parse().ok()?),
+            Field::Threads => process.threads.map(|threads| threads as f64),
+            Field::Nice => process.nice.map(|nice| nice as f64),
+            Field::Age => Some(process.run_time as f64),
+            Field::User | Field::Name => None,
+        }
+    }
+
+    // memory takes K, M, G and T suffixes and the age s, m, h and d
+    fn parse_number(&self, value: &str) -> Option<f64> {
+        let units: &[(char, f64)] = match self {
+            Field::Memory => &[
+                ('K', 1024.0),
+                ('M', 1024.0 * 1024.0),
+                ('G', 1024.0 * 1024.0 * 1024.0),
+                ('T', 1024.0 * 1024.0 * 1024.0 * 1024.0),
+            ],
+            Field::Age => &[('s', 1.0), ('m', 60.0), ('h', 3600.0), ('d', 86400.0)],
+            Field::Cpu => &[('%', 1.0)],
+            _ => &[],
+        };
+        let value = value.trim_end_matches("iB").trim_end_matches('B');
+        let (number, factor) = units
+            .iter()
+            .find_map(|(unit, factor)| {
+                let number = value
+                    .strip_suffix(*unit)
+                    .or_else(|| value.strip_suffix(unit.to_ascii_lowercase()))?;
+                Some((number, *factor))
+            })
+            .unwrap_or((value, 1.0));
+        number.parse::<f64>().ok().map(|number| number * factor)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Op {
+    // two character operators first
+    const SYMBOLS: [(&'static str, Op); 7] = [
+        ("<=", Op::LessOrEqual),
+        (">=", Op::GreaterOrEqual),
+        ("!=", Op::NotEqual),
+        ("==", Op::Equal),
+        ("<", Op::Less),
+        (">", Op::Greater),
+        ("=", Op::Equal),
+    ];
+}
+
+enum Value {
+    Number(f64),
+    Text(Substring),
+}
+
+pub(crate) struct Comparison {
+    field: Field,
+    op: Op,
+    value: Value,
+}
+
+impl Comparison {
+    fn parse(field: Field, rest: &str, case: SearchCase) -> Result<Self, String> {
+        let (op, value) = Op::SYMBOLS
+            .iter()
+            .find_map(|(symbol, op)| Some((*op, rest.strip_prefix(symbol)?)))
+            .ok_or_else(|| format!("unknown comparison '{}'", rest))?;
+        if value.is_empty() {
+            return Err(format!("missing value after '{}'", rest));
+        }
+
+        let value = if field.is_text() {
+            if !matches!(op, Op::Equal | Op::NotEqual) {
+                return Err(format!("{} can only be compared with = or !=", rest));
+            }
+            Value::Text(Substring::new(value, case.is_sensitive(value)))
+        } else {
+            Value::Number(
+                field
+                    .parse_number(value)
+                    .ok_or_else(|| format!("'{}' is not a number", value))?,
+            )
+        };
+        Ok(Self { field, op, value })
+    }
+
+    fn matches(&self, process: &ProcessInfo) -> bool {
+        match &self.value {
+            Value::Text(expected) => {
+                let actual = match self.field {
+                    Field::User => &process.user,
+                    _ => &process.name,
+                };
+                expected.equals(actual) == matches!(self.op, Op::Equal)
+            }
+            Value::Number(expected) => {
+                let Some(actual) = self.field.number(process) else {
+                    return false;
+                };
+                match self.op {
+                    Op::Less => actual < *expected,
+                    Op::LessOrEqual => actual <= *expected,
+                    Op::Greater => actual > *expected,
+                    Op::GreaterOrEqual => actual >= *expected,
+                    Op::Equal => actual == *expected,
+                    Op::NotEqual => actual != *expected,
+                }
+            }
+        }
+    }
+}
+
+// `2G` or `512M` in bytes, read like the memory comparisons
+pub(crate) fn parse_memory(value: &str) -> Option<u64> {
+    Field::Memory.parse_number(value).map(|bytes| bytes as u64)
+}
+
+// taken as an expression rather than fuzzy terms once it has an operator or a comparison
+pub(crate) fn is_expression(input: &str) -> bool {
+    input.contains(['&', '|', '(', ')'])
+        || input
+            .split_whitespace()
+            .any(|word| Field::split(word.trim_start_matches('!')).is_some())
+}
+
+enum Token {
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+    Word(String),
+}
+
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            // `&&` and `||` read the same as `&` and `|`
+            '&' | '|' => {
+                if chars.peek() == Some(&c) {
+                    chars.next();
+                }
+                tokens.push(if c == '&' { Token::And } else { Token::Or });
+            }
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '!' => tokens.push(Token::Not),
+            c => {
+                // a `!` inside a word is part of it, as in `user!=root`
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '&' | '|' | '(' | ')') {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    tokens
+}
+
+// `|` binds looser than `&`, which binds looser than `!`
+pub(crate) fn parse(input: &str, case: SearchCase) -> Result<Expr, String> {
+    let mut parser = Parser {
+        tokens: tokenize(input),
+        position: 0,
+        case,
+    };
+    let expr = parser.or()?;
+    match parser.tokens.get(parser.position) {
+        None => Ok(expr),
+        Some(Token::Close) => Err("unmatched ')'".to_string()),
+        Some(_) => Err("expected & or | between terms".to_string()),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    case: SearchCase,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while let Some(Token::Or) = self.peek() {
+            self.position += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        while let Some(Token::And) = self.peek() {
+            self.position += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        let case = self.case;
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::Open) => {
+                let expr = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("unclosed '('".to_string()),
+                }
+            }
+            Some(Token::Word(word)) => match Field::split(word) {
+                Some((field, rest)) => Comparison::parse(field, rest, case).map(Expr::Compare),
+                None => Ok(Expr::Text(Substring::new(word, case.is_sensitive(word)))),
+            },
+            Some(Token::Close) => Err("unexpected ')'".to_string()),
+            Some(Token::And | Token::Or) | None => Err("expected a term".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn processes() -> Vec<ProcessInfo> {
+        let mut nginx = ProcessInfo::stub(1, "nginx");
+        nginx.cpu_usage = 80.0;
+        nginx.run_time = 2 * 3600;
+        let mut node = ProcessInfo::stub(2, "node");
+        node.cpu_usage = 10.0;
+        node.memory = 2 * 1024 * 1024 * 1024;
+        let mut postgres = ProcessInfo::stub(3, "postgres");
+        postgres.memory = 512 * 1024 * 1024;
+        postgres.user = "postgres".to_string();
+        vec![nginx, node, postgres]
+    }
+
+    // the pids of the processes the expression matches
+    fn matching(input: &str, case: SearchCase) -> Vec<u32> {
+        let expr = parse(input, case).unwrap_or_else(|err| panic!("{}: {}", input, err));
+        processes()
+            .iter()
+            .filter(|process| expr.matches(process))
+            .map(|process| process.pid.to_string().parse().unwrap())
+            .collect()
+    }
+
+    fn error(input: &str) -> Option<String> {
+        parse(input, SearchCase::Smart).err()
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let smart = SearchCase::Smart;
+        assert_eq!(matching("postgres | node & cpu>50", smart), [3]);
+        assert_eq!(matching("(postgres | node) & cpu>5", smart), [2]);
+        assert_eq!(matching("node && cpu>5 || nginx", smart), [1, 2]);
+        assert_eq!(matching("!node & !nginx", smart), [3]);
+        assert_eq!(matching("!(node | nginx)", smart), [3]);
+        assert_eq!(matching("!!node", smart), [2]);
+    }
+
+    #[test]
+    fn reads_the_units_of_each_field() {
+        let smart = SearchCase::Smart;
+        assert_eq!(matching("mem>1G", smart), [2]);
+        assert_eq!(matching("memory>=512M", smart), [2, 3]);
+        assert_eq!(matching("mem<600MiB & mem>0", smart), [3]);
+        assert_eq!(matching("cpu>=80%", smart), [1]);
+        assert_eq!(matching("age>90m", smart), [1]);
+        assert_eq!(matching("age<1d & pid!=1", smart), [2, 3]);
+        assert_eq!(parse_memory("2G"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_memory("1.5k"), Some(1536));
+        assert_eq!(parse_memory("100"), Some(100));
+        assert_eq!(parse_memory("lots"), None);
+    }
+
+    #[test]
+    fn compares_users_and_names_as_text() {
+        assert_eq!(matching("user=postgres", SearchCase::Smart), [3]);
+        assert_eq!(matching("user!=root", SearchCase::Smart), [3]);
+        assert!(matching("name=Node", SearchCase::Smart).is_empty());
+        assert_eq!(matching("name=Node", SearchCase::Insensitive), [2]);
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        let expected = |message: &str| Some(message.to_string());
+        assert_eq!(error("node &"), expected("expected a term"));
+        assert_eq!(error("| node"), expected("expected a term"));
+        assert_eq!(error("(node"), expected("unclosed '('"));
+        assert_eq!(error("node)"), expected("unmatched ')'"));
+        assert_eq!(error("()"), expected("unexpected ')'"));
+        assert_eq!(
+            error("node nginx"),
+            expected("expected & or | between terms")
+        );
+        assert_eq!(error("cpu>abc"), expected("'abc' is not a number"));
+        assert_eq!(error("cpu>"), expected("missing value after '>'"));
+        assert_eq!(error("cpu!5"), expected("unknown comparison '!5'"));
+        assert_eq!(
+            error("user>root"),
+            expected(">root can only be compared with = or !=")
+        );
+    }
+
+    #[test]
+    fn tells_expressions_from_fuzzy_terms() {
+        assert!(!is_expression("node server"));
+        assert!(!is_expression("cpufreq"));
+        assert!(is_expression("cpu>5"));
+        assert!(is_expression("!user=root"));
+        assert!(is_expression("node | nginx"));
+    }
+}
@@ -0,0 +1,230 @@
+// per-process gpu memory and utilization, from nvidia-smi on nvidia cards and from the
+// drm fdinfo (amdgpu, i915 and others) everywhere else
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    process::Command,
+};
+
+use sysinfo::{Pid, PidExt};
+
+use crate::{files::open_files, process::in_path};
+
+#[derive(Clone, Copy, Default)]
+pub(crate) struct GpuSample {
+    // bytes of video memory
+    pub(crate) memory: u64,
+    // total time spent on the gpu engines, only drm drivers report it
+    pub(crate) engine_ns: Option<u64>,
+    // the share of the gpu over the last second, as nvidia-smi reports it
+    pub(crate) usage: Option<f32>,
+}
+
+// walking the fds of every process for the /dev/dri ones takes a while, in between only the
+// processes found with one last time are looked at again
+const DRM_RESCAN_EVERY: usize = 5;
+
+// the processes with a /dev/dri fd at the last full walk of /proc
+#[derive(Default)]
+pub(crate) struct DrmClients {
+    pids: HashSet<Pid>,
+    // samples until the next full walk
+    rescan_in: usize,
+}
+
+// every process using a gpu, none when there is no gpu to ask
+pub(crate) fn sample(clients: &mut DrmClients) -> Option<HashMap<Pid, GpuSample>> {
+    let nvidia = in_path("nvidia-smi");
+    let drm = Path::new("/dev/dri").is_dir();
+    if !nvidia && !drm {
+        return None;
+    }
+
+    let mut samples = HashMap::new();
+    if nvidia {
+        nvidia_memory(&mut samples);
+        nvidia_usage(&mut samples);
+    }
+    if drm {
+        drm_usage(&mut samples, clients);
+    }
+    Some(samples)
+}
+
+// `nvidia-smi --query-compute-apps` prints one `pid, MiB` line per process
+fn nvidia_memory(samples: &mut HashMap<Pid, GpuSample>) {
+    let Ok(output) = Command::new("nvidia-smi")
+        .args([
+            "--query-compute-apps=pid,used_memory",
+            "--format=csv,noheader,nounits",
+        ])
+        .output()
+    else {
+        return;
+    };
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((pid, memory)) = line.split_once(',') else {
+            continue;
+        };
+        let (Ok(pid), Ok(memory)) = (pid.trim().parse(), memory.trim().parse::<u64>()) else {
+            continue;
+        };
+        samples.entry(Pid::from_u32(pid)).or_default().memory += memory * 1024 * 1024;
+    }
+}
+
+// `nvidia-smi pmon` prints one line per process and gpu, the sm column is the utilization,
+// `-` when the process didn't run on that gpu. it samples for a second before printing
+fn nvidia_usage(samples: &mut HashMap<Pid, GpuSample>) {
+    let Ok(output) = Command::new("nvidia-smi")
+        .args(["pmon", "--count", "1", "--select", "u"])
+        .output()
+    else {
+        return;
+    };
+
+    add_pmon(samples, &String::from_utf8_lossy(&output.stdout));
+}
+
+// a process on several gpus gets their utilization added up
+fn add_pmon(samples: &mut HashMap<Pid, GpuSample>, output: &str) {
+    for line in output.lines().filter(|line| !line.starts_with('#')) {
+        let fields: Vec<_> = line.split_whitespace().collect();
+        let (Some(Ok(pid)), Some(Ok(sm))) = (
+            fields.get(1).map(|pid| pid.parse()),
+            fields.get(3).map(|sm| sm.parse::<f32>()),
+        ) else {
+            continue;
+        };
+        let sample = samples.entry(Pid::from_u32(pid)).or_default();
+        sample.usage = Some(sample.usage.unwrap_or(0.0) + sm);
+    }
+}
+
+// sums up the fdinfo of every /dev/dri fd, see the kernel's drm-usage-stats
+fn drm_usage(samples: &mut HashMap<Pid, GpuSample>, clients: &mut DrmClients) {
+    let pids: Vec<Pid> = if clients.rescan_in == 0 {
+        clients.rescan_in = DRM_RESCAN_EVERY;
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return;
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+            .map(Pid::from_u32)
+            .collect()
+    } else {
+        clients.pids.iter().copied().collect()
+    };
+    clients.rescan_in -= 1;
+    clients.pids.clear();
+
+    for pid in pids {
+        // the fds of other users' processes can't be read
+        let Ok(files) = open_files(pid) else {
+            continue;
+        };
+        if files.iter().any(|f| f.target.starts_with("/dev/dri/")) {
+            clients.pids.insert(pid);
+        }
+
+        // a dup'ed fd is the same client, it must not be counted twice
+        let mut seen = HashSet::new();
+        for file in files.iter().filter(|f| f.target.starts_with("/dev/dri/")) {
+            let Ok(info) = fs::read_to_string(format!("/proc/{}/fdinfo/{}", pid, file.fd)) else {
+                continue;
+            };
+            let Some(client) = fdinfo_field(&info, "drm-client-id") else {
+                continue;
+            };
+            if seen.insert(client.to_string()) {
+                add_fdinfo(samples.entry(pid).or_default(), &info);
+            }
+        }
+    }
+}
+
+// the engine time and video memory of one drm client
+fn add_fdinfo(sample: &mut GpuSample, info: &str) {
+    // drm-total-* is the newer name of drm-memory-*, a driver may print both for the same memory
+    let (mut total, mut memory) = (None, None);
+    for line in info.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let amount = parse_amount(value).unwrap_or(0);
+        if key.starts_with("drm-engine-") && !key.starts_with("drm-engine-capacity-") {
+            sample.engine_ns = Some(sample.engine_ns.unwrap_or(0) + amount);
+        } else if key.starts_with("drm-total-vram") {
+            total = Some(total.unwrap_or(0) + amount);
+        } else if key.starts_with("drm-memory-vram") {
+            memory = Some(memory.unwrap_or(0) + amount);
+        }
+    }
+    sample.memory += total.or(memory).unwrap_or(0);
+}
+
+fn fdinfo_field<'a>(info: &'a str, name: &str) -> Option<&'a str> {
+    info.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key == name).then(|| value.trim())
+    })
+}
+
+// `1234 ns`, `512 KiB` or `3 MiB`, memory is returned in bytes
+fn parse_amount(value: &str) -> Option<u64> {
+    let mut parts = value.split_whitespace();
+    let amount: u64 = parts.next()?.parse().ok()?;
+    let scale = match parts.next() {
+        Some("KiB") => 1024,
+        Some("MiB") => 1024 * 1024,
+        Some("GiB") => 1024 * 1024 * 1024,
+        _ => 1,
+    };
+    Some(amount * scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_the_vram_of_a_client_once() {
+        let mut sample = GpuSample::default();
+        add_fdinfo(
+            &mut sample,
+            "drm-driver:\tamdgpu\ndrm-client-id:\t12\ndrm-memory-vram:\t2048 KiB\n\
+             drm-total-vram:\t2048 KiB\ndrm-resident-vram:\t2048 KiB\n\
+             drm-engine-gfx:\t1000 ns\ndrm-engine-compute:\t500 ns\n\
+             drm-engine-capacity-gfx:\t2\n",
+        );
+        assert_eq!(sample.memory, 2048 * 1024);
+        assert_eq!(sample.engine_ns, Some(1500));
+
+        // older kernels only have drm-memory-*
+        let mut sample = GpuSample::default();
+        add_fdinfo(
+            &mut sample,
+            "drm-memory-vram:\t3 MiB\ndrm-memory-gtt:\t1 MiB\n",
+        );
+        assert_eq!(sample.memory, 3 * 1024 * 1024);
+        assert_eq!(sample.engine_ns, None);
+    }
+
+    #[test]
+    fn reads_the_sm_column_of_pmon() {
+        let output = "\
+# gpu         pid   type     sm    mem    enc    dec    command
+# Idx           #    C/G      %      %      %      %    name
+    0       1234     C     45     10      -      -    python
+    1       1234     C     20      5      -      -    python
+    0       5678     G      -      -      -      -    Xorg
+";
+        let mut samples = HashMap::new();
+        add_pmon(&mut samples, output);
+        assert_eq!(samples[&Pid::from_u32(1234)].usage, Some(65.0));
+        assert!(!samples.contains_key(&Pid::from_u32(5678)));
+    }
+}
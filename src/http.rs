@@ -0,0 +1,164 @@
+// just enough HTTP/1.1 for the metrics exporter and the API, one request per connection
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+// the whole request has to arrive within this, a client sending a byte now and then is dropped too
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_HEADERS: usize = 100;
+// the request line and each header
+const MAX_LINE: usize = 8 * 1024;
+// connections handled at once, the ones above are closed right away
+const MAX_CONNECTIONS: usize = 64;
+// the API only takes small JSON bodies
+const MAX_BODY: usize = 64 * 1024;
+
+pub(crate) struct Request {
+    pub(crate) method: String,
+    // without the query string
+    pub(crate) path: String,
+    // the names lowercased
+    headers: Vec<(String, String)>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Request {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+// hands every connection to `handle` on a thread of its own, so a slow client doesn't hold up the
+// others, until the listener fails
+pub(crate) fn serve_connections(listener: &TcpListener, handle: impl Fn(TcpStream) + Sync) {
+    let open = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            if open.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                open.fetch_sub(1, Ordering::SeqCst);
+                continue;
+            }
+            let (open, handle) = (&open, &handle);
+            scope.spawn(move || {
+                handle(stream);
+                open.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+    });
+}
+
+// reads from the stream until the deadline, however the bytes trickle in
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    until: Instant,
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "request took too long",
+            ));
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        let mut stream = self.stream;
+        stream.read(buf)
+    }
+}
+
+// a line of at most MAX_LINE bytes, empty at the end of the stream
+fn read_line(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut line = String::new();
+    reader.take(MAX_LINE as u64).read_line(&mut line)?;
+    if line.len() == MAX_LINE && !line.ends_with('\n') {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "line too long"));
+    }
+    Ok(line)
+}
+
+pub(crate) fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    let mut reader = BufReader::new(Deadline {
+        stream,
+        until: Instant::now() + READ_TIMEOUT,
+    });
+
+    let line = read_line(&mut reader)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(invalid("malformed request line"));
+    };
+    let path = target.split_once('?').map_or(target, |(path, _)| path);
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        headers: vec![],
+        body: vec![],
+    };
+
+    loop {
+        let line = read_line(&mut reader)?;
+        if line.trim_end().is_empty() {
+            break;
+        }
+        if request.headers.len() == MAX_HEADERS {
+            return Err(invalid("too many headers"));
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let header = (name.trim().to_lowercase(), value.trim().to_string());
+            request.headers.push(header);
+        }
+    }
+
+    let length = match request.header("content-length") {
+        Some(length) => length
+            .parse::<usize>()
+            .map_err(|_| invalid("invalid content-length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY {
+        return Err(invalid("body too large"));
+    }
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body)?;
+    Ok(request)
+}
+
+pub(crate) fn respond(
+    mut stream: &TcpStream,
+    status: u16,
+    content_type: &str,
+    body: &str,
+) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes())?;
+    stream.flush()
+}
@@ -0,0 +1,912 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use sysinfo::Signal;
+
+use crate::{
+    app::{App, InputMode},
+    container::ContainerControl,
+    services::ServiceControl,
+    systemd::UnitControl,
+};
+
+// everything that can be bound to a key in normal mode
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    Next,
+    Prev,
+    PageDown,
+    PageUp,
+    First,
+    Last,
+    SelectParent,
+    SelectChild,
+    JumpToPid,
+    Kill,
+    PoliteKill,
+    KillMarked,
+    KillAllByName,
+    SignalMenu,
+    SignalPrompt,
+    Suspend,
+    Resume,
+    ToggleMark,
+    MarkAll,
+    InvertMarks,
+    ClearMarks,
+    VisualMark,
+    ScheduleKill,
+    ShowScheduledKills,
+    Respawn,
+    Spawn,
+    CoreDump,
+    ToggleDryRun,
+    LowerPriority,
+    RaisePriority,
+    SetAffinity,
+    TogglePin,
+    SortByName,
+    SortByMemory,
+    NextSortColumn,
+    PrevSortColumn,
+    ReverseSort,
+    Search,
+    SavedFilters,
+    ToggleMyProcesses,
+    CycleStateFilter,
+    ToggleTree,
+    ToggleGroups,
+    ToggleCollapse,
+    ToggleCollapseAll,
+    ToggleDetails,
+    ToggleCmd,
+    ScrollLeft,
+    ScrollRight,
+    ToggleCpuMeters,
+    CycleMemoryKind,
+    ChooseColumns,
+    ToggleServices,
+    ShowOpenFiles,
+    ShowLimits,
+    ShowConnections,
+    ShowThreads,
+    ShowChildren,
+    ShowExited,
+    ShowHistory,
+    Export,
+    Refresh,
+    IncreaseRefreshInterval,
+    DecreaseRefreshInterval,
+    CopyPid,
+    CopyCommand,
+    CopyExe,
+    CopyCwd,
+    RevealExe,
+    Help,
+    Quit,
+}
+
+impl Action {
+    // in the order they are listed in the help
+    pub(crate) const ALL: [Action; 71] = [
+        Action::Next,
+        Action::Prev,
+        Action::PageDown,
+        Action::PageUp,
+        Action::First,
+        Action::Last,
+        Action::SelectParent,
+        Action::SelectChild,
+        Action::JumpToPid,
+        Action::Kill,
+        Action::PoliteKill,
+        Action::KillMarked,
+        Action::KillAllByName,
+        Action::SignalMenu,
+        Action::SignalPrompt,
+        Action::Suspend,
+        Action::Resume,
+        Action::ToggleMark,
+        Action::MarkAll,
+        Action::InvertMarks,
+        Action::ClearMarks,
+        Action::VisualMark,
+        Action::ScheduleKill,
+        Action::ShowScheduledKills,
+        Action::Respawn,
+        Action::Spawn,
+        Action::CoreDump,
+        Action::ToggleDryRun,
+        Action::LowerPriority,
+        Action::RaisePriority,
+        Action::SetAffinity,
+        Action::TogglePin,
+        Action::SortByName,
+        Action::SortByMemory,
+        Action::NextSortColumn,
+        Action::PrevSortColumn,
+        Action::ReverseSort,
+        Action::Search,
+        Action::SavedFilters,
+        Action::ToggleMyProcesses,
+        Action::CycleStateFilter,
+        Action::ToggleTree,
+        Action::ToggleGroups,
+        Action::ToggleCollapse,
+        Action::ToggleCollapseAll,
+        Action::ToggleDetails,
+        Action::ToggleCmd,
+        Action::ScrollLeft,
+        Action::ScrollRight,
+        Action::ToggleCpuMeters,
+        Action::CycleMemoryKind,
+        Action::ChooseColumns,
+        Action::ToggleServices,
+        Action::ShowOpenFiles,
+        Action::ShowLimits,
+        Action::ShowConnections,
+        Action::ShowThreads,
+        Action::ShowChildren,
+        Action::ShowExited,
+        Action::ShowHistory,
+        Action::Export,
+        Action::Refresh,
+        Action::IncreaseRefreshInterval,
+        Action::DecreaseRefreshInterval,
+        Action::CopyPid,
+        Action::CopyCommand,
+        Action::CopyExe,
+        Action::CopyCwd,
+        Action::RevealExe,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    // the key used for the action in the config file
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Action::Next => "next",
+            Action::Prev => "prev",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::SelectParent => "select_parent",
+            Action::SelectChild => "select_child",
+            Action::JumpToPid => "jump_to_pid",
+            Action::Kill => "kill",
+            Action::PoliteKill => "polite_kill",
+            Action::KillMarked => "kill_marked",
+            Action::KillAllByName => "kill_all_by_name",
+            Action::SignalMenu => "signal_menu",
+            Action::SignalPrompt => "signal_prompt",
+            Action::Suspend => "suspend",
+            Action::Resume => "resume",
+            Action::ToggleMark => "toggle_mark",
+            Action::MarkAll => "mark_all",
+            Action::InvertMarks => "invert_marks",
+            Action::ClearMarks => "clear_marks",
+            Action::VisualMark => "visual_mark",
+            Action::ScheduleKill => "schedule_kill",
+            Action::ShowScheduledKills => "scheduled_kills",
+            Action::Respawn => "respawn",
+            Action::Spawn => "spawn",
+            Action::CoreDump => "core_dump",
+            Action::ToggleDryRun => "dry_run",
+            Action::LowerPriority => "lower_priority",
+            Action::RaisePriority => "raise_priority",
+            Action::SetAffinity => "set_affinity",
+            Action::TogglePin => "toggle_pin",
+            Action::SortByName => "sort_by_name",
+            Action::SortByMemory => "sort_by_memory",
+            Action::NextSortColumn => "next_sort_column",
+            Action::PrevSortColumn => "prev_sort_column",
+            Action::ReverseSort => "reverse_sort",
+            Action::Search => "search",
+            Action::SavedFilters => "saved_filters",
+            Action::ToggleMyProcesses => "toggle_my_processes",
+            Action::CycleStateFilter => "cycle_state_filter",
+            Action::ToggleTree => "toggle_tree",
+            Action::ToggleGroups => "toggle_groups",
+            Action::ToggleCollapse => "toggle_collapse",
+            Action::ToggleCollapseAll => "toggle_collapse_all",
+            Action::ToggleDetails => "toggle_details",
+            Action::ToggleCmd => "toggle_command_line",
+            Action::ScrollLeft => "scroll_left",
+            Action::ScrollRight => "scroll_right",
+            Action::ToggleCpuMeters => "toggle_cpu_meters",
+            Action::CycleMemoryKind => "cycle_memory_column",
+            Action::ChooseColumns => "columns",
+            Action::ToggleServices => "services",
+            Action::ShowOpenFiles => "open_files",
+            Action::ShowLimits => "limits",
+            Action::ShowConnections => "connections",
+            Action::ShowThreads => "threads",
+            Action::ShowChildren => "children",
+            Action::ShowExited => "exited",
+            Action::ShowHistory => "history",
+            Action::Export => "export",
+            Action::Refresh => "refresh",
+            Action::IncreaseRefreshInterval => "increase_refresh_interval",
+            Action::DecreaseRefreshInterval => "decrease_refresh_interval",
+            Action::CopyPid => "copy_pid",
+            Action::CopyCommand => "copy_command_line",
+            Action::CopyExe => "copy_exe",
+            Action::CopyCwd => "copy_cwd",
+            Action::RevealExe => "reveal_exe",
+            Action::Help => "help",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    // what --read-only turns off
+    pub(crate) fn is_destructive(&self) -> bool {
+        matches!(
+            self,
+            Action::Kill
+                | Action::PoliteKill
+                | Action::KillMarked
+                | Action::KillAllByName
+                | Action::SignalMenu
+                | Action::SignalPrompt
+                | Action::Suspend
+                | Action::Resume
+                | Action::ScheduleKill
+                | Action::Respawn
+                | Action::Spawn
+                | Action::CoreDump
+                | Action::LowerPriority
+                | Action::RaisePriority
+                | Action::SetAffinity
+        )
+    }
+
+    pub(crate) fn category(&self) -> &'static str {
+        match self {
+            Action::Next
+            | Action::Prev
+            | Action::PageDown
+            | Action::PageUp
+            | Action::First
+            | Action::Last
+            | Action::SelectParent
+            | Action::SelectChild
+            | Action::JumpToPid => "Navigation",
+            Action::Kill
+            | Action::PoliteKill
+            | Action::KillMarked
+            | Action::KillAllByName
+            | Action::SignalMenu
+            | Action::SignalPrompt
+            | Action::Suspend
+            | Action::Resume
+            | Action::ToggleMark
+            | Action::MarkAll
+            | Action::InvertMarks
+            | Action::ClearMarks
+            | Action::VisualMark
+            | Action::ScheduleKill
+            | Action::ShowScheduledKills
+            | Action::Respawn
+            | Action::Spawn
+            | Action::CoreDump
+            | Action::ToggleDryRun => "Killing",
+            Action::LowerPriority | Action::RaisePriority | Action::SetAffinity => "Priority",
+            Action::SortByName
+            | Action::SortByMemory
+            | Action::NextSortColumn
+            | Action::PrevSortColumn
+            | Action::ReverseSort => "Sorting",
+            Action::Search
+            | Action::SavedFilters
+            | Action::ToggleMyProcesses
+            | Action::CycleStateFilter
+            | Action::TogglePin => "Filtering",
+            Action::ToggleTree
+            | Action::ToggleGroups
+            | Action::ToggleCollapse
+            | Action::ToggleCollapseAll
+            | Action::ToggleDetails
+            | Action::ToggleCmd
+            | Action::ScrollLeft
+            | Action::ScrollRight
+            | Action::ToggleCpuMeters
+            | Action::CycleMemoryKind
+            | Action::ChooseColumns
+            | Action::ToggleServices
+            | Action::ShowOpenFiles
+            | Action::ShowLimits
+            | Action::ShowConnections
+            | Action::ShowThreads
+            | Action::ShowChildren
+            | Action::ShowExited
+            | Action::ShowHistory
+            | Action::Export
+            | Action::Refresh
+            | Action::IncreaseRefreshInterval
+            | Action::DecreaseRefreshInterval => "View",
+            Action::CopyPid
+            | Action::CopyCommand
+            | Action::CopyExe
+            | Action::CopyCwd
+            | Action::RevealExe
+            | Action::Help
+            | Action::Quit => "General",
+        }
+    }
+
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            Action::Next => "Move down",
+            Action::Prev => "Move up",
+            Action::PageDown => "Move a page down",
+            Action::PageUp => "Move a page up",
+            Action::First => "Jump to the first process",
+            Action::Last => "Jump to the last process",
+            Action::SelectParent => "Collapse the selected subtree, or jump to the parent process",
+            Action::SelectChild => "Expand the selected subtree, or jump to the first child",
+            Action::JumpToPid => "Jump to the process with a given PID",
+            Action::Kill => "Kill the selected process",
+            Action::PoliteKill => "Send SIGTERM, then SIGKILL if it still runs after a timeout",
+            Action::KillMarked => "Kill the marked (or the selected) processes",
+            Action::KillAllByName => "Kill every process with the selected name",
+            Action::SignalMenu => "Choose a signal to send",
+            Action::SignalPrompt => "Send any signal, typed by name (USR1, HUP) or number",
+            Action::Suspend => "Suspend (SIGSTOP) the marked (or the selected) processes",
+            Action::Resume => "Resume (SIGCONT) the marked (or the selected) processes",
+            Action::ToggleMark => "Mark/unmark the selected process",
+            Action::MarkAll => "Mark every process shown, e.g. all the matches of a search",
+            Action::InvertMarks => "Invert the marks of the processes shown",
+            Action::ClearMarks => "Unmark every process",
+            Action::VisualMark => {
+                "Mark the rows the selection moves over, again to stop (Shift+Up/Down mark too)"
+            }
+            Action::ScheduleKill => "Kill the selected process after a delay, e.g. 30m",
+            Action::ShowScheduledKills => "List the scheduled kills, d cancels one",
+            Action::Respawn => "Start the last killed process again, as it was started",
+            Action::Spawn => "Start a command, detached from the terminal",
+            Action::CoreDump => "Dump the core or the threads of the selected process",
+            Action::ToggleDryRun => "Toggle dry run, signals are only logged in the history",
+            Action::LowerPriority => {
+                "Raise the nice value of the marked (or the selected) processes"
+            }
+            Action::RaisePriority => {
+                "Lower the nice value of the marked (or the selected) processes"
+            }
+            Action::SetAffinity => "Set the CPUs the selected process may run on",
+            Action::TogglePin => "Pin/unpin the selected name to the top",
+            Action::SortByName => "Sort by name",
+            Action::SortByMemory => "Sort by memory",
+            Action::NextSortColumn => "Sort by the next column",
+            Action::PrevSortColumn => "Sort by the previous column",
+            Action::ReverseSort => "Reverse the sort order",
+            Action::Search => "Search",
+            Action::SavedFilters => "Apply a filter saved in the config",
+            Action::ToggleMyProcesses => "Toggle only my processes/all users",
+            Action::CycleStateFilter => "Only show running, sleeping, stopped or zombie processes",
+            Action::ToggleTree => "Toggle the tree view",
+            Action::ToggleGroups => "Toggle one row per process name, with the usage added up",
+            Action::ToggleCollapse => "Collapse/expand the selected subtree or name",
+            Action::ToggleCollapseAll => "Collapse/expand every subtree or name",
+            Action::ToggleDetails => "Toggle the details pane",
+            Action::ToggleCmd => "Toggle the full command line",
+            Action::ScrollLeft => "Scroll the name or command line column left",
+            Action::ScrollRight => "Scroll the name or command line column right",
+            Action::ToggleCpuMeters => "Toggle a meter per CPU core",
+            Action::CycleMemoryKind => "Show resident, virtual or shared memory",
+            Action::ChooseColumns => "Choose the columns shown and their order",
+            Action::ToggleServices => "Show the Windows services",
+            Action::ShowOpenFiles => "Show the files the selected process has open",
+            Action::ShowLimits => "Show the resource limits of the selected process",
+            Action::ShowConnections => "Show the network connections of the selected process",
+            Action::ShowThreads => "Show the threads of the selected process",
+            Action::ShowChildren => "Show the children of the selected process, and theirs",
+            Action::ShowExited => "Show the processes that exited recently",
+            Action::ShowHistory => "Show everything done this session",
+            Action::Export => "Export the table as shown to a CSV file",
+            Action::Refresh => "Refetch the processes now",
+            Action::IncreaseRefreshInterval => "Refresh less often",
+            Action::DecreaseRefreshInterval => "Refresh more often",
+            Action::CopyPid => "Copy the PID of the selected process",
+            Action::CopyCommand => "Copy the command line of the selected process",
+            Action::CopyExe => "Copy the executable path of the selected process",
+            Action::CopyCwd => "Copy the working directory of the selected process",
+            Action::RevealExe => "Open the directory of the selected process's executable",
+            Action::Help => "Toggle this help",
+            Action::Quit => "Quit",
+        }
+    }
+
+    fn default_keys(&self) -> Vec<KeyCode> {
+        match self {
+            Action::Next => vec![KeyCode::Down, KeyCode::Char('j')],
+            Action::Prev => vec![KeyCode::Up, KeyCode::Char('k')],
+            Action::PageDown => vec![KeyCode::PageDown],
+            Action::PageUp => vec![KeyCode::PageUp],
+            Action::First => vec![KeyCode::Home],
+            Action::Last => vec![KeyCode::End, KeyCode::Char('G')],
+            Action::SelectParent => vec![KeyCode::Left],
+            Action::SelectChild => vec![KeyCode::Right],
+            Action::JumpToPid => vec![KeyCode::Char(':')],
+            Action::Kill => vec![KeyCode::Char('d')],
+            Action::PoliteKill => vec![KeyCode::Char('x')],
+            Action::KillMarked => vec![KeyCode::Enter],
+            Action::KillAllByName => vec![KeyCode::Char('K')],
+            Action::SignalMenu => vec![KeyCode::Char('s')],
+            Action::SignalPrompt => vec![KeyCode::Char('#')],
+            Action::Suspend => vec![KeyCode::Char('z')],
+            Action::Resume => vec![KeyCode::Char('Z')],
+            Action::ToggleMark => vec![KeyCode::Char(' ')],
+            Action::MarkAll => vec![KeyCode::Char('M')],
+            Action::InvertMarks => vec![KeyCode::Char('I')],
+            Action::ClearMarks => vec![KeyCode::Esc],
+            Action::VisualMark => vec![KeyCode::Char('V')],
+            Action::ScheduleKill => vec![KeyCode::Char('w')],
+            Action::ShowScheduledKills => vec![KeyCode::Char('W')],
+            Action::Respawn => vec![KeyCode::Char('R')],
+            Action::Spawn => vec![KeyCode::Char('!')],
+            Action::CoreDump => vec![KeyCode::Char('Q')],
+            Action::ToggleDryRun => vec![KeyCode::Char('P')],
+            Action::LowerPriority => vec![KeyCode::Char(']')],
+            Action::RaisePriority => vec![KeyCode::Char('[')],
+            Action::SetAffinity => vec![KeyCode::Char('a')],
+            Action::TogglePin => vec![KeyCode::Char('p')],
+            Action::SortByName => vec![KeyCode::Char('n')],
+            Action::SortByMemory => vec![KeyCode::Char('m')],
+            Action::NextSortColumn => vec![KeyCode::Char('>')],
+            Action::PrevSortColumn => vec![KeyCode::Char('<')],
+            Action::ReverseSort => vec![KeyCode::Char('o')],
+            Action::Search => vec![KeyCode::Char('i')],
+            Action::SavedFilters => vec![KeyCode::Char('F')],
+            Action::ToggleMyProcesses => vec![KeyCode::Char('u')],
+            Action::CycleStateFilter => vec![KeyCode::Char('e')],
+            Action::ToggleTree => vec![KeyCode::Char('t')],
+            Action::ToggleGroups => vec![KeyCode::Char('N')],
+            Action::ToggleCollapse => vec![KeyCode::Char('c')],
+            Action::ToggleCollapseAll => vec![KeyCode::Char('A')],
+            Action::ToggleDetails => vec![KeyCode::Tab],
+            Action::ToggleCmd => vec![KeyCode::Char('f')],
+            Action::ScrollLeft => vec![KeyCode::Char('{')],
+            Action::ScrollRight => vec![KeyCode::Char('}')],
+            Action::ToggleCpuMeters => vec![KeyCode::Char('B')],
+            Action::CycleMemoryKind => vec![KeyCode::Char('v')],
+            Action::ChooseColumns => vec![KeyCode::Char('H')],
+            Action::ToggleServices => vec![KeyCode::Char('S')],
+            Action::ShowOpenFiles => vec![KeyCode::Char('l')],
+            Action::ShowLimits => vec![KeyCode::Char('U')],
+            Action::ShowConnections => vec![KeyCode::Char('C')],
+            Action::ShowThreads => vec![KeyCode::Char('T')],
+            Action::ShowChildren => vec![KeyCode::Char('D')],
+            Action::ShowExited => vec![KeyCode::Char('X')],
+            Action::ShowHistory => vec![KeyCode::Char('L')],
+            Action::Export => vec![KeyCode::Char('E')],
+            Action::Refresh => vec![KeyCode::Char('r')],
+            Action::IncreaseRefreshInterval => vec![KeyCode::Char('+')],
+            Action::DecreaseRefreshInterval => vec![KeyCode::Char('-')],
+            Action::CopyPid => vec![KeyCode::Char('y')],
+            Action::CopyCommand => vec![KeyCode::Char('Y')],
+            Action::CopyExe => vec![KeyCode::Char('b')],
+            Action::CopyCwd => vec![KeyCode::Char('.')],
+            Action::RevealExe => vec![KeyCode::Char('O')],
+            Action::Help => vec![KeyCode::Char('?')],
+            Action::Quit => vec![KeyCode::Char('q')],
+        }
+    }
+}
+
+pub(crate) struct KeyMap {
+    pub(crate) bindings: Vec<(KeyCode, Action)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .flat_map(|action| {
+                action
+                    .default_keys()
+                    .into_iter()
+                    .map(move |code| (code, *action))
+            })
+            .collect();
+
+        Self { bindings }
+    }
+}
+
+impl KeyMap {
+    // replaces the keys of `action`, the keys are taken away from any other action
+    pub(crate) fn bind(&mut self, action: Action, codes: &[KeyCode]) {
+        self.bindings
+            .retain(|(code, a)| *a != action && !codes.contains(code));
+        self.bindings
+            .extend(codes.iter().map(|code| (*code, action)));
+    }
+
+    pub(crate) fn action(&self, code: KeyCode) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, action)| *action)
+    }
+
+    // e.g. "Down/j"
+    pub(crate) fn keys(&self, action: Action) -> String {
+        let keys: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(code, _)| key_label(*code))
+            .collect();
+
+        if keys.is_empty() {
+            "unbound".to_string()
+        } else {
+            keys.join("/")
+        }
+    }
+}
+
+// the inverse of `key_label`, used for the keys in the config file
+pub(crate) fn parse_key(key: &str) -> Option<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+
+    let code = match key.to_lowercase().as_str() {
+        "space" => KeyCode::Char(' '),
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pgup" | "pageup" => KeyCode::PageUp,
+        "pgdn" | "pagedown" => KeyCode::PageDown,
+        other => {
+            let n = other.strip_prefix('f')?.parse().ok()?;
+            KeyCode::F(n)
+        }
+    };
+
+    Some(code)
+}
+
+fn key_label(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        code => format!("{:?}", code),
+    }
+}
+
+// rows moved per mouse wheel step
+const MOUSE_SCROLL_ROWS: isize = 3;
+
+// counts above this are taken as a typo rather than repeating a motion forever
+const MAX_COUNT: usize = 100_000;
+
+// what has been typed of a vim style key sequence in normal mode, e.g. the `5` of `5j`
+// or the first `g` of `gg`
+#[derive(Default)]
+pub(crate) struct PendingKeys {
+    count: Option<usize>,
+    g: bool,
+}
+
+impl PendingKeys {
+    // e.g. `5g`, empty when nothing is pending
+    pub(crate) fn label(&self) -> String {
+        let count = self.count.map(|n| n.to_string()).unwrap_or_default();
+        format!("{}{}", count, if self.g { "g" } else { "" })
+    }
+}
+
+// a count repeats the motions, `<n>G` and `<n>gg` go to row n, Ctrl-d and Ctrl-u move half a page
+fn handle_normal_key(app: &mut App, key: KeyEvent) {
+    let pending = std::mem::take(&mut app.pending_keys);
+    let count = pending.count;
+    let steps = count.unwrap_or(1) as isize;
+
+    // never fall through to the bound action, Ctrl-d is not a kill
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        match key.code {
+            KeyCode::Char('d') => app.half_page(steps),
+            KeyCode::Char('u') => app.half_page(-steps),
+            KeyCode::Char('t') => app.new_tab(),
+            KeyCode::Char('w') => app.close_tab(),
+            _ => {}
+        }
+        return;
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        if let KeyCode::Char(c @ '1'..='9') = key.code {
+            app.switch_tab(c as usize - '1' as usize);
+        }
+        return;
+    }
+    // extends the marks over the rows moved across, like in a file manager
+    if key.modifiers.contains(KeyModifiers::SHIFT) {
+        match key.code {
+            KeyCode::Down => return app.mark_and_move(steps),
+            KeyCode::Up => return app.mark_and_move(-steps),
+            _ => {}
+        }
+    }
+
+    let bound = app.config.keymap.action(key.code);
+    // no digit is bound by default, one bound in the config can only continue a count
+    if let KeyCode::Char(c @ '0'..='9') = key.code {
+        if count.is_some() || (c != '0' && bound.is_none()) {
+            let digit = c.to_digit(10).unwrap_or(0) as usize;
+            let count = count.unwrap_or(0) * 10 + digit;
+            app.pending_keys.count = Some(count.min(MAX_COUNT));
+            return;
+        }
+    }
+    if key.code == KeyCode::Char('g') && bound.is_none() {
+        if pending.g {
+            app.go_to_row(count.unwrap_or(1));
+        } else {
+            app.pending_keys = PendingKeys { count, g: true };
+        }
+        return;
+    }
+
+    let Some(action) = bound else {
+        return;
+    };
+    match (action, count) {
+        (Action::Next, Some(_)) => app.move_selection(steps),
+        (Action::Prev, Some(_)) => app.move_selection(-steps),
+        (Action::Last, Some(n)) => app.go_to_row(n),
+        (Action::PageDown | Action::PageUp | Action::ScrollLeft | Action::ScrollRight, Some(n)) => {
+            for _ in 0..n {
+                app.perform(action);
+            }
+        }
+        _ => app.perform(action),
+    }
+    app.update_visual();
+}
+
+pub(crate) fn handle_key(app: &mut App, key: KeyEvent) {
+    let code = key.code;
+    match app.input_mode {
+        InputMode::Normal => handle_normal_key(app, key),
+        InputMode::Help => match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => app.exit_input_mode(),
+            KeyCode::Down | KeyCode::Char('j') => app.scroll_help(1),
+            KeyCode::Up | KeyCode::Char('k') => app.scroll_help(-1),
+            _ => {}
+        },
+        InputMode::SignalMenu => match code {
+            KeyCode::Esc | KeyCode::Char('q') => app.exit_input_mode(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_signal(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_signal(),
+            KeyCode::Enter => app.send_chosen_signal(),
+            KeyCode::Char('#') => app.open_signal_input(),
+            _ => {}
+        },
+        InputMode::Columns => match code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') | KeyCode::Char('H') => {
+                app.close_columns()
+            }
+            KeyCode::Down | KeyCode::Char('j') => app.next_column(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_column(),
+            KeyCode::Char(' ') => app.toggle_column(),
+            KeyCode::Char('J') => app.move_column(1),
+            KeyCode::Char('K') => app.move_column(-1),
+            _ => {}
+        },
+        InputMode::Filters => match code {
+            KeyCode::Esc | KeyCode::Char('q') => app.exit_input_mode(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_filter(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_filter(),
+            KeyCode::Enter => app.apply_chosen_filter(),
+            _ => {}
+        },
+        InputMode::Confirm => match code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => app.confirm_pending_signal(),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.cancel_pending_signal(),
+            KeyCode::Char('s') | KeyCode::Char('S') => app.stop_pending_owner(),
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                app.control_pending_unit(UnitControl::Restart)
+            }
+            KeyCode::Char('k') | KeyCode::Char('K') => {
+                app.control_pending_container(ContainerControl::Kill)
+            }
+            _ => {}
+        },
+        InputMode::ConfirmCoreDump => match code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => app.confirm_core_dump(),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.cancel_core_dump(),
+            _ => {}
+        },
+        InputMode::Escalate => match code {
+            KeyCode::Char('k') | KeyCode::Char('K') => app.escalate(),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.dismiss_survivors(),
+            _ => {}
+        },
+        InputMode::Zombies => match code {
+            KeyCode::Char('c') | KeyCode::Char('C') => app.signal_zombie_parents(),
+            KeyCode::Char('k') | KeyCode::Char('K') => app.kill_zombie_parents(),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.dismiss_zombies(),
+            _ => {}
+        },
+        InputMode::Elevate => match code {
+            KeyCode::Char('s') | KeyCode::Char('S') => app.confirm_elevation(),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.cancel_elevation(),
+            _ => {}
+        },
+        InputMode::Services => match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('S') => app.exit_input_mode(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_service(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_service(),
+            KeyCode::Char('x') => app.control_selected_service(ServiceControl::Stop),
+            KeyCode::Char('g') => app.control_selected_service(ServiceControl::Start),
+            KeyCode::Char('r') => app.control_selected_service(ServiceControl::Restart),
+            _ => {}
+        },
+        InputMode::OpenFiles => match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('l') => app.exit_input_mode(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_open_file(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_open_file(),
+            _ => {}
+        },
+        InputMode::Limits => match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('U') => app.exit_input_mode(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_limit(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_limit(),
+            _ => {}
+        },
+        InputMode::Connections => match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('C') => app.exit_input_mode(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_connection(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_connection(),
+            _ => {}
+        },
+        InputMode::Threads => match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('T') => app.exit_input_mode(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_thread(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_thread(),
+            _ => {}
+        },
+        InputMode::ScheduleKill => match code {
+            KeyCode::Esc => app.exit_input_mode(),
+            KeyCode::Backspace => {
+                app.schedule_input.pop();
+            }
+            KeyCode::Enter => app.apply_schedule_kill(),
+            KeyCode::Char(c) => app.schedule_input.push(c),
+            _ => {}
+        },
+        InputMode::ScheduledKills => match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('W') => app.exit_input_mode(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_scheduled_kill(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_scheduled_kill(),
+            KeyCode::Char('d') | KeyCode::Delete => app.cancel_scheduled_kill(),
+            _ => {}
+        },
+        InputMode::History => match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('L') => app.exit_input_mode(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_history(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_history(),
+            _ => {}
+        },
+        InputMode::Exited => match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('X') => app.exit_input_mode(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_exited(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_exited(),
+            KeyCode::Enter => app.respawn_selected_exited(),
+            _ => {}
+        },
+        InputMode::Alerts => match code {
+            KeyCode::Esc | KeyCode::Char('q') => app.dismiss_alerts(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_alert(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_alert(),
+            KeyCode::Enter => app.select_alert(),
+            _ => {}
+        },
+        InputMode::Children => match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('D') => app.exit_input_mode(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_child(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_child(),
+            _ => {}
+        },
+        InputMode::Affinity => match code {
+            KeyCode::Esc => app.exit_input_mode(),
+            KeyCode::Backspace => {
+                app.affinity_input.pop();
+            }
+            KeyCode::Enter => app.apply_affinity(),
+            KeyCode::Char(c) => app.affinity_input.push(c),
+            _ => {}
+        },
+        InputMode::CoreDump => match code {
+            KeyCode::Esc => app.exit_input_mode(),
+            KeyCode::Char('q') => app.core_dump_signal(Signal::Quit),
+            KeyCode::Char('a') => app.core_dump_signal(Signal::Abort),
+            KeyCode::Char('g') => app.core_dump_gcore(),
+            _ => {}
+        },
+        InputMode::Spawn => match code {
+            KeyCode::Esc => app.exit_input_mode(),
+            KeyCode::Backspace => {
+                app.spawn_input.pop();
+            }
+            KeyCode::Enter => app.apply_spawn(),
+            KeyCode::Char(c) => app.spawn_input.push(c),
+            _ => {}
+        },
+        InputMode::SignalInput => match code {
+            KeyCode::Esc => app.exit_input_mode(),
+            KeyCode::Backspace => {
+                app.signal_input.pop();
+            }
+            KeyCode::Enter => app.apply_signal_input(),
+            KeyCode::Char(c) => app.signal_input.push(c),
+            _ => {}
+        },
+        InputMode::JumpToPid => match code {
+            KeyCode::Esc => app.exit_input_mode(),
+            KeyCode::Backspace => {
+                app.jump_input.pop();
+            }
+            KeyCode::Enter => app.apply_jump_to_pid(),
+            KeyCode::Char(c) => app.jump_input.push(c),
+            _ => {}
+        },
+        InputMode::Editing => match code {
+            KeyCode::Esc => app.exit_input_mode(),
+            KeyCode::Up => app.recall_search(true),
+            KeyCode::Down => app.recall_search(false),
+            KeyCode::Backspace => {
+                app.search_input.pop();
+                app.search_history.reset();
+                app.validate_search_input();
+            }
+            KeyCode::Enter => {
+                app.search();
+                if app.search_error.is_none() {
+                    app.exit_input_mode();
+                }
+            }
+            KeyCode::Char(c) => {
+                app.search_input.push(c);
+                app.search_history.reset();
+                app.validate_search_input();
+            }
+            _ => {}
+        },
+    }
+}
+
+pub(crate) fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    // popups and the search input keep the keyboard focus
+    if !matches!(app.input_mode, InputMode::Normal) {
+        return;
+    }
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => app.click(mouse.column, mouse.row),
+        MouseEventKind::ScrollDown => app.scroll(MOUSE_SCROLL_ROWS),
+        MouseEventKind::ScrollUp => app.scroll(-MOUSE_SCROLL_ROWS),
+        _ => {}
+    }
+}
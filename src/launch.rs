@@ -0,0 +1,146 @@
+// starting processes from the TUI, detached so they outlive it and don't write over the table
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+};
+
+use sysinfo::{Process, ProcessExt};
+
+// what a process was started with, remembered when it is killed so it can be started again
+#[derive(Clone)]
+pub struct Launch {
+    pub cmd: Vec<String>,
+    // none when it couldn't be read, the new process gets ours then
+    pub cwd: Option<PathBuf>,
+    // `KEY=value` entries, empty when they couldn't be read, the new process gets ours then
+    pub environ: Vec<String>,
+    // the uid and gid it ran as, none for the commands typed in the TUI, which run as us
+    pub owner: Option<(u32, u32)>,
+}
+
+impl Launch {
+    // none for kernel threads and the processes whose command line can't be read
+    pub fn of(process: &Process) -> Option<Self> {
+        if process.cmd().is_empty() {
+            return None;
+        }
+        let cwd = process.cwd();
+        #[cfg(unix)]
+        let owner = process
+            .user_id()
+            .zip(process.group_id())
+            .map(|(uid, gid)| (**uid, *gid));
+        #[cfg(not(unix))]
+        let owner = None;
+        Some(Self {
+            cmd: process.cmd().to_vec(),
+            cwd: (!cwd.as_os_str().is_empty()).then(|| cwd.to_path_buf()),
+            environ: process.environ().to_vec(),
+            owner,
+        })
+    }
+
+    // a command line typed in the TUI, run by the shell for its pipes, globs and variables
+    pub fn shell(command: &str) -> Self {
+        let shell: &[&str] = if cfg!(windows) {
+            &["cmd", "/C"]
+        } else {
+            &["sh", "-c"]
+        };
+        let mut cmd: Vec<String> = shell.iter().map(|arg| arg.to_string()).collect();
+        cmd.push(command.to_string());
+        Self {
+            cmd,
+            cwd: None,
+            environ: vec![],
+            owner: None,
+        }
+    }
+
+    // the pid of the new process
+    pub fn spawn(&self) -> io::Result<u32> {
+        let (program, args) = self
+            .cmd
+            .split_first()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty command line"))?;
+
+        // `./server` is relative to where it was started, not to where we run
+        let mut program = PathBuf::from(program);
+        if let Some(cwd) = &self.cwd {
+            if program.is_relative() && program.components().count() > 1 {
+                program = cwd.join(program);
+            }
+        }
+
+        let mut command = Command::new(program);
+        command
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        if let Some(cwd) = &self.cwd {
+            command.current_dir(cwd);
+        }
+        if !self.environ.is_empty() {
+            command.env_clear().envs(
+                self.environ
+                    .iter()
+                    .filter_map(|entry| entry.split_once('=')),
+            );
+        }
+        // its own process group, so Ctrl-C in the terminal doesn't reach it
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+
+            // as root, e.g. under sudo, another user's process is started as them again and not
+            // as root with their command line and environment
+            if let Some((uid, gid)) = self.owner {
+                let euid = unsafe { libc::geteuid() };
+                if euid == 0 {
+                    command.uid(uid).gid(gid);
+                } else if uid != euid {
+                    return Err(io::Error::new(
+                        io::ErrorKind::PermissionDenied,
+                        format!("it ran as uid {}, only root can start it as them", uid),
+                    ));
+                }
+            }
+        }
+
+        let mut child = command.spawn()?;
+        let pid = child.id();
+        // reaped once it exits, so it doesn't stay a zombie while the TUI runs
+        thread::spawn(move || child.wait());
+        Ok(pid)
+    }
+}
+
+// shows the file in the file manager, selected where the platform allows it, or else its directory
+pub fn reveal(path: &Path) -> io::Result<()> {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("explorer");
+        command.arg(format!("/select,{}", path.display()));
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    } else {
+        let mut command = Command::new("xdg-open");
+        command.arg(path.parent().unwrap_or(path));
+        command
+    };
+
+    // the file manager may be started by it and keep running
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    let mut child = command.spawn()?;
+    thread::spawn(move || child.wait());
+    Ok(())
+}
@@ -0,0 +1,33 @@
+// the listing, filtering and signalling behind the process_killer TUI and subcommands
+pub mod api;
+pub mod app;
+pub mod audit;
+pub mod cgroup;
+pub mod clipboard;
+pub mod collector;
+pub mod columns;
+pub mod config;
+pub mod container;
+pub mod error;
+mod events;
+pub mod export;
+pub mod files;
+mod filter_expr;
+mod gpu;
+mod http;
+pub mod input;
+pub mod launch;
+pub mod limits;
+pub mod metrics;
+pub mod net;
+pub mod notify;
+pub mod process;
+pub mod refresh;
+mod rules;
+pub mod search_history;
+pub mod services;
+pub mod systemd;
+pub mod ui;
+mod websocket;
+
+pub use process::{ProcessInfo, ProcessManager};
@@ -0,0 +1,63 @@
+// the resource limits of a process, like prlimit -p, to tell a process about to run out of
+// open files or memory
+use std::{fs, io};
+
+use sysinfo::{Pid, PidExt};
+
+pub struct ResourceLimit {
+    // e.g. `Max open files`
+    pub name: String,
+    // `unlimited` or a number in `units`
+    pub soft: String,
+    pub hard: String,
+    // empty for the priorities
+    pub units: String,
+}
+
+impl ResourceLimit {
+    // none when unlimited
+    pub fn soft_value(&self) -> Option<u64> {
+        self.soft.parse().ok()
+    }
+}
+
+// the limits of `pid` in the order the kernel lists them, reading another user's needs root
+pub fn resource_limits(pid: Pid) -> io::Result<Vec<ResourceLimit>> {
+    if cfg!(not(target_os = "linux")) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "resource limits are only available on linux",
+        ));
+    }
+
+    let content = fs::read_to_string(format!("/proc/{}/limits", pid.as_u32()))?;
+    let mut lines = content.lines();
+    // the columns are aligned under the header, and the names have spaces
+    let header = lines.next().unwrap_or_default();
+    let column = |title: &str| {
+        header
+            .find(title)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unexpected limits format"))
+    };
+    let (soft, hard, units) = (
+        column("Soft Limit")?,
+        column("Hard Limit")?,
+        column("Units")?,
+    );
+
+    let field = |line: &str, start: usize, end: usize| {
+        line.get(start..end.min(line.len()))
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    Ok(lines
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| ResourceLimit {
+            name: field(line, 0, soft),
+            soft: field(line, soft, hard),
+            hard: field(line, hard, units),
+            units: field(line, units, line.len()),
+        })
+        .collect())
+}
@@ -1,164 +1,613 @@
-use std::{error::Error, io};
+use std::{
+    collections::HashSet,
+    env,
+    error::Error,
+    io::{self, IsTerminal, Write},
+    net::IpAddr,
+    panic,
+    path::PathBuf,
+    process, thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
+use clap::{Parser, Subcommand};
 use crossterm::{
-    event::{self, DisableMouseCapture, Event, KeyCode},
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, Clear, LeaveAlternateScreen},
 };
-use sysinfo::{Pid, ProcessExt, System, SystemExt};
-use tui::{
-    backend::{Backend, CrosstermBackend},
-    layout::{Constraint, Layout},
-    style::{Color, Style},
-    text::{Span, Spans, Text},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
-    Frame, Terminal,
+use process_killer::{
+    api::{self, ProcessRecord},
+    app::{run_app, App},
+    collector,
+    config::Config,
+    metrics,
+    net::listening_pids,
+    process::{
+        format_bytes, format_timestamp, parse_signal, send_signal, signal_name, SignalError,
+        SortColumn, SortOrder, SIGNALS,
+    },
+    ProcessManager,
 };
+use regex::Regex;
+use sysinfo::{Pid, PidExt, Process, ProcessExt, Signal, System, SystemExt};
+use tui::{backend::CrosstermBackend, Terminal};
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Read the config from this file instead of the default location
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Don't ask for confirmation before sending a signal
+    #[arg(long)]
+    no_confirm: bool,
+
+    /// Open the TUI sorted by this column, e.g. cpu or memory
+    #[arg(long, value_name = "COLUMN", value_parser = parse_sort_column)]
+    sort: Option<SortColumn>,
+
+    /// Direction of --sort, or of the sort in the config
+    #[arg(long, value_parser = parse_sort_order)]
+    order: Option<SortOrder>,
+
+    /// Open the TUI with this search applied, written like in the search input
+    #[arg(long, value_name = "SEARCH")]
+    filter: Option<String>,
+
+    /// Open the TUI in the tree view
+    #[arg(long)]
+    tree: bool,
+
+    /// Open the TUI with the details pane shown
+    #[arg(long)]
+    details: bool,
+
+    /// Signal every process whose command line matches REGEX and exit, without starting the TUI
+    #[arg(long, value_name = "REGEX")]
+    kill_matching: Option<String>,
+
+    /// Signal sent by --kill-matching
+    #[arg(
+        long,
+        default_value = "SIGTERM",
+        value_parser = parse_signal_arg,
+        requires = "kill_matching"
+    )]
+    signal: Signal,
+
+    /// Only log what would be killed without sending anything, in the TUI and the subcommands
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Serve the CPU and memory of the processes in the Prometheus format on PORT instead of
+    /// starting the TUI
+    #[arg(long, value_name = "PORT")]
+    metrics_port: Option<u16>,
+
+    /// Address the metrics are served on, e.g. 0.0.0.0 for a Prometheus on another machine
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        default_value = "127.0.0.1",
+        requires = "metrics_port"
+    )]
+    metrics_listen: IpAddr,
+
+    /// Run on HOST over ssh instead, e.g. user@web-1, with process_killer installed there
+    #[arg(long, value_name = "HOST")]
+    host: Option<String>,
+
+    /// Passed on by --host, the title then names the machine
+    #[arg(long, hide = true)]
+    show_host: bool,
+
+    /// Disable everything that signals, renices, starts or stops processes, to only monitor
+    #[arg(long)]
+    read_only: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
 
-enum SortByNameOptions {
-    ASC,
-    DESC,
-    NONE,
+// without a subcommand the TUI is started
+#[derive(Subcommand)]
+enum Command {
+    /// Send a signal to every process matching a name, without starting the TUI
+    Kill {
+        /// Process name, matched as a substring unless --exact is given
+        name: String,
+
+        /// Signal to send, e.g. TERM, SIGKILL or HUP
+        #[arg(short, long, default_value = "SIGTERM", value_parser = parse_signal_arg)]
+        signal: Signal,
+
+        /// Only match processes whose name is exactly NAME
+        #[arg(short, long)]
+        exact: bool,
+
+        /// Print what would be killed without sending anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Send a signal to the processes listening on a TCP or UDP port, without starting the TUI
+    Port {
+        port: u16,
+
+        /// Signal to send, e.g. TERM, SIGKILL or HUP
+        #[arg(short, long, default_value = "SIGTERM", value_parser = parse_signal_arg)]
+        signal: Signal,
+
+        /// Print what would be killed without sending anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Keep running and signal every process whose command line matches REGEX, including new ones
+    Watch {
+        /// Matched against the full command line of every process
+        #[arg(value_name = "REGEX")]
+        pattern: String,
+
+        /// Signal to send, e.g. TERM, SIGKILL or HUP
+        #[arg(short, long, default_value = "SIGTERM", value_parser = parse_signal_arg)]
+        signal: Signal,
+
+        /// Seconds between two checks
+        #[arg(short, long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+
+        /// Print what would be killed without sending anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Serve a JSON API to list processes, show one and signal it, for dashboards and scripts
+    Serve {
+        /// Address to listen on, e.g. 0.0.0.0:7878 for other machines
+        #[arg(short, long, default_value = "127.0.0.1:7878")]
+        listen: String,
+    },
+    /// Keep a CPU history of every process for the details view of the TUI
+    Daemon {
+        /// Seconds between samples
+        #[arg(short, long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+    /// Print the process table and exit
+    List {
+        /// Print a JSON array instead of a table
+        #[arg(long)]
+        json: bool,
+    },
 }
 
-enum InputMode {
-    NORMAL,
-    EDITING,
+fn parse_sort_column(name: &str) -> Result<SortColumn, String> {
+    SortColumn::from_name(name).ok_or_else(|| {
+        format!(
+            "unknown column, expected one of {}",
+            SortColumn::names().join(", ")
+        )
+    })
 }
 
-struct App {
-    state: TableState,
-    processes: Vec<(Pid, String)>,
-    sort_by_name_option: SortByNameOptions,
-    search_input: String,
-    input_mode: InputMode,
+fn parse_sort_order(name: &str) -> Result<SortOrder, String> {
+    SortOrder::from_name(name).ok_or_else(|| "expected asc or desc".to_string())
 }
 
-impl App {
-    fn new() -> Self {
-        let mut processes = vec![];
-        let s = System::new_all();
-        for process in s.processes() {
-            processes.push((*process.0, process.1.name().to_string()));
-        }
-        processes.sort_by(|a, b| a.1.cmp(&b.1));
-
-        Self {
-            processes: processes,
-            state: TableState::default(),
-            sort_by_name_option: SortByNameOptions::NONE,
-            search_input: String::new(),
-            input_mode: InputMode::NORMAL,
-        }
+fn parse_signal_arg(name: &str) -> Result<Signal, String> {
+    parse_signal(name).ok_or_else(|| {
+        let names: Vec<&str> = SIGNALS.iter().map(|(_, n)| *n).collect();
+        format!(
+            "unknown signal, expected a number or a name like {}",
+            names.join(", ")
+        )
+    })
+}
+
+// `process_killer kill`, signals every matching process and prints what it did
+fn kill_by_name(
+    config: &Config,
+    name: &str,
+    signal: Signal,
+    exact: bool,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let manager = ProcessManager::new();
+    let targets = manager.find_by_name(name, exact);
+    if targets.is_empty() {
+        return Err(format!("no process matches '{}'", name).into());
     }
 
-    pub fn switch_sort(&mut self) {
-        // if sort option is NONE then set to ASC otherwise toggle ASC and DESC
-        self.sort_by_name_option = match self.sort_by_name_option {
-            SortByNameOptions::ASC => SortByNameOptions::DESC,
-            SortByNameOptions::DESC => SortByNameOptions::ASC,
-            SortByNameOptions::NONE => SortByNameOptions::ASC,
-        };
+    signal_processes(config, &targets, signal, dry_run)
+}
 
-        match self.sort_by_name_option {
-            SortByNameOptions::ASC => {
-                self.processes.sort_by(|a, b| a.1.cmp(&b.1));
-            }
-            SortByNameOptions::DESC => {
-                self.processes.sort_by(|a, b| b.1.cmp(&a.1));
-            }
-            _ => {}
-        };
+// `process_killer port`, signals whatever listens on `port`
+fn kill_by_port(
+    config: &Config,
+    port: u16,
+    signal: Signal,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let manager = ProcessManager::new();
+    let targets: Vec<&Process> = listening_pids(port)?
+        .into_iter()
+        .filter_map(|pid| manager.process(pid))
+        .collect();
+    if targets.is_empty() {
+        return Err(format!(
+            "nothing is listening on port {} (other users' processes are only found as root)",
+            port
+        )
+        .into());
+    }
+
+    signal_processes(config, &targets, signal, dry_run)
+}
+
+// `--kill-matching`, signals every process whose command line matches `pattern`
+fn kill_matching(
+    config: &Config,
+    pattern: &str,
+    signal: Signal,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let regex = Regex::new(pattern)?;
+    let manager = ProcessManager::new();
+    let targets = manager.find_matching(&regex);
+
+    // nothing left to clean up is not an error for cron jobs and CI scripts
+    if targets.is_empty() {
+        println!("no process matches '{}'", pattern);
+        return Ok(());
     }
 
-    pub fn next(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i >= self.processes.len() {
-                    0
-                } else {
-                    i + 1
+    signal_processes(config, &targets, signal, dry_run)
+}
+
+// `process_killer watch`, keeps signalling every process matching `pattern` until interrupted
+fn watch_and_kill(
+    config: &Config,
+    pattern: &str,
+    signal: Signal,
+    interval: Duration,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let regex = Regex::new(pattern)?;
+    let mut manager = ProcessManager::new();
+    let signal_name = signal_name(signal);
+    // don't signal a process again while it is still shutting down
+    let mut signalled: HashSet<Pid> = HashSet::new();
+
+    println!(
+        "[{}] watching for '{}' every {}s, Ctrl-C to stop",
+        format_timestamp(now()),
+        pattern,
+        interval.as_secs()
+    );
+    loop {
+        signalled.retain(|pid| manager.process(*pid).is_some());
+
+        let targets = manager
+            .find_matching(&regex)
+            .into_iter()
+            .filter(|p| !signalled.contains(&p.pid()) && !config.is_protected(p))
+            .collect::<Vec<_>>();
+        for process in targets {
+            signalled.insert(process.pid());
+            if dry_run {
+                println!(
+                    "[{}] would send {} to {} {}",
+                    format_timestamp(now()),
+                    signal_name,
+                    process.pid(),
+                    process.name()
+                );
+                continue;
+            }
+
+            let sent = send_signal(process, signal);
+            audit(config, process, signal, &sent);
+            let result = match sent {
+                Ok(()) => "sent",
+                Err(SignalError::PermissionDenied) => "permission denied sending",
+                Err(SignalError::Unsupported) => {
+                    return Err(format!("{} is not supported on this platform", signal_name).into())
                 }
+                Err(_) => "failed to send",
+            };
+            println!(
+                "[{}] {} {} to {} {}",
+                format_timestamp(now()),
+                result,
+                signal_name,
+                process.pid(),
+                process.name()
+            );
+        }
+
+        thread::sleep(interval);
+        manager.refresh();
+    }
+}
+
+// `--host`, runs process_killer on the host with the rest of the arguments, in a terminal there
+// unless the output goes to a pipe, so `--host web-1 list --json | jq` works too
+fn run_on_host(host: &str) -> Result<(), Box<dyn Error>> {
+    let mut args = vec!["process_killer".to_string(), "--show-host".to_string()];
+    let mut given = env::args().skip(1);
+    while let Some(arg) = given.next() {
+        // the path is one on this machine, the other one reads its own config
+        if arg == "--config" || arg.starts_with("--config=") {
+            if arg == "--config" {
+                given.next();
             }
-            None => 0,
-        };
+            eprintln!("--config is not passed on, {} reads its own config", host);
+        } else if arg == "--host" {
+            given.next();
+        } else if !arg.starts_with("--host=") {
+            args.push(arg);
+        }
+    }
+    // ssh runs the arguments through the remote shell
+    let command: Vec<String> = args.iter().map(|arg| shell_quote(arg)).collect();
+
+    let mut ssh = process::Command::new("ssh");
+    if io::stdout().is_terminal() {
+        ssh.arg("-t");
+    }
+    let status = ssh
+        .arg(host)
+        .arg("--")
+        .arg(command.join(" "))
+        .status()
+        .map_err(|err| format!("could not run ssh: {}", err))?;
+    process::exit(status.code().unwrap_or(1));
+}
+
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+// seconds since the unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
 
-        self.state.select(Some(i))
+// appends a signal to the audit log, failing to is only a warning
+fn audit(config: &Config, process: &Process, signal: Signal, result: &Result<(), SignalError>) {
+    let Some(audit_log) = &config.audit_log else {
+        return;
+    };
+    let result = result.as_ref().map_err(|err| err.to_string()).copied();
+    if let Err(err) = audit_log.record(process, signal, &result) {
+        eprintln!("could not write to the audit log: {}", err);
     }
+}
+
+// sends `signal` to every target, printing one line per process
+fn signal_processes(
+    config: &Config,
+    targets: &[&Process],
+    signal: Signal,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let signal_name = signal_name(signal);
+    let mut failed = 0;
+    let mut denied = 0;
+    for process in targets {
+        if config.is_protected(process) {
+            eprintln!(
+                "refusing to send {} to protected process {} {}",
+                signal_name,
+                process.pid(),
+                process.name()
+            );
+            continue;
+        }
+
+        if dry_run {
+            println!(
+                "would send {} to {} {}",
+                signal_name,
+                process.pid(),
+                process.name()
+            );
+            continue;
+        }
 
-    pub fn prev(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i <= 0 {
-                    self.processes.len() - 1
-                } else {
-                    i - 1
+        let result = send_signal(process, signal);
+        audit(config, process, signal, &result);
+        match result {
+            Ok(()) => println!(
+                "sent {} to {} {}",
+                signal_name,
+                process.pid(),
+                process.name()
+            ),
+            Err(SignalError::Unsupported) => {
+                return Err(format!("{} is not supported on this platform", signal_name).into())
+            }
+            Err(err) => {
+                failed += 1;
+                if let SignalError::PermissionDenied = err {
+                    denied += 1;
                 }
+                eprintln!(
+                    "failed to send {} to {} {}: {}",
+                    signal_name,
+                    process.pid(),
+                    process.name(),
+                    err
+                );
             }
-            None => 0,
+        }
+    }
+
+    if denied > 0 {
+        let elevated = if cfg!(windows) {
+            "from an administrator prompt"
+        } else {
+            "with sudo"
         };
+        eprintln!(
+            "{} process(es) belong to another user, run the same command {} to signal them",
+            denied, elevated
+        );
+    }
+    if failed > 0 {
+        return Err(format!("{} process(es) could not be signalled", failed).into());
+    }
 
-        self.state.select(Some(i))
+    Ok(())
+}
+
+// `process_killer list`, samples twice so the cpu usage is meaningful
+fn list_processes(json: bool) -> Result<(), Box<dyn Error>> {
+    let mut manager = ProcessManager::new();
+    thread::sleep(System::MINIMUM_CPU_UPDATE_INTERVAL);
+    manager.refresh();
+
+    let mut processes = manager.processes();
+    processes.sort_by_key(|p| p.pid);
+
+    let mut stdout = io::stdout().lock();
+    if json {
+        let records: Vec<ProcessRecord> = processes.iter().map(ProcessRecord::from).collect();
+        serde_json::to_writer_pretty(&mut stdout, &records)?;
+        writeln!(stdout)?;
+        return Ok(());
     }
 
-    pub fn kill(&mut self) {
-        let process = self.processes[self.state.selected().unwrap()].clone();
-        let s = System::new_all();
-        s.process(process.0).unwrap().kill();
-        self.refetch_process()
+    writeln!(
+        stdout,
+        "{:>8} {:>6} {:>10} {:<12} NAME",
+        "PID", "CPU%", "MEMORY", "USER"
+    )?;
+    for p in &processes {
+        writeln!(
+            stdout,
+            "{:>8} {:>6.1} {:>10} {:<12} {}",
+            p.pid.as_u32(),
+            p.cpu_usage,
+            format_bytes(p.memory),
+            p.user,
+            p.name
+        )?;
     }
 
-    pub fn refetch_process(&mut self) {
-        let s = System::new_all();
+    Ok(())
+}
 
-        self.processes.clear();
-        for process in s.processes() {
-            self.processes
-                .push((*process.0, process.1.name().to_string()));
-        }
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    if let Some(host) = &cli.host {
+        return run_on_host(host);
     }
+    // before touching the terminal, so config errors are printed normally
+    let mut config = Config::load(cli.config.clone())?;
+    if cli.no_confirm {
+        config.confirm_kill = false;
+    }
+    config.dry_run |= cli.dry_run;
+    config.read_only |= cli.read_only;
+    config.show_host = cli.show_host;
+    let signals = cli.kill_matching.is_some()
+        || matches!(
+            cli.command,
+            Some(Command::Kill { .. } | Command::Port { .. } | Command::Watch { .. })
+        );
+    if config.read_only && signals {
+        return Err("no signal is sent in read-only mode".into());
+    }
+    let startup = &mut config.startup;
+    // an order alone turns the configured sort around, or sorts by name
+    match (cli.sort, cli.order, startup.sort) {
+        (Some(column), order, _) => startup.sort = Some((column, order.unwrap_or(SortOrder::Asc))),
+        (None, Some(order), Some((column, _))) => startup.sort = Some((column, order)),
+        (None, Some(order), None) => startup.sort = Some((SortColumn::Name, order)),
+        (None, None, _) => {}
+    }
+    if cli.filter.is_some() {
+        startup.filter = cli.filter.clone();
+    }
+    startup.tree_view |= cli.tree;
+    startup.show_details |= cli.details;
 
-    pub fn enter_input_mode(&mut self) {
-        self.input_mode = InputMode::EDITING;
+    if let Some(pattern) = &cli.kill_matching {
+        return kill_matching(&config, pattern, cli.signal, config.dry_run);
     }
 
-    pub fn exit_input_mode(&mut self) {
-        self.input_mode = InputMode::NORMAL;
+    if let Some(port) = cli.metrics_port {
+        return Ok(metrics::serve(&config, cli.metrics_listen, port)?);
     }
 
-    pub fn search(&mut self) {
-        self.refetch_process();
-        if self.search_input == "" {
-            return;
+    match cli.command {
+        Some(Command::Kill {
+            name,
+            signal,
+            exact,
+            dry_run,
+        }) => return kill_by_name(&config, &name, signal, exact, dry_run || config.dry_run),
+        Some(Command::Port {
+            port,
+            signal,
+            dry_run,
+        }) => return kill_by_port(&config, port, signal, dry_run || config.dry_run),
+        Some(Command::Watch {
+            pattern,
+            signal,
+            interval,
+            dry_run,
+        }) => {
+            let interval = Duration::from_secs(interval);
+            return watch_and_kill(
+                &config,
+                &pattern,
+                signal,
+                interval,
+                dry_run || config.dry_run,
+            );
         }
-        let mut filtered_process: Vec<(Pid, String)> = vec![];
-        self.processes.iter().for_each(|(pid, name)| {
-            if self.search_input.contains(name) || name.contains(&self.search_input.to_string()) {
-                filtered_process.push((*pid, name.to_string()));
-            }
-        });
-
-        self.processes = filtered_process;
+        Some(Command::Serve { listen }) => {
+            // not an argument, where every user could read it in the process list
+            let token = env::var("PROCESS_KILLER_TOKEN")
+                .ok()
+                .or(config.api_token.clone())
+                .filter(|token| !token.is_empty())
+                .ok_or("set a token in PROCESS_KILLER_TOKEN or api_token in the config")?;
+            return Ok(api::serve(&config, &listen, &token)?);
+        }
+        Some(Command::Daemon { interval }) => {
+            return Ok(collector::run_daemon(Duration::from_secs(interval))?)
+        }
+        Some(Command::List { json }) => return list_processes(json),
+        None => {}
     }
-}
 
-fn main() -> Result<(), Box<dyn Error>> {
-    enable_raw_mode().expect("Could not enable raw mode");
+    // a panic would otherwise leave the terminal in raw mode
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+        default_hook(info);
+    }));
+
+    enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(
         stdout,
         LeaveAlternateScreen,
-        DisableMouseCapture,
+        EnableMouseCapture,
         Clear(crossterm::terminal::ClearType::All)
-    )
-    .unwrap();
+    )?;
 
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend).unwrap();
+    let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
+    let mut app = App::new(config);
     let res = run_app(&mut terminal, &mut app);
-    // terminal.draw(f)
 
     disable_raw_mode()?;
     execute!(
@@ -174,119 +623,3 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
-
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<()> {
-    loop {
-        terminal.draw(|f| ui(f, app))?;
-
-        if let Event::Key(key) = event::read()? {
-            match app.input_mode {
-                InputMode::NORMAL => match key.code {
-                    KeyCode::Down => app.next(),
-                    KeyCode::Up => app.prev(),
-                    KeyCode::Char('d') => app.kill(),
-                    KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Char('n') => app.switch_sort(),
-                    KeyCode::Char('j') => app.next(),
-                    KeyCode::Char('k') => app.prev(),
-                    KeyCode::Char('i') => app.enter_input_mode(),
-                    KeyCode::Char('r') => app.refetch_process(),
-                    _ => {}
-                },
-                InputMode::EDITING => match key.code {
-                    KeyCode::Esc => app.exit_input_mode(),
-                    KeyCode::Backspace => {
-                        app.search_input.pop();
-                    }
-                    KeyCode::Enter => {
-                        app.search();
-                        app.exit_input_mode();
-                    }
-                    KeyCode::Char(c) => app.search_input.push(c),
-                    _ => {}
-                },
-            }
-        }
-    }
-}
-
-fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
-    let size = f.size();
-
-    let input_block = Block::default()
-        .title("Search process by name")
-        .borders(Borders::ALL);
-
-    let main_block = Block::default()
-        .borders(Borders::ALL)
-        .title("Process Killer By @himanshurajora, The Vedik Dev")
-        .title_alignment(tui::layout::Alignment::Right);
-
-    let instruction_block = Block::default()
-        .borders(Borders::ALL)
-        .title("Instructions")
-        .style(Style::default().fg(Color::Green));
-
-    let rows = app.processes.iter().enumerate().map(|(i, f)| {
-        let index = Cell::from(i.to_string());
-        let pid = Cell::from(f.0.to_string());
-        let name = Cell::from(f.1.to_string());
-
-        Row::new([index, pid, name])
-    });
-
-    let selected_style = Style::default().bg(Color::Red);
-
-    let table = Table::new(rows)
-        .header(Row::new([
-            Cell::from("S.N."),
-            Cell::from("PID"),
-            Cell::from("Name"),
-        ]))
-        .block(main_block)
-        .highlight_style(selected_style)
-        .highlight_symbol(">> ")
-        .widths(&[
-            Constraint::Percentage(10),
-            Constraint::Length(20),
-            Constraint::Min(70),
-        ]);
-
-    let chunks = Layout::default()
-        .direction(tui::layout::Direction::Vertical)
-        .constraints([
-            Constraint::Percentage(10),
-            Constraint::Percentage(80),
-            Constraint::Percentage(10),
-        ])
-        .split(size);
-
-    let instructions = vec![
-        Span::from("'D to Destory/Kill', 'N for toggle sorting', J/K or Up/Down to navigate, 'R to refetch','Q to quit', 'I to input mode', 'Esc to exit input mode', 'Enter to search'"),
-    ];
-
-    let sort_name_text = Text::from(Spans::from(instructions));
-    let paragraph = Paragraph::new(sort_name_text).block(instruction_block);
-
-    let mut char_spans = vec![];
-    app.search_input
-        .chars()
-        .into_iter()
-        .for_each(|c| char_spans.push(Span::from(c.to_string())));
-
-    let input_text = Text::from(Spans::from(char_spans));
-    let width = input_text.width() as u16;
-
-    let input_paragraph = match &app.input_mode {
-        InputMode::NORMAL => Paragraph::new(app.search_input.as_ref()).block(input_block),
-        InputMode::EDITING => Paragraph::new(input_text)
-            .style(Style::default().fg(Color::Yellow))
-            .block(input_block),
-    };
-
-    f.set_cursor(chunks[0].x + width + 1, chunks[0].y + 1);
-    f.render_widget(input_paragraph, chunks[0]);
-    f.render_stateful_widget(table, chunks[1], &mut app.state);
-    // f.render_widget(instruction_block, chunks[1]);
-    f.render_widget(paragraph, chunks[2]);
-}
@@ -0,0 +1,210 @@
+// `--metrics-port`, the cpu and memory of the processes matching the configured `metrics`
+// patterns in the prometheus exposition format, instead of the TUI
+use std::{
+    fmt::Write,
+    io,
+    net::{IpAddr, TcpListener},
+    sync::Mutex,
+};
+
+use regex::Regex;
+use sysinfo::PidExt;
+
+use crate::{
+    config::Config,
+    http::{read_request, respond, serve_connections},
+    ProcessInfo, ProcessManager,
+};
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+// a value of every exported process
+struct Gauge {
+    name: &'static str,
+    help: &'static str,
+    value: fn(&ProcessInfo) -> f64,
+}
+
+const GAUGES: [Gauge; 3] = [
+    Gauge {
+        name: "process_killer_cpu_usage_percent",
+        help: "CPU usage since the previous scrape, 100 per fully used core.",
+        value: |process| process.cpu_usage as f64,
+    },
+    Gauge {
+        name: "process_killer_memory_bytes",
+        help: "Resident memory.",
+        value: |process| process.memory as f64,
+    },
+    Gauge {
+        name: "process_killer_virtual_memory_bytes",
+        help: "Virtual memory.",
+        value: |process| process.virtual_memory as f64,
+    },
+];
+
+// answers the scrapes until killed, each connection on its own thread, the cpu usage covers the
+// time since the last one
+pub fn serve(config: &Config, address: IpAddr, port: u16) -> io::Result<()> {
+    let listener = TcpListener::bind((address, port))?;
+    let manager = Mutex::new(ProcessManager::new());
+    println!(
+        "serving metrics on http://{}/metrics, Ctrl-C to stop",
+        listener.local_addr()?
+    );
+
+    serve_connections(&listener, |stream| {
+        let Ok(request) = read_request(&stream) else {
+            return;
+        };
+        let result = match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/metrics") => {
+                let body = {
+                    let mut manager = manager.lock().unwrap_or_else(|err| err.into_inner());
+                    manager.refresh();
+                    render(&manager.processes(), &config.metrics)
+                };
+                respond(&stream, 200, CONTENT_TYPE, &body)
+            }
+            ("GET", _) => respond(&stream, 404, CONTENT_TYPE, "only /metrics is served\n"),
+            _ => respond(&stream, 405, CONTENT_TYPE, "only GET is supported\n"),
+        };
+        // the scraper went away, it tries again on its own
+        if let Err(err) = result {
+            eprintln!("could not answer a scrape: {}", err);
+        }
+    });
+    Ok(())
+}
+
+// every process matching one of `patterns`, labelled with the first it matches, or every process
+// when there are none
+fn render(processes: &[ProcessInfo], patterns: &[Regex]) -> String {
+    let mut matched: Vec<(&ProcessInfo, Option<&Regex>)> = vec![];
+    for process in processes {
+        if patterns.is_empty() {
+            matched.push((process, None));
+            continue;
+        }
+        let pattern = patterns
+            .iter()
+            .find(|pattern| pattern.is_match(&process.name) || pattern.is_match(&process.cmd));
+        if pattern.is_some() {
+            matched.push((process, pattern));
+        }
+    }
+    matched.sort_by_key(|(process, _)| process.pid);
+
+    let labels = |process: &ProcessInfo, pattern: Option<&Regex>| {
+        let mut labels = format!(
+            "pid=\"{}\",name=\"{}\",user=\"{}\"",
+            process.pid.as_u32(),
+            escape(&process.name),
+            escape(&process.user)
+        );
+        if let Some(pattern) = pattern {
+            let _ = write!(labels, ",pattern=\"{}\"", escape(pattern.as_str()));
+        }
+        labels
+    };
+
+    let mut out = String::new();
+    for gauge in &GAUGES {
+        let _ = writeln!(
+            out,
+            "# HELP {} {}\n# TYPE {} gauge",
+            gauge.name, gauge.help, gauge.name
+        );
+        for (process, pattern) in &matched {
+            let _ = writeln!(
+                out,
+                "{}{{{}}} {}",
+                gauge.name,
+                labels(process, *pattern),
+                (gauge.value)(process)
+            );
+        }
+    }
+
+    // zero when nothing matches, to alert on a service that is gone
+    if !patterns.is_empty() {
+        let name = "process_killer_matching_processes";
+        let _ = writeln!(
+            out,
+            "# HELP {} Processes matching the pattern.\n# TYPE {} gauge",
+            name, name
+        );
+        for pattern in patterns {
+            let count = matched
+                .iter()
+                .filter(|(_, matched)| matched.is_some_and(|p| p.as_str() == pattern.as_str()))
+                .count();
+            let _ = writeln!(
+                out,
+                "{}{{pattern=\"{}\"}} {}",
+                name,
+                escape(pattern.as_str()),
+                count
+            );
+        }
+    }
+    out
+}
+
+// a label value, quoted by the caller
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(escape(r#"C:\bin\"x""#), r#"C:\\bin\\\"x\""#);
+        assert_eq!(escape("two\nlines"), "two\\nlines");
+        assert_eq!(escape("plain"), "plain");
+    }
+
+    #[test]
+    fn labels_the_matching_processes_with_their_pattern() {
+        let mut web = ProcessInfo::stub(20, "we\"b\n");
+        web.memory = 2048;
+        let other = ProcessInfo::stub(10, "other");
+        let patterns = [Regex::new("^we").unwrap(), Regex::new("^gone$").unwrap()];
+
+        let out = render(&[web, other], &patterns);
+        assert!(out.contains(
+            "process_killer_memory_bytes{pid=\"20\",name=\"we\\\"b\\n\",user=\"root\",pattern=\"^we\"} 2048\n"
+        ));
+        assert!(!out.contains("pid=\"10\""));
+        assert!(out.contains("process_killer_matching_processes{pattern=\"^we\"} 1\n"));
+        assert!(out.contains("process_killer_matching_processes{pattern=\"^gone$\"} 0\n"));
+    }
+
+    #[test]
+    fn exports_every_process_without_patterns() {
+        let processes = [ProcessInfo::stub(2, "b"), ProcessInfo::stub(1, "a")];
+        let out = render(&processes, &[]);
+        let cpu: Vec<&str> = out
+            .lines()
+            .filter(|line| line.starts_with("process_killer_cpu_usage_percent{"))
+            .collect();
+        assert_eq!(
+            cpu,
+            [
+                "process_killer_cpu_usage_percent{pid=\"1\",name=\"a\",user=\"root\"} 0",
+                "process_killer_cpu_usage_percent{pid=\"2\",name=\"b\",user=\"root\"} 0",
+            ]
+        );
+        assert!(!out.contains("process_killer_matching_processes"));
+        // every line is a comment or a sample, a newline in a name would start a line of its own
+        assert!(out
+            .lines()
+            .all(|line| line.starts_with('#') || line.starts_with("process_killer_")));
+    }
+}
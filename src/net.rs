@@ -0,0 +1,185 @@
+// tcp and udp sockets from /proc/net, owned by a process through its socket fds
+use std::{
+    collections::HashSet,
+    fs, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+use sysinfo::{Pid, PidExt};
+
+use crate::files::open_files;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Protocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+}
+
+impl Protocol {
+    const ALL: [Protocol; 4] = [Protocol::Tcp, Protocol::Tcp6, Protocol::Udp, Protocol::Udp6];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Tcp6 => "tcp6",
+            Protocol::Udp => "udp",
+            Protocol::Udp6 => "udp6",
+        }
+    }
+
+    fn is_tcp(&self) -> bool {
+        matches!(self, Protocol::Tcp | Protocol::Tcp6)
+    }
+}
+
+pub struct Connection {
+    pub protocol: Protocol,
+    pub local: SocketAddr,
+    // 0.0.0.0:0 while listening or not connected
+    pub remote: SocketAddr,
+    // the tcp state, empty for an unconnected udp socket
+    pub state: &'static str,
+    pub inode: u64,
+}
+
+impl Connection {
+    // a listening tcp socket, or a bound udp one waiting for datagrams
+    pub fn is_listening(&self) -> bool {
+        if self.protocol.is_tcp() {
+            self.state == "LISTEN"
+        } else {
+            self.remote.port() == 0
+        }
+    }
+}
+
+// the names netstat and ss use, indexed by the kernel's tcp state number
+const TCP_STATES: [&str; 12] = [
+    "",
+    "ESTABLISHED",
+    "SYN_SENT",
+    "SYN_RECV",
+    "FIN_WAIT1",
+    "FIN_WAIT2",
+    "TIME_WAIT",
+    "CLOSE",
+    "CLOSE_WAIT",
+    "LAST_ACK",
+    "LISTEN",
+    "CLOSING",
+];
+
+// `0100007F:1F90`, each 32 bit word of the address is printed in host byte order
+fn parse_address(field: &str) -> Option<SocketAddr> {
+    let (ip, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let mut bytes = vec![];
+    for i in (0..ip.len()).step_by(8) {
+        let word = u32::from_str_radix(ip.get(i..i + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+
+    let ip = match bytes.len() {
+        4 => IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])),
+        16 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?)),
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+// one line of /proc/net/tcp and friends, after the header
+fn parse_line(protocol: Protocol, line: &str) -> Option<Connection> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let state = usize::from_str_radix(fields.get(3)?, 16).ok()?;
+    let state = match (protocol.is_tcp(), state) {
+        (true, state) => TCP_STATES.get(state).copied().unwrap_or(""),
+        (false, 1) => "ESTABLISHED",
+        (false, _) => "",
+    };
+
+    Some(Connection {
+        protocol,
+        local: parse_address(fields.get(1)?)?,
+        remote: parse_address(fields.get(2)?)?,
+        state,
+        inode: fields.get(9)?.parse().ok()?,
+    })
+}
+
+// every tcp and udp socket on the system, ipv6 ones are skipped when it's disabled
+pub fn connections() -> io::Result<Vec<Connection>> {
+    if cfg!(not(target_os = "linux")) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "network connections are only available on linux",
+        ));
+    }
+
+    let mut connections = vec![];
+    for protocol in Protocol::ALL {
+        let table = match fs::read_to_string(format!("/proc/net/{}", protocol.label())) {
+            Ok(table) => table,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        connections.extend(
+            table
+                .lines()
+                .skip(1)
+                .filter_map(|line| parse_line(protocol, line)),
+        );
+    }
+
+    Ok(connections)
+}
+
+// the sockets `pid` has open, matched by inode
+pub fn process_connections(pid: Pid) -> io::Result<Vec<Connection>> {
+    let inodes: Vec<u64> = open_files(pid)?
+        .iter()
+        .filter_map(|file| file.socket_inode())
+        .collect();
+
+    let mut connections: Vec<Connection> = connections()?
+        .into_iter()
+        .filter(|connection| inodes.contains(&connection.inode))
+        .collect();
+    connections.sort_by_key(|connection| (connection.protocol.label(), connection.local.port()));
+    Ok(connections)
+}
+
+// the processes listening on `port`, only our own user's unless running as root
+pub fn listening_pids(port: u16) -> io::Result<Vec<Pid>> {
+    let inodes: HashSet<u64> = connections()?
+        .into_iter()
+        .filter(|connection| connection.local.port() == port && connection.is_listening())
+        .map(|connection| connection.inode)
+        .collect();
+    if inodes.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut pids = vec![];
+    for entry in fs::read_dir("/proc")? {
+        let Some(pid) = entry?.file_name().to_str().and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+        let pid = Pid::from_u32(pid);
+        // the fds of other users' processes can't be read
+        let Ok(files) = open_files(pid) else {
+            continue;
+        };
+        if files
+            .iter()
+            .filter_map(|file| file.socket_inode())
+            .any(|inode| inodes.contains(&inode))
+        {
+            pids.push(pid);
+        }
+    }
+
+    pids.sort();
+    Ok(pids)
+}
@@ -0,0 +1,41 @@
+// desktop notifications for the alerts, through the desktop's tool like the clipboard
+use std::{
+    io,
+    process::{Command, Stdio},
+};
+
+use crate::process::in_path;
+
+pub fn notify(summary: &str, body: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        // the debug format quotes and escapes like an applescript string
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {:?} with title {:?}",
+            body, summary
+        ));
+        command
+    } else if in_path("notify-send") {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name", "process_killer", summary, body]);
+        command
+    } else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "notify-send is not installed",
+        ));
+    };
+
+    let status = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "notification failed with {}",
+            status
+        )));
+    }
+    Ok(())
+}
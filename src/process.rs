@@ -0,0 +1,1548 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    env, fmt, fs, io,
+    path::Path,
+    process::ExitStatus,
+    time::{Duration, Instant},
+};
+
+#[cfg(not(windows))]
+use std::process::Command;
+
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use regex::{Regex, RegexBuilder};
+use sysinfo::{
+    get_current_pid, CpuExt, CpuRefreshKind, Pid, PidExt, Process, ProcessExt, ProcessRefreshKind,
+    ProcessStatus, RefreshKind, Signal, System, SystemExt, Uid, UserExt,
+};
+
+use crate::{
+    cgroup::{process_cgroup, Cgroup},
+    filter_expr::{self, Expr},
+    gpu::{self, DrmClients, GpuSample},
+    net::listening_pids,
+};
+
+#[derive(Clone, Copy)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+    None,
+}
+
+impl SortOrder {
+    // as written in the config and on the command line, `None` is only the initial name order
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "asc" => Some(SortOrder::Asc),
+            "desc" => Some(SortOrder::Desc),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SortColumn {
+    Pid,
+    Ppid,
+    Name,
+    Cpu,
+    Memory,
+    DiskRead,
+    DiskWrite,
+    GpuMemory,
+    GpuUsage,
+    Threads,
+    User,
+    Cgroup,
+    Nice,
+    Age,
+}
+
+impl SortColumn {
+    const ALL: [SortColumn; 14] = [
+        SortColumn::Pid,
+        SortColumn::Ppid,
+        SortColumn::Name,
+        SortColumn::Cpu,
+        SortColumn::Memory,
+        SortColumn::DiskRead,
+        SortColumn::DiskWrite,
+        SortColumn::GpuMemory,
+        SortColumn::GpuUsage,
+        SortColumn::Threads,
+        SortColumn::User,
+        SortColumn::Cgroup,
+        SortColumn::Nice,
+        SortColumn::Age,
+    ];
+
+    // the same names as the table columns in the config
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortColumn::Pid => "pid",
+            SortColumn::Ppid => "ppid",
+            SortColumn::Name => "name",
+            SortColumn::Cpu => "cpu",
+            SortColumn::Memory => "memory",
+            SortColumn::DiskRead => "disk_read",
+            SortColumn::DiskWrite => "disk_write",
+            SortColumn::GpuMemory => "gpu_memory",
+            SortColumn::GpuUsage => "gpu_usage",
+            SortColumn::Threads => "threads",
+            SortColumn::User => "user",
+            SortColumn::Cgroup => "cgroup",
+            SortColumn::Nice => "nice",
+            SortColumn::Age => "age",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|column| column.name() == name)
+    }
+
+    pub fn names() -> Vec<&'static str> {
+        Self::ALL.iter().map(|column| column.name()).collect()
+    }
+
+    pub(crate) fn title(&self) -> &'static str {
+        match self {
+            SortColumn::Pid => "PID",
+            SortColumn::Ppid => "PPID",
+            SortColumn::Name => "Name",
+            SortColumn::Cpu => "CPU%",
+            SortColumn::Memory => "Memory",
+            SortColumn::DiskRead => "Read/s",
+            SortColumn::DiskWrite => "Write/s",
+            SortColumn::GpuMemory => "GPU mem",
+            SortColumn::GpuUsage => "GPU%",
+            SortColumn::Threads => "Threads",
+            SortColumn::User => "User",
+            SortColumn::Cgroup => "Cgroup",
+            SortColumn::Nice => "Nice",
+            SortColumn::Age => "Age",
+        }
+    }
+
+    pub(crate) fn compare(&self, a: &ProcessInfo, b: &ProcessInfo) -> Ordering {
+        match self {
+            SortColumn::Pid => a.pid.cmp(&b.pid),
+            SortColumn::Ppid => a.parent.cmp(&b.parent),
+            SortColumn::Name => a.name.cmp(&b.name),
+            SortColumn::Cpu => a.cpu_usage.total_cmp(&b.cpu_usage),
+            SortColumn::Memory => a.memory.cmp(&b.memory),
+            SortColumn::DiskRead => a.disk_read.cmp(&b.disk_read),
+            SortColumn::DiskWrite => a.disk_write.cmp(&b.disk_write),
+            SortColumn::GpuMemory => a.gpu_memory.cmp(&b.gpu_memory),
+            SortColumn::Threads => a.threads.cmp(&b.threads),
+            SortColumn::GpuUsage => a
+                .gpu_usage
+                .unwrap_or(-1.0)
+                .total_cmp(&b.gpu_usage.unwrap_or(-1.0)),
+            SortColumn::User => a.user.cmp(&b.user),
+            SortColumn::Cgroup => a.cgroup.cmp(&b.cgroup),
+            SortColumn::Nice => a.nice.cmp(&b.nice),
+            // the later a process started the younger it is
+            SortColumn::Age => b.start_time.cmp(&a.start_time),
+        }
+    }
+
+    pub(crate) fn is_gpu(&self) -> bool {
+        matches!(self, SortColumn::GpuMemory | SortColumn::GpuUsage)
+    }
+
+    // the next column when cycling, `step` is 1 or -1, the gpu ones are skipped while hidden
+    pub(crate) fn cycle(&self, step: isize, with_gpu: bool) -> SortColumn {
+        let len = Self::ALL.len() as isize;
+        let mut i = Self::ALL.iter().position(|c| c == self).unwrap_or(0) as isize;
+        loop {
+            i = (i + step).rem_euclid(len);
+            let column = Self::ALL[i as usize];
+            if with_gpu || !column.is_gpu() {
+                return column;
+            }
+        }
+    }
+}
+
+// signals offered by the signal picker, roughly from gentlest to most aggressive
+pub const SIGNALS: [(Signal, &str); 8] = [
+    (Signal::Term, "SIGTERM"),
+    (Signal::Interrupt, "SIGINT"),
+    (Signal::Hangup, "SIGHUP"),
+    (Signal::Quit, "SIGQUIT"),
+    (Signal::Abort, "SIGABRT"),
+    (Signal::Stop, "SIGSTOP"),
+    (Signal::Continue, "SIGCONT"),
+    (Signal::Kill, "SIGKILL"),
+];
+
+#[derive(Clone)]
+pub struct ProcessInfo {
+    pub pid: Pid,
+    pub name: String,
+    pub cmd: String,
+    pub user_id: Option<Uid>,
+    // the owner's name, or the raw uid when it can't be resolved
+    pub user: String,
+    pub cpu_usage: f32,
+    pub memory: u64,
+    pub virtual_memory: u64,
+    // only read while the memory column shows it
+    pub shared_memory: Option<u64>,
+    // bytes per second over the last refresh, 0 until there were two
+    pub disk_read: u64,
+    pub disk_write: u64,
+    // video memory in bytes and the share of the gpu engines, none when unknown
+    pub gpu_memory: Option<u64>,
+    pub gpu_usage: Option<f32>,
+    // only known on linux
+    pub threads: Option<usize>,
+    pub cgroup: Option<Cgroup>,
+    pub start_time: u64,
+    pub run_time: u64,
+    pub status: ProcessStatus,
+    pub parent: Option<Pid>,
+    pub nice: Option<i32>,
+    // how well the row matches a fuzzy search
+    pub score: i64,
+    // listed above everything else
+    pub pinned: bool,
+    // appeared over the last few seconds
+    pub spawned: bool,
+    // only used by the tree view
+    pub depth: usize,
+    pub has_children: bool,
+    // every process of a row standing for all the ones with its name, empty for a single process
+    pub members: Vec<Pid>,
+}
+
+impl ProcessInfo {
+    pub fn new(pid: Pid, process: &Process, system: &System) -> Self {
+        let user = process
+            .user_id()
+            .map(|uid| match system.get_user_by_id(uid) {
+                Some(user) => user.name().to_string(),
+                None => uid.to_string(),
+            })
+            .unwrap_or_default();
+
+        Self {
+            pid,
+            name: process.name().to_string(),
+            cmd: process.cmd().join(" "),
+            user_id: process.user_id().cloned(),
+            user,
+            cpu_usage: process.cpu_usage(),
+            memory: process.memory(),
+            virtual_memory: process.virtual_memory(),
+            shared_memory: None,
+            disk_read: 0,
+            disk_write: 0,
+            gpu_memory: None,
+            gpu_usage: None,
+            threads: thread_count(process),
+            cgroup: process_cgroup(pid).ok(),
+            start_time: process.start_time(),
+            run_time: process.run_time(),
+            status: process.status(),
+            parent: process.parent(),
+            nice: nice_value(pid),
+            score: 0,
+            pinned: false,
+            spawned: false,
+            depth: 0,
+            has_children: false,
+            members: vec![],
+        }
+    }
+
+    // one row for all of `processes`, which share a name, with their usage added up
+    pub(crate) fn group(processes: &[ProcessInfo]) -> Self {
+        let mut group = processes[0].clone();
+        let rest = &processes[1..];
+        let add = |a: Option<u64>, b: Option<u64>| match (a, b) {
+            (None, None) => None,
+            (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+        };
+        for process in rest {
+            group.cpu_usage += process.cpu_usage;
+            group.memory += process.memory;
+            group.virtual_memory += process.virtual_memory;
+            group.shared_memory = add(group.shared_memory, process.shared_memory);
+            group.disk_read += process.disk_read;
+            group.disk_write += process.disk_write;
+            group.gpu_memory = add(group.gpu_memory, process.gpu_memory);
+            group.gpu_usage = match (group.gpu_usage, process.gpu_usage) {
+                (None, None) => None,
+                (a, b) => Some(a.unwrap_or(0.0) + b.unwrap_or(0.0)),
+            };
+            group.threads = add(
+                group.threads.map(|n| n as u64),
+                process.threads.map(|n| n as u64),
+            )
+            .map(|n| n as usize);
+            // as old as the oldest of them
+            group.start_time = group.start_time.min(process.start_time);
+            group.run_time = group.run_time.max(process.run_time);
+            group.score = group.score.max(process.score);
+            group.spawned |= process.spawned;
+        }
+        group.has_children = true;
+        group.members = processes.iter().map(|p| p.pid).collect();
+        group
+    }
+}
+
+#[cfg(test)]
+impl ProcessInfo {
+    // a process of root doing nothing, the tests set what they look at
+    pub(crate) fn stub(pid: u32, name: &str) -> Self {
+        Self {
+            pid: Pid::from_u32(pid),
+            name: name.to_string(),
+            cmd: name.to_string(),
+            user_id: None,
+            user: "root".to_string(),
+            cpu_usage: 0.0,
+            memory: 0,
+            virtual_memory: 0,
+            shared_memory: None,
+            disk_read: 0,
+            disk_write: 0,
+            gpu_memory: None,
+            gpu_usage: None,
+            threads: None,
+            cgroup: None,
+            start_time: 0,
+            run_time: 0,
+            status: ProcessStatus::Run,
+            parent: None,
+            nice: None,
+            score: 0,
+            pinned: false,
+            spawned: false,
+            depth: 0,
+            has_children: false,
+            members: vec![],
+        }
+    }
+}
+
+// what the memory column shows
+#[derive(Clone, Copy, PartialEq)]
+pub enum MemoryKind {
+    // resident, what is actually in ram
+    Resident,
+    Virtual,
+    // the part of the resident memory that may be shared with other processes, linux only
+    Shared,
+}
+
+impl MemoryKind {
+    pub(crate) fn title(&self) -> &'static str {
+        match self {
+            MemoryKind::Resident => "Memory",
+            MemoryKind::Virtual => "Virtual",
+            MemoryKind::Shared => "Shared",
+        }
+    }
+
+    pub(crate) fn next(&self) -> MemoryKind {
+        match self {
+            MemoryKind::Resident => MemoryKind::Virtual,
+            MemoryKind::Virtual => MemoryKind::Shared,
+            MemoryKind::Shared => MemoryKind::Resident,
+        }
+    }
+
+    pub(crate) fn of(&self, process: &ProcessInfo) -> Option<u64> {
+        match self {
+            MemoryKind::Resident => Some(process.memory),
+            MemoryKind::Virtual => Some(process.virtual_memory),
+            MemoryKind::Shared => process.shared_memory,
+        }
+    }
+}
+
+// the shared pages from /proc/<pid>/statm, in bytes
+#[cfg(target_os = "linux")]
+pub fn shared_memory(pid: Pid) -> Option<u64> {
+    let statm = std::fs::read_to_string(format!("/proc/{}/statm", pid.as_u32())).ok()?;
+    let pages: u64 = statm.split_whitespace().nth(2)?.parse().ok()?;
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    Some(pages * page_size as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn shared_memory(_pid: Pid) -> Option<u64> {
+    None
+}
+
+// the whole machine, shown above the table
+pub struct SystemSummary {
+    // averaged over all cores
+    pub cpu_usage: f32,
+    // the usage of every core, in order
+    pub cores: Vec<f32>,
+    pub memory_used: u64,
+    pub memory_total: u64,
+    pub swap_used: u64,
+    pub swap_total: u64,
+    // the 1, 5 and 15 minute load averages, windows has none
+    pub load: Option<[f64; 3]>,
+    pub processes: usize,
+}
+
+// a thread of a process, as listed by the threads pane
+pub struct ThreadInfo {
+    pub tid: Pid,
+    pub name: String,
+    pub cpu_usage: f32,
+    pub status: ProcessStatus,
+}
+
+// a descendant of a process, as listed by the children pane
+pub struct ChildInfo {
+    pub pid: Pid,
+    pub name: String,
+    // 1 for a direct child, 2 for a grandchild and so on
+    pub depth: usize,
+    pub cpu_usage: f32,
+    pub memory: u64,
+}
+
+#[cfg(target_os = "linux")]
+fn thread_count(process: &Process) -> Option<usize> {
+    // sysinfo leaves the main thread out of the tasks
+    Some(process.tasks.len() + 1)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn thread_count(_process: &Process) -> Option<usize> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn process_threads(process: &Process) -> Vec<ThreadInfo> {
+    // the main thread stands in for the process, so its cpu usage is the whole process's
+    let mut threads: Vec<ThreadInfo> = std::iter::once((&process.pid(), process))
+        .chain(&process.tasks)
+        .map(|(tid, task)| ThreadInfo {
+            tid: *tid,
+            name: task.name().to_string(),
+            cpu_usage: task.cpu_usage(),
+            status: task.status(),
+        })
+        .collect();
+    threads.sort_by_key(|thread| thread.tid);
+    threads
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_threads(_process: &Process) -> Vec<ThreadInfo> {
+    vec![]
+}
+
+// the nice value of a process, none when it can't be read
+#[cfg(unix)]
+pub fn nice_value(pid: Pid) -> Option<i32> {
+    // -1 is a valid nice value, only errno tells it apart from a failure
+    clear_errno();
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid.as_u32() as libc::id_t) };
+    let failed = nice == -1 && io::Error::last_os_error().raw_os_error().unwrap_or(0) != 0;
+    (!failed).then_some(nice)
+}
+
+#[cfg(not(unix))]
+pub fn nice_value(_pid: Pid) -> Option<i32> {
+    None
+}
+
+#[cfg(unix)]
+pub fn set_nice_value(pid: Pid, nice: i32) -> io::Result<()> {
+    match unsafe { libc::setpriority(libc::PRIO_PROCESS, pid.as_u32() as libc::id_t, nice) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn set_nice_value(_pid: Pid, _nice: i32) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "nice values are only supported on unix",
+    ))
+}
+
+// the cpus a process may run on
+#[cfg(target_os = "linux")]
+pub fn cpu_affinity(pid: Pid) -> io::Result<Vec<usize>> {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        let size = std::mem::size_of::<libc::cpu_set_t>();
+        if libc::sched_getaffinity(pid.as_u32() as libc::pid_t, size, &mut set) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok((0..libc::CPU_SETSIZE as usize)
+            .filter(|cpu| libc::CPU_ISSET(*cpu, &set))
+            .collect())
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn set_cpu_affinity(pid: Pid, cpus: &[usize]) -> io::Result<()> {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        for cpu in cpus {
+            libc::CPU_SET(*cpu, &mut set);
+        }
+        let size = std::mem::size_of::<libc::cpu_set_t>();
+        match libc::sched_setaffinity(pid.as_u32() as libc::pid_t, size, &set) {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn cpu_affinity(_pid: Pid) -> io::Result<Vec<usize>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "CPU affinity is only supported on linux",
+    ))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_cpu_affinity(_pid: Pid, _cpus: &[usize]) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "CPU affinity is only supported on linux",
+    ))
+}
+
+// formats cpus like taskset does, e.g. "0-3,6"
+pub fn format_cpu_list(cpus: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = vec![];
+    for cpu in cpus {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == *cpu => *end = *cpu,
+            _ => ranges.push((*cpu, *cpu)),
+        }
+    }
+
+    ranges
+        .iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// as many cpus as a cpu_set_t holds
+const MAX_CPUS: usize = 1024;
+
+// `0-3,8` into the cpus, sorted and without duplicates
+pub fn parse_cpu_list(input: &str) -> Result<Vec<usize>, String> {
+    let parse = |cpu: &str| {
+        let cpu = cpu.trim();
+        match cpu.parse::<usize>() {
+            Ok(number) if number < MAX_CPUS => Ok(number),
+            Ok(_) => Err(format!("CPU '{}' is out of range", cpu)),
+            Err(_) => Err(format!("invalid CPU '{}'", cpu)),
+        }
+    };
+
+    let mut cpus = vec![];
+    for part in input.split(',').filter(|part| !part.trim().is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    return Err(format!("backwards range '{}'", part.trim()));
+                }
+                cpus.extend(start..=end);
+            }
+            None => cpus.push(parse(part)?),
+        }
+    }
+    if cpus.is_empty() {
+        return Err("no CPU given".to_string());
+    }
+
+    cpus.sort_unstable();
+    cpus.dedup();
+    Ok(cpus)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn clear_errno() {
+    unsafe { *libc::__errno_location() = 0 }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+fn clear_errno() {
+    unsafe { *libc::__error() = 0 }
+}
+
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd"
+    ))
+))]
+fn clear_errno() {}
+
+// only show processes in one state, cycled through from the table
+#[derive(Clone, Copy, PartialEq)]
+pub enum StateFilter {
+    All,
+    Running,
+    Sleeping,
+    Stopped,
+    Zombie,
+}
+
+impl StateFilter {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            StateFilter::All => "all",
+            StateFilter::Running => "running",
+            StateFilter::Sleeping => "sleeping",
+            StateFilter::Stopped => "stopped",
+            StateFilter::Zombie => "zombie",
+        }
+    }
+
+    pub(crate) fn next(&self) -> StateFilter {
+        match self {
+            StateFilter::All => StateFilter::Running,
+            StateFilter::Running => StateFilter::Sleeping,
+            StateFilter::Sleeping => StateFilter::Stopped,
+            StateFilter::Stopped => StateFilter::Zombie,
+            StateFilter::Zombie => StateFilter::All,
+        }
+    }
+
+    pub(crate) fn matches(&self, status: ProcessStatus) -> bool {
+        match self {
+            StateFilter::All => true,
+            StateFilter::Running => status == ProcessStatus::Run,
+            StateFilter::Sleeping => matches!(
+                status,
+                ProcessStatus::Sleep
+                    | ProcessStatus::Idle
+                    | ProcessStatus::UninterruptibleDiskSleep
+            ),
+            StateFilter::Stopped => matches!(status, ProcessStatus::Stop | ProcessStatus::Tracing),
+            StateFilter::Zombie => matches!(status, ProcessStatus::Zombie | ProcessStatus::Dead),
+        }
+    }
+}
+
+// a short, column friendly name for a process state
+pub fn status_label(status: ProcessStatus) -> String {
+    match status {
+        ProcessStatus::Run => "Running".to_string(),
+        ProcessStatus::UninterruptibleDiskSleep => "Disk sleep".to_string(),
+        ProcessStatus::Stop => "Suspended".to_string(),
+        status => status.to_string(),
+    }
+}
+
+// formats a byte count as KB/MB/GB
+pub fn format_bytes(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    const GB: f64 = MB * 1024.0;
+
+    let bytes = bytes as f64;
+    if bytes >= GB {
+        format!("{:.1} GB", bytes / GB)
+    } else if bytes >= MB {
+        format!("{:.1} MB", bytes / MB)
+    } else {
+        format!("{:.0} KB", bytes / KB)
+    }
+}
+
+// the signals beyond the ones in the picker, typed by name or number
+const OTHER_SIGNALS: [(Signal, &str); 22] = [
+    (Signal::User1, "SIGUSR1"),
+    (Signal::User2, "SIGUSR2"),
+    (Signal::Alarm, "SIGALRM"),
+    (Signal::Child, "SIGCHLD"),
+    (Signal::Pipe, "SIGPIPE"),
+    (Signal::Winch, "SIGWINCH"),
+    (Signal::TSTP, "SIGTSTP"),
+    (Signal::TTIN, "SIGTTIN"),
+    (Signal::TTOU, "SIGTTOU"),
+    (Signal::Urgent, "SIGURG"),
+    (Signal::XCPU, "SIGXCPU"),
+    (Signal::XFSZ, "SIGXFSZ"),
+    (Signal::VirtualAlarm, "SIGVTALRM"),
+    (Signal::Profiling, "SIGPROF"),
+    (Signal::IO, "SIGIO"),
+    (Signal::Power, "SIGPWR"),
+    (Signal::Sys, "SIGSYS"),
+    (Signal::Trap, "SIGTRAP"),
+    (Signal::Bus, "SIGBUS"),
+    (Signal::FloatingPointException, "SIGFPE"),
+    (Signal::Illegal, "SIGILL"),
+    (Signal::Segv, "SIGSEGV"),
+];
+
+fn all_signals() -> impl Iterator<Item = &'static (Signal, &'static str)> {
+    SIGNALS.iter().chain(OTHER_SIGNALS.iter())
+}
+
+// the number of the signal on this platform, none for the ones it doesn't have
+#[cfg(unix)]
+pub fn signal_number(signal: Signal) -> Option<i32> {
+    let number = match signal {
+        Signal::Hangup => libc::SIGHUP,
+        Signal::Interrupt => libc::SIGINT,
+        Signal::Quit => libc::SIGQUIT,
+        Signal::Illegal => libc::SIGILL,
+        Signal::Trap => libc::SIGTRAP,
+        Signal::Abort | Signal::IOT => libc::SIGABRT,
+        Signal::Bus => libc::SIGBUS,
+        Signal::FloatingPointException => libc::SIGFPE,
+        Signal::Kill => libc::SIGKILL,
+        Signal::User1 => libc::SIGUSR1,
+        Signal::Segv => libc::SIGSEGV,
+        Signal::User2 => libc::SIGUSR2,
+        Signal::Pipe => libc::SIGPIPE,
+        Signal::Alarm => libc::SIGALRM,
+        Signal::Term => libc::SIGTERM,
+        Signal::Child => libc::SIGCHLD,
+        Signal::Continue => libc::SIGCONT,
+        Signal::Stop => libc::SIGSTOP,
+        Signal::TSTP => libc::SIGTSTP,
+        Signal::TTIN => libc::SIGTTIN,
+        Signal::TTOU => libc::SIGTTOU,
+        Signal::Urgent => libc::SIGURG,
+        Signal::XCPU => libc::SIGXCPU,
+        Signal::XFSZ => libc::SIGXFSZ,
+        Signal::VirtualAlarm => libc::SIGVTALRM,
+        Signal::Profiling => libc::SIGPROF,
+        Signal::Winch => libc::SIGWINCH,
+        Signal::IO | Signal::Poll => libc::SIGIO,
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        Signal::Power => libc::SIGPWR,
+        Signal::Sys => libc::SIGSYS,
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        Signal::Power => return None,
+    };
+    Some(number)
+}
+
+#[cfg(not(unix))]
+pub fn signal_number(_signal: Signal) -> Option<i32> {
+    None
+}
+
+// a name with or without the SIG prefix in any case, e.g. `usr1` or `SIGHUP`, or a number
+pub fn parse_signal(name: &str) -> Option<Signal> {
+    let name = name.trim().to_uppercase();
+    if let Ok(number) = name.parse::<i32>() {
+        return all_signals()
+            .map(|(signal, _)| *signal)
+            .find(|signal| signal_number(*signal) == Some(number));
+    }
+    let name = name.strip_prefix("SIG").unwrap_or(&name);
+    all_signals()
+        .find(|(_, n)| &n[3..] == name)
+        .map(|(signal, _)| *signal)
+}
+
+pub fn signal_name(signal: Signal) -> String {
+    all_signals()
+        .find(|(s, _)| *s == signal)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| signal.to_string())
+}
+
+// formats a unix timestamp as "YYYY-MM-DD HH:MM:SS UTC"
+pub fn format_timestamp(secs: u64) -> String {
+    // days since epoch to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let days = (secs / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let time = secs % 86400;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+// formats a number of seconds as e.g. "3h 20m"
+pub fn format_duration(secs: u64) -> String {
+    let days = secs / 86400;
+    let hours = secs % 86400 / 3600;
+    let minutes = secs % 3600 / 60;
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+// whether text searches tell upper and lower case apart
+#[derive(Clone, Copy, Default)]
+pub(crate) enum SearchCase {
+    // only when the query has an uppercase letter, like vim's smartcase
+    #[default]
+    Smart,
+    Insensitive,
+    Sensitive,
+}
+
+impl SearchCase {
+    pub(crate) const NAMES: [&'static str; 3] = ["smart", "insensitive", "sensitive"];
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "smart" => Some(SearchCase::Smart),
+            "insensitive" => Some(SearchCase::Insensitive),
+            "sensitive" => Some(SearchCase::Sensitive),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_sensitive(&self, query: &str) -> bool {
+        match self {
+            SearchCase::Smart => has_uppercase(query),
+            SearchCase::Insensitive => false,
+            SearchCase::Sensitive => true,
+        }
+    }
+}
+
+// ignores the letter after a backslash, so the `\S` and `\D` of a regex don't count
+fn has_uppercase(query: &str) -> bool {
+    let mut escaped = false;
+    query.chars().any(|c| {
+        let upper = !escaped && c.is_uppercase();
+        escaped = !escaped && c == '\\';
+        upper
+    })
+}
+
+// a plain substring rather than a fuzzy match, which would hide far more than asked for
+pub(crate) struct Substring {
+    term: String,
+    case_sensitive: bool,
+}
+
+impl Substring {
+    pub(crate) fn new(term: &str, case_sensitive: bool) -> Self {
+        let term = if case_sensitive {
+            term.to_string()
+        } else {
+            term.to_lowercase()
+        };
+        Self {
+            term,
+            case_sensitive,
+        }
+    }
+
+    pub(crate) fn matches(&self, text: &str) -> bool {
+        if self.case_sensitive {
+            text.contains(&self.term)
+        } else {
+            text.to_lowercase().contains(&self.term)
+        }
+    }
+
+    // the whole of `text` rather than a part of it
+    pub(crate) fn equals(&self, text: &str) -> bool {
+        if self.case_sensitive {
+            text == self.term
+        } else {
+            text.to_lowercase() == self.term
+        }
+    }
+}
+
+// what the table is currently filtered by, parsed from the search input on Enter
+pub(crate) enum SearchFilter {
+    All,
+    // space separated terms, each fuzzy matched (skim style) against the name and command line
+    Fuzzy {
+        terms: Vec<String>,
+        // `!<term>`, hides the processes whose name or command line contains it
+        excluded: Vec<Substring>,
+        matcher: Box<SkimMatcherV2>,
+    },
+    // a query prefixed with `/`, matched against the name and the full command line,
+    // `!/` hides the matching processes instead
+    Regex {
+        regex: Regex,
+        inverted: bool,
+    },
+    // words, comparisons like `cpu>50` and `mem>1G`, `&`, `|`, `!` and parentheses
+    Expr(Expr),
+    // `:<port>`, the processes listening on it, looked up again on every refresh
+    Port {
+        port: u16,
+        pids: Vec<Pid>,
+    },
+}
+
+impl SearchFilter {
+    pub(crate) fn parse(input: &str, case: SearchCase) -> Result<Self, String> {
+        if input.trim().is_empty() {
+            return Ok(SearchFilter::All);
+        }
+
+        // anything but a port number after the `:` is searched for as text. the listeners are
+        // only looked up by `refresh`, parsing runs on every keystroke
+        if let Some(port) = input.trim().strip_prefix(':').and_then(|p| p.parse().ok()) {
+            return Ok(SearchFilter::Port { port, pids: vec![] });
+        }
+
+        let (inverted, regex) = match input.strip_prefix("!/") {
+            Some(pattern) => (true, Some(pattern)),
+            None => (false, input.strip_prefix('/')),
+        };
+        match regex {
+            Some(pattern) => RegexBuilder::new(pattern)
+                .case_insensitive(!case.is_sensitive(pattern))
+                .build()
+                .map(|regex| SearchFilter::Regex { regex, inverted })
+                .map_err(|err| err.to_string()),
+            None if filter_expr::is_expression(input) => {
+                filter_expr::parse(input, case).map(SearchFilter::Expr)
+            }
+            None => {
+                let case_sensitive = case.is_sensitive(input);
+                let matcher = SkimMatcherV2::default();
+                let matcher = if case_sensitive {
+                    matcher.respect_case()
+                } else {
+                    matcher.ignore_case()
+                };
+                // a lone `!` is searched for as is
+                let (excluded, terms): (Vec<&str>, Vec<&str>) = input
+                    .split_whitespace()
+                    .partition(|term| term.len() > 1 && term.starts_with('!'));
+                Ok(SearchFilter::Fuzzy {
+                    terms: terms.into_iter().map(String::from).collect(),
+                    excluded: excluded
+                        .into_iter()
+                        .map(|term| Substring::new(&term[1..], case_sensitive))
+                        .collect(),
+                    matcher: Box::new(matcher),
+                })
+            }
+        }
+    }
+
+    // the match score of a process, None when it is filtered out
+    pub(crate) fn score(&self, process: &ProcessInfo) -> Option<i64> {
+        match self {
+            SearchFilter::All => Some(0),
+            SearchFilter::Fuzzy {
+                terms,
+                excluded,
+                matcher,
+            } => {
+                let haystack = format!("{} {}", process.name, process.cmd);
+                if excluded
+                    .iter()
+                    .any(|exclusion| exclusion.matches(&haystack))
+                {
+                    return None;
+                }
+                terms
+                    .iter()
+                    .map(|term| matcher.fuzzy_match(&haystack, term))
+                    .sum()
+            }
+            SearchFilter::Regex { regex, inverted } => {
+                let matched = regex.is_match(&process.name) || regex.is_match(&process.cmd);
+                (matched != *inverted).then_some(0)
+            }
+            SearchFilter::Expr(expr) => expr.matches(process).then_some(0),
+            SearchFilter::Port { pids, .. } => pids.contains(&process.pid).then_some(0),
+        }
+    }
+
+    // looks up who listens on the port again, the other filters don't depend on the system
+    pub(crate) fn refresh(&mut self) {
+        if let SearchFilter::Port { port, pids } = self {
+            *pids = listening_pids(*port).unwrap_or_default();
+        }
+    }
+
+    pub(crate) fn is_port(&self) -> bool {
+        matches!(self, SearchFilter::Port { .. })
+    }
+
+    pub(crate) fn is_ranked(&self) -> bool {
+        matches!(self, SearchFilter::Fuzzy { .. })
+    }
+}
+
+// splits a leading `u:<user>` term off the search input
+pub(crate) fn split_user_filter(input: &str) -> (Option<String>, &str) {
+    match input.trim_start().strip_prefix("u:") {
+        Some(rest) => {
+            let (user, query) = rest.split_once(' ').unwrap_or((rest, ""));
+            (Some(user.to_string()), query)
+        }
+        None => (None, input),
+    }
+}
+
+fn cmdline_matches(regex: &Regex, process: &Process) -> bool {
+    // kernel threads have no command line, fall back to the name
+    let cmd = process.cmd().join(" ");
+    regex.is_match(if cmd.is_empty() { process.name() } else { &cmd })
+}
+
+// owns the sysinfo snapshot, finding and signalling processes goes through here
+pub struct ProcessManager {
+    system: System,
+    // left out of every search, our own command line tends to match
+    own_pid: Option<Pid>,
+    // sysinfo keeps a process around when refreshing it alone finds it gone,
+    // so it is hidden here until the next full refresh
+    exited: HashSet<Pid>,
+    // the disk usage sysinfo reports covers the time between the last two refreshes
+    refreshed_at: Instant,
+    sample_period: Duration,
+    // none until `refresh_gpu` finds a gpu
+    gpu: Option<HashMap<Pid, GpuSample>>,
+    gpu_usage: HashMap<Pid, f32>,
+    drm_clients: DrmClients,
+}
+
+impl Default for ProcessManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessManager {
+    pub fn new() -> Self {
+        // only what the table needs, disks, networks and sensors are slow to scan
+        let refresh = RefreshKind::new()
+            .with_processes(ProcessRefreshKind::everything())
+            .with_cpu(CpuRefreshKind::new().with_cpu_usage())
+            .with_memory()
+            .with_users_list();
+        Self {
+            system: System::new_with_specifics(refresh),
+            own_pid: get_current_pid().ok(),
+            exited: HashSet::new(),
+            refreshed_at: Instant::now(),
+            sample_period: Duration::ZERO,
+            gpu: None,
+            gpu_usage: HashMap::new(),
+            drm_clients: DrmClients::default(),
+        }
+    }
+
+    // updates the process list, started processes are added and exited ones dropped
+    pub fn refresh(&mut self) {
+        self.system.refresh_processes();
+        self.system.refresh_cpu();
+        self.system.refresh_memory();
+        self.exited.clear();
+        self.sample_period = self.refreshed_at.elapsed();
+        self.refreshed_at = Instant::now();
+    }
+
+    // samples the gpus, run right after `refresh` so the utilization covers the same period
+    pub fn refresh_gpu(&mut self) {
+        let Some(samples) = gpu::sample(&mut self.drm_clients) else {
+            return;
+        };
+
+        let period = self.sample_period.as_nanos() as f32;
+        let previous = self.gpu.take().unwrap_or_default();
+        self.gpu_usage = samples
+            .iter()
+            .filter_map(|(pid, sample)| {
+                // nvidia-smi measures it itself, the drm engine time needs two samples
+                if let Some(usage) = sample.usage {
+                    return Some((*pid, usage));
+                }
+                if period == 0.0 {
+                    return None;
+                }
+                let before = previous.get(pid)?.engine_ns?;
+                let busy = sample.engine_ns?.saturating_sub(before);
+                Some((*pid, busy as f32 / period * 100.0))
+            })
+            .collect();
+        self.gpu = Some(samples);
+    }
+
+    pub fn summary(&self) -> SystemSummary {
+        let load = self.system.load_average();
+        SystemSummary {
+            cpu_usage: self.system.global_cpu_info().cpu_usage(),
+            cores: self
+                .system
+                .cpus()
+                .iter()
+                .map(|cpu| cpu.cpu_usage())
+                .collect(),
+            memory_used: self.system.used_memory(),
+            memory_total: self.system.total_memory(),
+            swap_used: self.system.used_swap(),
+            swap_total: self.system.total_swap(),
+            load: cfg!(not(windows)).then_some([load.one, load.five, load.fifteen]),
+            processes: self
+                .system
+                .processes()
+                .len()
+                .saturating_sub(self.exited.len()),
+        }
+    }
+
+    pub fn has_gpu(&self) -> bool {
+        self.gpu.is_some()
+    }
+
+    // updates just these processes, much cheaper than a full refresh after signalling a few
+    pub fn refresh_pids(&mut self, pids: &[Pid]) {
+        for pid in pids {
+            if !self.system.refresh_process(*pid) {
+                self.exited.insert(*pid);
+            }
+        }
+    }
+
+    pub fn system(&self) -> &System {
+        &self.system
+    }
+
+    pub fn process(&self, pid: Pid) -> Option<&Process> {
+        if self.exited.contains(&pid) {
+            return None;
+        }
+        self.system.process(pid)
+    }
+
+    // the threads of a process by tid, empty where sysinfo doesn't list them
+    pub fn threads(&self, pid: Pid) -> Vec<ThreadInfo> {
+        self.process(pid).map(process_threads).unwrap_or_default()
+    }
+
+    // every child of a process, their children and so on, depth first with siblings by pid
+    pub fn descendants(&self, pid: Pid) -> Vec<ChildInfo> {
+        let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+        for (child, process) in self.system.processes() {
+            if self.exited.contains(child) {
+                continue;
+            }
+            if let Some(parent) = process.parent() {
+                children.entry(parent).or_default().push(*child);
+            }
+        }
+        for siblings in children.values_mut() {
+            siblings.sort();
+        }
+
+        let mut descendants = vec![];
+        let mut stack: Vec<(Pid, usize)> = vec![(pid, 0)];
+        while let Some((parent, depth)) = stack.pop() {
+            if parent != pid {
+                let process = &self.system.processes()[&parent];
+                descendants.push(ChildInfo {
+                    pid: parent,
+                    name: process.name().to_string(),
+                    depth,
+                    cpu_usage: process.cpu_usage(),
+                    memory: process.memory(),
+                });
+            }
+            // pushed in reverse so the lowest pid is visited first
+            if let Some(siblings) = children.get(&parent) {
+                stack.extend(siblings.iter().rev().map(|child| (*child, depth + 1)));
+            }
+        }
+        descendants
+    }
+
+    // the process running this code
+    pub fn own_process(&self) -> Option<&Process> {
+        self.own_pid.and_then(|pid| self.process(pid))
+    }
+
+    // a row for every process, in no particular order
+    pub fn processes(&self) -> Vec<ProcessInfo> {
+        self.system
+            .processes()
+            .iter()
+            .filter(|(pid, _)| !self.exited.contains(pid))
+            .map(|(pid, process)| {
+                let mut info = ProcessInfo::new(*pid, process, &self.system);
+                let usage = process.disk_usage();
+                info.disk_read = self.per_second(usage.read_bytes);
+                info.disk_write = self.per_second(usage.written_bytes);
+                if let Some(gpu) = &self.gpu {
+                    info.gpu_memory = Some(gpu.get(pid).map_or(0, |sample| sample.memory));
+                    info.gpu_usage = self.gpu_usage.get(pid).copied();
+                }
+                info
+            })
+            .collect()
+    }
+
+    // a byte count sampled over the last refresh as a rate
+    fn per_second(&self, bytes: u64) -> u64 {
+        if self.sample_period.is_zero() {
+            return 0;
+        }
+        (bytes as f64 / self.sample_period.as_secs_f64()) as u64
+    }
+
+    // every other process whose name is or (without `exact`) contains `name`, by pid
+    pub fn find_by_name(&self, name: &str, exact: bool) -> Vec<&Process> {
+        self.find(|p| {
+            if exact {
+                p.name() == name
+            } else {
+                p.name().contains(name)
+            }
+        })
+    }
+
+    // every other process whose command line matches `regex`, by pid
+    pub fn find_matching(&self, regex: &Regex) -> Vec<&Process> {
+        self.find(|p| cmdline_matches(regex, p))
+    }
+
+    fn find(&self, matches: impl Fn(&Process) -> bool) -> Vec<&Process> {
+        let mut found: Vec<&Process> = self
+            .system
+            .processes()
+            .values()
+            .filter(|p| Some(p.pid()) != self.own_pid && !self.exited.contains(&p.pid()))
+            .filter(|p| matches(p))
+            .collect();
+        found.sort_by_key(|p| p.pid());
+        found
+    }
+
+    pub fn signal(&self, pid: Pid, signal: Signal) -> Result<(), SignalError> {
+        match self.process(pid) {
+            Some(process) => send_signal(process, signal),
+            None => Err(SignalError::Gone),
+        }
+    }
+}
+
+// why a signal could not be sent
+#[derive(Debug)]
+pub enum SignalError {
+    // the signal does not exist on this platform
+    Unsupported,
+    // the process belongs to another user
+    PermissionDenied,
+    Gone,
+    Other(io::Error),
+}
+
+impl fmt::Display for SignalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SignalError::Unsupported => write!(f, "not supported on this platform"),
+            SignalError::PermissionDenied => write!(f, "permission denied"),
+            SignalError::Gone => write!(f, "already exited"),
+            SignalError::Other(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+pub fn send_signal(process: &Process, signal: Signal) -> Result<(), SignalError> {
+    // sysinfo kills through taskkill on windows, which loses the reason it failed
+    #[cfg(windows)]
+    if signal == Signal::Kill {
+        return terminate_process(process.pid());
+    }
+
+    match process.kill_with(signal) {
+        None => Err(SignalError::Unsupported),
+        Some(true) => Ok(()),
+        // kill_with is a bare kill(2) on unix, so errno still tells why it failed
+        Some(false) => Err(signal_error(io::Error::last_os_error())),
+    }
+}
+
+fn signal_error(err: io::Error) -> SignalError {
+    match err {
+        err if err.kind() == io::ErrorKind::PermissionDenied => SignalError::PermissionDenied,
+        err if err.raw_os_error() == Some(NO_SUCH_PROCESS) => SignalError::Gone,
+        err => SignalError::Other(err),
+    }
+}
+
+#[cfg(unix)]
+const NO_SUCH_PROCESS: i32 = libc::ESRCH;
+// what OpenProcess fails with once the pid is gone
+#[cfg(windows)]
+const NO_SUCH_PROCESS: i32 = winapi::shared::winerror::ERROR_INVALID_PARAMETER as i32;
+#[cfg(not(any(unix, windows)))]
+const NO_SUCH_PROCESS: i32 = -1;
+
+// TerminateProcess, the windows equivalent of SIGKILL
+#[cfg(windows)]
+fn terminate_process(pid: Pid) -> Result<(), SignalError> {
+    use winapi::um::{
+        handleapi::CloseHandle,
+        processthreadsapi::{OpenProcess, TerminateProcess},
+        winnt::PROCESS_TERMINATE,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, 0, pid.as_u32());
+        if handle.is_null() {
+            return Err(signal_error(io::Error::last_os_error()));
+        }
+        // read the error before CloseHandle can overwrite it
+        let result = match TerminateProcess(handle, 1) {
+            0 => Err(signal_error(io::Error::last_os_error())),
+            _ => Ok(()),
+        };
+        CloseHandle(handle);
+        result
+    }
+}
+
+// pkexec asks through the desktop's polkit agent, sudo on the terminal, windows has UAC
+pub fn elevation_tool() -> Option<&'static str> {
+    if cfg!(windows) {
+        return Some("UAC");
+    }
+    if cfg!(not(unix)) {
+        return None;
+    }
+
+    let graphical = env::var_os("DISPLAY").is_some() || env::var_os("WAYLAND_DISPLAY").is_some();
+    let tools: &[&'static str] = if graphical {
+        &["pkexec", "sudo"]
+    } else {
+        &["sudo"]
+    };
+    tools.iter().copied().find(|tool| in_path(tool))
+}
+
+pub(crate) fn in_path(program: &str) -> bool {
+    env::var_os("PATH")
+        .is_some_and(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+// writes a core file of `pid` to <dir>/core.<pid> through gdb's gcore, the process only stops for
+// as long as that takes
+pub fn gcore(pid: Pid, dir: &Path) -> io::Result<ExitStatus> {
+    if !in_path("gcore") {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "gcore is not installed, it comes with gdb",
+        ));
+    }
+    fs::create_dir_all(dir)?;
+    Command::new("gcore")
+        .arg("-o")
+        .arg(dir.join("core"))
+        .arg(pid.to_string())
+        .status()
+}
+
+// runs `kill -<signal> <pids>` as root through `tool`
+#[cfg(not(windows))]
+pub fn elevated_kill(tool: &str, signal: Signal, pids: &[Pid]) -> io::Result<ExitStatus> {
+    let name = signal_name(signal);
+    let name = name.strip_prefix("SIG").unwrap_or(&name);
+    Command::new(tool)
+        .arg("kill")
+        .arg(format!("-{}", name))
+        .args(pids.iter().map(|pid| pid.to_string()))
+        .status()
+}
+
+// runs `taskkill /F /PID <pid>...` elevated, windows shows the UAC prompt itself
+#[cfg(windows)]
+pub fn elevated_kill(_tool: &str, signal: Signal, pids: &[Pid]) -> io::Result<ExitStatus> {
+    use std::{
+        ffi::OsStr, iter, mem, os::windows::ffi::OsStrExt, os::windows::process::ExitStatusExt,
+    };
+    use winapi::um::{
+        handleapi::CloseHandle,
+        processthreadsapi::GetExitCodeProcess,
+        shellapi::{ShellExecuteExW, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW},
+        synchapi::WaitForSingleObject,
+        winbase::INFINITE,
+        winuser::SW_HIDE,
+    };
+
+    if signal != Signal::Kill {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("{} is not supported on windows", signal_name(signal)),
+        ));
+    }
+
+    let wide = |s: &str| -> Vec<u16> { OsStr::new(s).encode_wide().chain(iter::once(0)).collect() };
+    let verb = wide("runas");
+    let file = wide("taskkill.exe");
+    let args: Vec<String> = pids.iter().map(|pid| format!("/PID {}", pid)).collect();
+    let parameters = wide(&format!("/F {}", args.join(" ")));
+
+    unsafe {
+        let mut info: SHELLEXECUTEINFOW = mem::zeroed();
+        info.cbSize = mem::size_of::<SHELLEXECUTEINFOW>() as u32;
+        info.fMask = SEE_MASK_NOCLOSEPROCESS;
+        info.lpVerb = verb.as_ptr();
+        info.lpFile = file.as_ptr();
+        info.lpParameters = parameters.as_ptr();
+        info.nShow = SW_HIDE;
+        // fails with ERROR_CANCELLED when the prompt is declined
+        if ShellExecuteExW(&mut info) == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        WaitForSingleObject(info.hProcess, INFINITE);
+        let mut code = 0;
+        let ok = GetExitCodeProcess(info.hProcess, &mut code) != 0;
+        let err = io::Error::last_os_error();
+        CloseHandle(info.hProcess);
+        if ok {
+            Ok(ExitStatus::from_raw(code))
+        } else {
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_signal_names_in_any_case() {
+        assert_eq!(parse_signal("KILL"), Some(Signal::Kill));
+        assert_eq!(parse_signal("SIGKILL"), Some(Signal::Kill));
+        assert_eq!(parse_signal("sigterm"), Some(Signal::Term));
+        assert_eq!(parse_signal(" hup "), Some(Signal::Hangup));
+        assert_eq!(parse_signal("usr1"), Some(Signal::User1));
+        for (signal, name) in all_signals() {
+            assert_eq!(parse_signal(name), Some(*signal), "{}", name);
+        }
+        assert_eq!(parse_signal(""), None);
+        assert_eq!(parse_signal("SIG"), None);
+        assert_eq!(parse_signal("SIGFOO"), None);
+        assert_eq!(parse_signal("K"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parses_signal_numbers() {
+        assert_eq!(parse_signal("9"), Some(Signal::Kill));
+        assert_eq!(parse_signal("15"), Some(Signal::Term));
+        assert_eq!(
+            parse_signal(&libc::SIGUSR1.to_string()),
+            Some(Signal::User1)
+        );
+        assert_eq!(parse_signal("0"), None);
+        assert_eq!(parse_signal("-9"), None);
+        assert_eq!(parse_signal("999"), None);
+    }
+
+    #[test]
+    fn parses_cpu_lists() {
+        assert_eq!(parse_cpu_list("0"), Ok(vec![0]));
+        assert_eq!(parse_cpu_list("0-3"), Ok(vec![0, 1, 2, 3]));
+        assert_eq!(parse_cpu_list(" 4, 0-1 ,1,"), Ok(vec![0, 1, 4]));
+        assert_eq!(parse_cpu_list("2-2"), Ok(vec![2]));
+        assert_eq!(parse_cpu_list("1023"), Ok(vec![1023]));
+        assert_eq!(parse_cpu_list(""), Err("no CPU given".to_string()));
+        assert_eq!(parse_cpu_list(" , "), Err("no CPU given".to_string()));
+        assert_eq!(parse_cpu_list("a"), Err("invalid CPU 'a'".to_string()));
+        assert_eq!(parse_cpu_list("1-"), Err("invalid CPU ''".to_string()));
+        assert_eq!(parse_cpu_list("-1"), Err("invalid CPU ''".to_string()));
+        assert_eq!(
+            parse_cpu_list("1-2-3"),
+            Err("invalid CPU '2-3'".to_string())
+        );
+        assert_eq!(
+            parse_cpu_list("3-1"),
+            Err("backwards range '3-1'".to_string())
+        );
+        assert_eq!(
+            parse_cpu_list("1024"),
+            Err("CPU '1024' is out of range".to_string())
+        );
+        assert_eq!(
+            parse_cpu_list("0-99999999999"),
+            Err("CPU '99999999999' is out of range".to_string())
+        );
+    }
+
+    #[test]
+    fn smart_case_looks_for_unescaped_uppercase() {
+        assert!(!has_uppercase("firefox"));
+        assert!(has_uppercase("Firefox"));
+        assert!(has_uppercase("node Server"));
+        assert!(!has_uppercase("123 -_/"));
+        // `\S`, `\D` and `\W` of a regex
+        assert!(!has_uppercase(r"\S+\d"));
+        assert!(has_uppercase(r"\\S"));
+        assert!(has_uppercase(r"\sX"));
+        assert!(has_uppercase("Ä"));
+    }
+
+    #[test]
+    fn search_case_decides_the_sensitivity() {
+        assert!(!SearchCase::Smart.is_sensitive("chrome"));
+        assert!(SearchCase::Smart.is_sensitive("Chrome"));
+        assert!(!SearchCase::Insensitive.is_sensitive("Chrome"));
+        assert!(SearchCase::Sensitive.is_sensitive("chrome"));
+        for name in SearchCase::NAMES {
+            assert!(SearchCase::from_name(name).is_some(), "{}", name);
+        }
+        assert!(SearchCase::from_name("Smart").is_none());
+
+        let insensitive = Substring::new("Chrome", false);
+        assert!(insensitive.matches("/opt/google/chrome/chrome"));
+        assert!(insensitive.equals("CHROME"));
+        let sensitive = Substring::new("Chrome", true);
+        assert!(!sensitive.matches("/opt/google/chrome/chrome"));
+        assert!(sensitive.equals("Chrome"));
+        assert!(!sensitive.equals("Chrome2"));
+    }
+}
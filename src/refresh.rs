@@ -0,0 +1,81 @@
+use std::{
+    mem,
+    sync::mpsc::{self, Receiver, SendError, Sender},
+    thread,
+};
+
+use sysinfo::{System, SystemExt};
+
+use crate::process::ProcessManager;
+
+// runs the full scans on a worker thread, two managers are passed back and forth
+// so the UI always has one to read from while the other one is being refreshed
+pub struct Refresher {
+    requests: Sender<ProcessManager>,
+    results: Receiver<ProcessManager>,
+    // the manager the next refresh runs on, none while the worker has it
+    spare: Option<ProcessManager>,
+}
+
+impl Default for Refresher {
+    fn default() -> Self {
+        Self::spawn()
+    }
+}
+
+impl Refresher {
+    pub fn spawn() -> Self {
+        let (requests, worker_requests) = mpsc::channel::<ProcessManager>();
+        let (worker_results, results) = mpsc::channel();
+
+        thread::spawn(move || {
+            // the second manager is built here, sampled twice so its cpu usage is meaningful
+            let mut manager = ProcessManager::new();
+            thread::sleep(System::MINIMUM_CPU_UPDATE_INTERVAL);
+            manager.refresh();
+            manager.refresh_gpu();
+            if worker_results.send(manager).is_err() {
+                return;
+            }
+
+            for mut manager in worker_requests {
+                manager.refresh();
+                manager.refresh_gpu();
+                if worker_results.send(manager).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Self {
+            requests,
+            results,
+            spare: None,
+        }
+    }
+
+    // starts a refresh, unless one is running already
+    pub fn request(&mut self) {
+        if let Some(manager) = self.spare.take() {
+            // only if the worker died, keep the manager so we don't spin forever
+            if let Err(SendError(manager)) = self.requests.send(manager) {
+                self.spare = Some(manager);
+            }
+        }
+    }
+
+    pub fn is_refreshing(&self) -> bool {
+        self.spare.is_none()
+    }
+
+    // swaps in the refreshed manager once it is back, `current` is refreshed next time
+    pub fn poll(&mut self, current: &mut ProcessManager) -> bool {
+        match self.results.try_recv() {
+            Ok(manager) => {
+                self.spare = Some(mem::replace(current, manager));
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
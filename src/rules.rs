@@ -0,0 +1,250 @@
+// the [[rules]] of the config signal the processes that stay over a limit for a while, e.g. chrome
+// renderers over 2 GB for a minute, the [[alerts]] only tell about them
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use regex::Regex;
+use sysinfo::{Pid, Signal};
+
+use crate::process::{format_bytes, ProcessInfo};
+
+pub(crate) struct Limit {
+    // shown in the history and the alerts, the pattern unless the config names it
+    pub(crate) name: String,
+    // matched against the name, then the command line, every process when unset
+    pub(crate) pattern: Option<Regex>,
+    pub(crate) cpu_above: Option<f32>,
+    // in bytes
+    pub(crate) memory_above: Option<u64>,
+    // how long a process has to stay over before the limit goes off
+    pub(crate) duration: Duration,
+}
+
+impl Limit {
+    // over either one when both are set
+    fn is_over(&self, process: &ProcessInfo) -> bool {
+        let over = self.cpu_above.is_some_and(|cpu| process.cpu_usage > cpu)
+            || self
+                .memory_above
+                .is_some_and(|memory| process.memory > memory);
+        over && self
+            .pattern
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(&process.name) || pattern.is_match(&process.cmd))
+    }
+
+    // what the process went over, for the toast
+    pub(crate) fn describe(&self) -> String {
+        let mut limits = vec![];
+        if let Some(cpu) = self.cpu_above {
+            limits.push(format!("cpu > {}%", cpu));
+        }
+        if let Some(memory) = self.memory_above {
+            limits.push(format!("memory > {}", format_bytes(memory)));
+        }
+        format!("{} for {}s", limits.join(" or "), self.duration.as_secs())
+    }
+}
+
+pub(crate) struct Rule {
+    pub(crate) limit: Limit,
+    pub(crate) signal: Signal,
+}
+
+pub(crate) struct Alert {
+    pub(crate) limit: Limit,
+    // also sent as a desktop notification
+    pub(crate) desktop: bool,
+}
+
+// since when each process has been over each limit
+#[derive(Default)]
+pub(crate) struct LimitTracker {
+    over_since: HashMap<(usize, Pid), Instant>,
+    // went off already, not again until they get back under
+    fired: HashSet<(usize, Pid)>,
+}
+
+impl LimitTracker {
+    // the index of the limit and the pid of the processes over it for long enough. With `repeat`
+    // they start over afterwards, so a process that ignores a rule's signal gets it again once the
+    // duration has passed again, otherwise they go off once each time they cross the limit
+    pub(crate) fn due<'a>(
+        &mut self,
+        limits: impl Iterator<Item = &'a Limit>,
+        processes: &[ProcessInfo],
+        now: Instant,
+        repeat: bool,
+    ) -> Vec<(usize, Pid)> {
+        let mut over = HashMap::new();
+        let mut fired = HashSet::new();
+        let mut due = vec![];
+        for (i, limit) in limits.enumerate() {
+            for process in processes.iter().filter(|process| limit.is_over(process)) {
+                let key = (i, process.pid);
+                if self.fired.contains(&key) {
+                    fired.insert(key);
+                    continue;
+                }
+                let since = self.over_since.get(&key).copied().unwrap_or(now);
+                if now.duration_since(since) < limit.duration {
+                    over.insert(key, since);
+                    continue;
+                }
+                due.push(key);
+                if !repeat {
+                    fired.insert(key);
+                }
+            }
+        }
+        // dropping the ones back under the limit, or gone
+        self.over_since = over;
+        self.fired = fired;
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sysinfo::PidExt;
+
+    use super::*;
+
+    fn limit(pattern: Option<&str>, cpu_above: f32, secs: u64) -> Limit {
+        Limit {
+            name: "test".to_string(),
+            pattern: pattern.map(|pattern| Regex::new(pattern).unwrap()),
+            cpu_above: Some(cpu_above),
+            memory_above: None,
+            duration: Duration::from_secs(secs),
+        }
+    }
+
+    fn busy(pid: u32, name: &str, cpu: f32) -> ProcessInfo {
+        let mut process = ProcessInfo::stub(pid, name);
+        process.cpu_usage = cpu;
+        process
+    }
+
+    #[test]
+    fn goes_off_once_the_duration_passed() {
+        let limits = [limit(None, 50.0, 10)];
+        let mut tracker = LimitTracker::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let processes = [busy(1, "a", 90.0), busy(2, "b", 10.0)];
+        let pid = Pid::from_u32(1);
+
+        assert!(tracker
+            .due(limits.iter(), &processes, at(0), false)
+            .is_empty());
+        assert!(tracker
+            .due(limits.iter(), &processes, at(9), false)
+            .is_empty());
+        assert_eq!(
+            tracker.due(limits.iter(), &processes, at(10), false),
+            [(0, pid)]
+        );
+        // not again while it stays over
+        assert!(tracker
+            .due(limits.iter(), &processes, at(30), false)
+            .is_empty());
+
+        // back under, then over again starts over
+        let calm = [busy(1, "a", 10.0)];
+        assert!(tracker.due(limits.iter(), &calm, at(31), false).is_empty());
+        assert!(tracker
+            .due(limits.iter(), &processes, at(32), false)
+            .is_empty());
+        assert_eq!(
+            tracker.due(limits.iter(), &processes, at(42), false),
+            [(0, pid)]
+        );
+    }
+
+    #[test]
+    fn repeats_for_the_rules() {
+        let limits = [limit(None, 50.0, 10)];
+        let mut tracker = LimitTracker::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let processes = [busy(1, "a", 90.0)];
+        let pid = Pid::from_u32(1);
+
+        assert!(tracker
+            .due(limits.iter(), &processes, at(0), true)
+            .is_empty());
+        assert_eq!(
+            tracker.due(limits.iter(), &processes, at(10), true),
+            [(0, pid)]
+        );
+        assert!(tracker
+            .due(limits.iter(), &processes, at(11), true)
+            .is_empty());
+        assert_eq!(
+            tracker.due(limits.iter(), &processes, at(21), true),
+            [(0, pid)]
+        );
+    }
+
+    #[test]
+    fn a_process_that_went_away_starts_over() {
+        let limits = [limit(None, 50.0, 10)];
+        let mut tracker = LimitTracker::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let processes = [busy(1, "a", 90.0)];
+
+        assert!(tracker
+            .due(limits.iter(), &processes, at(0), false)
+            .is_empty());
+        assert!(tracker.due(limits.iter(), &[], at(5), false).is_empty());
+        // a new process with the same pid
+        assert!(tracker
+            .due(limits.iter(), &processes, at(10), false)
+            .is_empty());
+        assert_eq!(
+            tracker.due(limits.iter(), &processes, at(20), false).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn tracks_each_limit_on_its_own() {
+        let limits = [
+            limit(Some("^chrome"), 50.0, 0),
+            limit(Some("renderer"), 80.0, 5),
+        ];
+        let mut tracker = LimitTracker::default();
+        let start = Instant::now();
+        let mut renderer = busy(1, "chrome", 90.0);
+        renderer.cmd = "chrome --type=renderer".to_string();
+        let processes = [renderer, busy(2, "firefox", 99.0)];
+        let pid = Pid::from_u32(1);
+
+        // a zero duration goes off right away
+        assert_eq!(
+            tracker.due(limits.iter(), &processes, start, false),
+            [(0, pid)]
+        );
+        let later = start + Duration::from_secs(5);
+        assert_eq!(
+            tracker.due(limits.iter(), &processes, later, false),
+            [(1, pid)]
+        );
+    }
+
+    #[test]
+    fn memory_or_cpu() {
+        let mut limit = limit(None, 50.0, 0);
+        limit.memory_above = Some(1024);
+        let mut hog = busy(1, "hog", 0.0);
+        hog.memory = 2048;
+        assert!(limit.is_over(&hog));
+        assert!(limit.is_over(&busy(2, "spin", 60.0)));
+        assert!(!limit.is_over(&busy(3, "idle", 10.0)));
+        assert_eq!(limit.describe(), "cpu > 50% or memory > 1 KB for 0s");
+    }
+}
@@ -0,0 +1,107 @@
+// the queries searched for, recalled with Up and Down in the search input like a shell history
+use std::{env, fs, io, path::PathBuf};
+
+// older queries are dropped beyond this
+const MAX_ENTRIES: usize = 100;
+
+#[derive(Default)]
+pub struct SearchHistory {
+    // oldest first, without consecutive duplicates
+    entries: Vec<String>,
+    // the entry shown in the input, none while editing a new query
+    position: Option<usize>,
+    // what was typed before recalling, given back when moving past the newest entry
+    draft: String,
+    // where the history is kept across sessions, none to keep it for this session only
+    path: Option<PathBuf>,
+}
+
+impl SearchHistory {
+    // reads the saved queries, a missing or unreadable file starts an empty history
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let entries = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| content.lines().map(String::from).collect())
+            .unwrap_or_default();
+
+        Self {
+            entries,
+            path,
+            ..Self::default()
+        }
+    }
+
+    // remembers an applied query, saving the history when it is kept across sessions
+    pub fn push(&mut self, query: &str) -> io::Result<()> {
+        self.position = None;
+        let query = query.trim();
+        // a line break would split it into two entries when read back
+        if query.is_empty()
+            || query.contains('\n')
+            || self.entries.last().is_some_and(|last| last == query)
+        {
+            return Ok(());
+        }
+
+        self.entries.push(query.to_string());
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.entries.join("\n") + "\n")
+    }
+
+    // the query before the one shown, `input` is kept as the draft when leaving it
+    pub fn older(&mut self, input: &str) -> Option<&str> {
+        let i = match self.position {
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = input.to_string();
+                self.entries.len() - 1
+            }
+            Some(i) => i.saturating_sub(1),
+        };
+        self.position = Some(i);
+        Some(&self.entries[i])
+    }
+
+    // the query after the one shown, or the draft once past the newest
+    pub fn newer(&mut self) -> Option<&str> {
+        let i = self.position?;
+        if i + 1 < self.entries.len() {
+            self.position = Some(i + 1);
+            Some(&self.entries[i + 1])
+        } else {
+            self.position = None;
+            Some(&self.draft)
+        }
+    }
+
+    // typing leaves the recalled query, the next Up starts again from the newest
+    pub fn reset(&mut self) {
+        self.position = None;
+    }
+}
+
+// ~/.local/state/process_killer/search_history, or the platform equivalent
+pub(crate) fn default_history_path() -> Option<PathBuf> {
+    let state_dir = if cfg!(windows) {
+        env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))
+    };
+
+    state_dir.map(|dir| dir.join("process_killer").join("search_history"))
+}
@@ -0,0 +1,278 @@
+// windows services, many processes that refuse to die are hosted by one and just get restarted
+use std::io;
+
+use sysinfo::Pid;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ServiceState {
+    Stopped,
+    Starting,
+    Stopping,
+    Running,
+    Resuming,
+    Pausing,
+    Paused,
+}
+
+impl ServiceState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ServiceState::Stopped => "Stopped",
+            ServiceState::Starting => "Starting",
+            ServiceState::Stopping => "Stopping",
+            ServiceState::Running => "Running",
+            ServiceState::Resuming => "Resuming",
+            ServiceState::Pausing => "Pausing",
+            ServiceState::Paused => "Paused",
+        }
+    }
+}
+
+pub struct Service {
+    // the key name used by `sc`, display_name is what the services console shows
+    pub name: String,
+    pub display_name: String,
+    pub state: ServiceState,
+    // none while the service is not running
+    pub pid: Option<Pid>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ServiceControl {
+    Stop,
+    Start,
+    Restart,
+}
+
+impl ServiceControl {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ServiceControl::Stop => "stop",
+            ServiceControl::Start => "start",
+            ServiceControl::Restart => "restart",
+        }
+    }
+}
+
+// how long a restart waits for the service to stop before starting it again
+#[cfg(windows)]
+const STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+// closes the handle when dropped
+#[cfg(windows)]
+struct ScHandle(winapi::um::winsvc::SC_HANDLE);
+
+#[cfg(windows)]
+impl ScHandle {
+    fn manager(access: u32) -> io::Result<Self> {
+        let handle = unsafe {
+            winapi::um::winsvc::OpenSCManagerW(std::ptr::null(), std::ptr::null(), access)
+        };
+        if handle.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self(handle))
+    }
+
+    fn service(&self, name: &str, access: u32) -> io::Result<Self> {
+        let name = wide(name);
+        let handle = unsafe { winapi::um::winsvc::OpenServiceW(self.0, name.as_ptr(), access) };
+        if handle.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self(handle))
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ScHandle {
+    fn drop(&mut self) {
+        unsafe { winapi::um::winsvc::CloseServiceHandle(self.0) };
+    }
+}
+
+#[cfg(windows)]
+fn wide(s: &str) -> Vec<u16> {
+    use std::{ffi::OsStr, iter, os::windows::ffi::OsStrExt};
+    OsStr::new(s).encode_wide().chain(iter::once(0)).collect()
+}
+
+// reads a nul terminated utf-16 string
+#[cfg(windows)]
+unsafe fn from_wide(ptr: *const u16) -> String {
+    let mut len = 0;
+    while *ptr.add(len) != 0 {
+        len += 1;
+    }
+    String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len))
+}
+
+#[cfg(windows)]
+fn service_state(state: u32) -> ServiceState {
+    use winapi::um::winsvc::{
+        SERVICE_CONTINUE_PENDING, SERVICE_PAUSED, SERVICE_PAUSE_PENDING, SERVICE_RUNNING,
+        SERVICE_START_PENDING, SERVICE_STOP_PENDING,
+    };
+    match state {
+        SERVICE_START_PENDING => ServiceState::Starting,
+        SERVICE_STOP_PENDING => ServiceState::Stopping,
+        SERVICE_RUNNING => ServiceState::Running,
+        SERVICE_CONTINUE_PENDING => ServiceState::Resuming,
+        SERVICE_PAUSE_PENDING => ServiceState::Pausing,
+        SERVICE_PAUSED => ServiceState::Paused,
+        _ => ServiceState::Stopped,
+    }
+}
+
+// every win32 service, running or not, sorted by name
+#[cfg(windows)]
+pub fn list_services() -> io::Result<Vec<Service>> {
+    use sysinfo::PidExt;
+    use winapi::{
+        shared::winerror::ERROR_MORE_DATA,
+        um::{
+            winnt::SERVICE_WIN32,
+            winsvc::{
+                EnumServicesStatusExW, ENUM_SERVICE_STATUS_PROCESSW, SC_ENUM_PROCESS_INFO,
+                SC_MANAGER_ENUMERATE_SERVICE, SERVICE_STATE_ALL,
+            },
+        },
+    };
+
+    let manager = ScHandle::manager(SC_MANAGER_ENUMERATE_SERVICE)?;
+    let mut services = vec![];
+    // u64 so the entries written into it are aligned
+    let mut buffer: Vec<u64> = vec![];
+    let mut resume = 0;
+    loop {
+        let mut needed = 0;
+        let mut returned = 0;
+        let done = unsafe {
+            EnumServicesStatusExW(
+                manager.0,
+                SC_ENUM_PROCESS_INFO,
+                SERVICE_WIN32,
+                SERVICE_STATE_ALL,
+                buffer.as_mut_ptr() as *mut u8,
+                (buffer.len() * 8) as u32,
+                &mut needed,
+                &mut returned,
+                &mut resume,
+                std::ptr::null(),
+            )
+        } != 0;
+        let err = io::Error::last_os_error();
+        if !done && err.raw_os_error() != Some(ERROR_MORE_DATA as i32) {
+            return Err(err);
+        }
+
+        let entries = buffer.as_ptr() as *const ENUM_SERVICE_STATUS_PROCESSW;
+        for i in 0..returned as usize {
+            let entry = unsafe { &*entries.add(i) };
+            let status = entry.ServiceStatusProcess;
+            services.push(Service {
+                name: unsafe { from_wide(entry.lpServiceName) },
+                display_name: unsafe { from_wide(entry.lpDisplayName) },
+                state: service_state(status.dwCurrentState),
+                pid: (status.dwProcessId != 0).then(|| Pid::from_u32(status.dwProcessId)),
+            });
+        }
+
+        if done {
+            break;
+        }
+        // the rest is fetched from `resume` on, into a buffer big enough for it
+        let size = (needed as usize).div_ceil(8);
+        if size > buffer.len() {
+            buffer.resize(size, 0);
+        }
+    }
+
+    services.sort_by_key(|service| service.name.to_lowercase());
+    Ok(services)
+}
+
+#[cfg(not(windows))]
+pub fn list_services() -> io::Result<Vec<Service>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "services are only available on windows",
+    ))
+}
+
+// stopping and starting usually needs an elevated process_killer
+#[cfg(windows)]
+pub fn control_service(name: &str, control: ServiceControl) -> io::Result<()> {
+    use winapi::um::winsvc::{
+        SC_MANAGER_CONNECT, SERVICE_QUERY_STATUS, SERVICE_START, SERVICE_STOP,
+    };
+
+    let manager = ScHandle::manager(SC_MANAGER_CONNECT)?;
+    let service = manager.service(name, SERVICE_START | SERVICE_STOP | SERVICE_QUERY_STATUS)?;
+    match control {
+        ServiceControl::Stop => stop_service(&service),
+        ServiceControl::Start => start_service(&service),
+        ServiceControl::Restart => {
+            stop_service(&service)?;
+            wait_until_stopped(&service)?;
+            start_service(&service)
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub fn control_service(_name: &str, _control: ServiceControl) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "services are only available on windows",
+    ))
+}
+
+// a service that is not running counts as stopped already
+#[cfg(windows)]
+fn stop_service(service: &ScHandle) -> io::Result<()> {
+    use winapi::{
+        shared::winerror::ERROR_SERVICE_NOT_ACTIVE,
+        um::winsvc::{ControlService, SERVICE_CONTROL_STOP, SERVICE_STATUS},
+    };
+
+    let mut status: SERVICE_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { ControlService(service.0, SERVICE_CONTROL_STOP, &mut status) } != 0 {
+        return Ok(());
+    }
+    match io::Error::last_os_error() {
+        err if err.raw_os_error() == Some(ERROR_SERVICE_NOT_ACTIVE as i32) => Ok(()),
+        err => Err(err),
+    }
+}
+
+#[cfg(windows)]
+fn start_service(service: &ScHandle) -> io::Result<()> {
+    match unsafe { winapi::um::winsvc::StartServiceW(service.0, 0, std::ptr::null_mut()) } {
+        0 => Err(io::Error::last_os_error()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(windows)]
+fn wait_until_stopped(service: &ScHandle) -> io::Result<()> {
+    use std::{thread, time::Instant};
+    use winapi::um::winsvc::{QueryServiceStatus, SERVICE_STATUS, SERVICE_STOPPED};
+
+    let deadline = Instant::now() + STOP_TIMEOUT;
+    while Instant::now() < deadline {
+        let mut status: SERVICE_STATUS = unsafe { std::mem::zeroed() };
+        if unsafe { QueryServiceStatus(service.0, &mut status) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if status.dwCurrentState == SERVICE_STOPPED {
+            return Ok(());
+        }
+        thread::sleep(std::time::Duration::from_millis(250));
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::TimedOut,
+        "the service did not stop in time",
+    ))
+}
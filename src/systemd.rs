@@ -0,0 +1,70 @@
+// systemd units, a service's main process that is killed is often just restarted by systemd
+use std::{io, process::Command};
+
+use sysinfo::{Pid, PidExt};
+
+use crate::process::in_path;
+
+#[derive(Clone)]
+pub struct Unit {
+    // e.g. `nginx.service` or `session-2.scope`
+    pub name: String,
+    // run by the user's own systemd instance, controlled with `systemctl --user`
+    pub user: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum UnitControl {
+    Stop,
+    Restart,
+}
+
+impl UnitControl {
+    pub fn label(&self) -> &'static str {
+        match self {
+            UnitControl::Stop => "stop",
+            UnitControl::Restart => "restart",
+        }
+    }
+}
+
+impl Unit {
+    fn systemctl(&self) -> Command {
+        let mut command = Command::new("systemctl");
+        if self.user {
+            command.arg("--user");
+        }
+        // a polkit prompt would fight the TUI for the terminal
+        command.arg("--no-ask-password");
+        command
+    }
+
+    // only services have one, none for scopes or when systemctl isn't there
+    pub fn main_pid(&self) -> Option<Pid> {
+        if !self.name.ends_with(".service") || !in_path("systemctl") {
+            return None;
+        }
+        let output = self
+            .systemctl()
+            .args(["show", "--property", "MainPID", "--value", &self.name])
+            .output()
+            .ok()?;
+        match String::from_utf8_lossy(&output.stdout).trim().parse() {
+            Ok(0) | Err(_) => None,
+            Ok(pid) => Some(Pid::from_u32(pid)),
+        }
+    }
+}
+
+// stopping a system unit usually needs root
+pub fn control_unit(unit: &Unit, control: UnitControl) -> io::Result<()> {
+    let output = unit
+        .systemctl()
+        .args([control.label(), &unit.name])
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(stderr.trim().to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,1610 @@
+use std::{path::Path, time::Instant};
+
+use sysinfo::{ProcessExt, ProcessStatus, SystemExt, UserExt};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans, Text},
+    widgets::{
+        Block, Borders, Cell, List, ListItem, Paragraph, Row, Sparkline, Table, TableState, Wrap,
+    },
+    Frame,
+};
+
+use crate::{
+    app::{App, InputMode, EXITED_KEEP},
+    cgroup::{process_cgroup, Cgroup},
+    columns::Column,
+    config::Theme,
+    input::{Action, KeyMap},
+    process::{
+        cpu_affinity, format_bytes, format_cpu_list, format_duration, format_timestamp, nice_value,
+        signal_name, status_label, SortOrder, StateFilter, SystemSummary, SIGNALS,
+    },
+    services::ServiceState,
+};
+
+// below this the layout is replaced by a message asking for a larger terminal
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 12;
+// the instructions bar, the system summary and the core meters are dropped in this order
+// as the terminal gets shorter, the meters also need room for their own rows
+const INSTRUCTIONS_MIN_HEIGHT: u16 = 20;
+const SUMMARY_MIN_HEIGHT: u16 = 16;
+const METERS_MIN_HEIGHT: u16 = 24;
+// narrower than this the details pane goes under the table instead of next to it
+const DETAILS_SIDE_MIN_WIDTH: u16 = 120;
+
+// no point listing more processes in a popup than fit on screen
+const MAX_LISTED: usize = 10;
+
+// shown next to the refresh interval while a refresh runs
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+pub(crate) fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let size = f.size();
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        render_too_small(f, app, size);
+        return;
+    }
+
+    // the search input and the table always, the rest only while there is room for it
+    let show_instructions = size.height >= INSTRUCTIONS_MIN_HEIGHT;
+    let show_summary = size.height >= SUMMARY_MIN_HEIGHT;
+    let summary = app.manager.summary();
+    let meters_height = meter_rows(summary.cores.len()) as u16 + 2;
+    let show_meters = app.show_cpu_meters && size.height >= meters_height + METERS_MIN_HEIGHT;
+    let chunks = Layout::default()
+        .direction(tui::layout::Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(if show_summary { 3 } else { 0 }),
+            Constraint::Length(if show_meters { meters_height } else { 0 }),
+            Constraint::Min(0),
+            Constraint::Length(if show_instructions { 3 } else { 0 }),
+            Constraint::Length(1),
+        ])
+        .split(size);
+    let main_area = chunks[3];
+    if show_summary {
+        render_summary(f, app, &summary, chunks[1]);
+    }
+    if show_meters {
+        render_cpu_meters(f, app, &summary.cores, chunks[2]);
+    }
+
+    // side by side on a wide terminal, the details go under the table on a narrow one
+    let (table_area, details_area) = if app.show_details {
+        let (direction, constraints) = if size.width >= DETAILS_SIDE_MIN_WIDTH {
+            (
+                tui::layout::Direction::Horizontal,
+                [Constraint::Percentage(60), Constraint::Percentage(40)],
+            )
+        } else {
+            (
+                tui::layout::Direction::Vertical,
+                [Constraint::Percentage(50), Constraint::Percentage(50)],
+            )
+        };
+        let panes = Layout::default()
+            .direction(direction)
+            .constraints(constraints)
+            .split(main_area);
+        (panes[0], Some(panes[1]))
+    } else {
+        (main_area, None)
+    };
+
+    // two borders and the header
+    app.table_height = table_area.height.saturating_sub(3) as usize;
+    app.table_area = table_area;
+    app.scroll_to_selection();
+
+    let input_block = match &app.search_error {
+        Some(err) => Block::default()
+            .title(format!(
+                "Invalid regex: {}",
+                err.lines().last().unwrap_or("")
+            ))
+            .border_style(app.config.theme.warning),
+        None => Block::default().title(format!(
+            "Fuzzy search processes (prefix with / for regex, !<term> to hide, u:<user> for a user, :<port> for a port) | {}",
+            if app.only_my_processes {
+                "Only my processes"
+            } else {
+                "All users"
+            }
+        )),
+    }
+    .borders(Borders::ALL);
+
+    let mut title = String::from("Process Killer By @himanshurajora, The Vedik Dev");
+    title = format!(
+        "{}/{} | {}",
+        app.state.selected().map_or(0, |i| i + 1),
+        app.processes.len(),
+        title
+    );
+    if let Some(host) = &app.remote_host {
+        title = format!("{} | {}", host, title);
+    }
+    if app.visual.is_some() {
+        title = format!("VISUAL | {}", title);
+    }
+    // the soonest one, `W` lists them all
+    if let Some(kill) = app.scheduled_kills.first() {
+        let left = kill.at.saturating_duration_since(Instant::now());
+        title = format!(
+            "{} scheduled, next {} in {} | {}",
+            app.scheduled_kills.len(),
+            kill.name,
+            format_duration(left.as_secs()),
+            title
+        );
+    }
+    let pending_keys = app.pending_keys.label();
+    if !pending_keys.is_empty() {
+        title = format!("{} | {}", pending_keys, title);
+    }
+    if app.state_filter != StateFilter::All {
+        title = format!("Only {} | {}", app.state_filter.label(), title);
+    }
+    let main_block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_alignment(tui::layout::Alignment::Right);
+
+    let instruction_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Instructions")
+        .style(app.config.theme.status);
+
+    let tree_view = app.tree_view;
+    let collapsed = &app.collapsed;
+    let grouped = app.grouped;
+    let expanded = &app.expanded;
+    let marked = &app.marked;
+    let theme = app.config.theme;
+    let show_cmd = app.show_cmd;
+    let name_scroll = app.name_scroll;
+    let memory_kind = app.memory_kind;
+    // the gpu ones only when there is a gpu to report on
+    let gpu = app.manager.has_gpu();
+    let columns: Vec<Column> = app
+        .columns
+        .iter()
+        .filter(|(column, shown)| *shown && (gpu || !column.is_gpu()))
+        .map(|(column, _)| *column)
+        .collect();
+    let rows = app
+        .processes
+        .iter()
+        .enumerate()
+        .skip(app.scroll_offset)
+        .take(app.table_height)
+        .map(|(i, f)| {
+            let cell = |column: &Column| match column {
+                Column::Index => Cell::from(i.to_string()),
+                // a name's row stands for several processes
+                Column::Pid | Column::Ppid if !f.members.is_empty() => Cell::from("-"),
+                Column::Pid => Cell::from(f.pid.to_string()),
+                Column::Ppid => {
+                    Cell::from(f.parent.map_or("-".to_string(), |ppid| ppid.to_string()))
+                }
+                Column::Cpu => Cell::from(format!("{:.1}", f.cpu_usage)),
+                Column::Memory => {
+                    Cell::from(memory_kind.of(f).map_or("-".to_string(), format_bytes))
+                }
+                Column::DiskRead => Cell::from(format!("{}/s", format_bytes(f.disk_read))),
+                Column::DiskWrite => Cell::from(format!("{}/s", format_bytes(f.disk_write))),
+                Column::GpuMemory => Cell::from(f.gpu_memory.map_or("-".to_string(), format_bytes)),
+                Column::GpuUsage => {
+                    Cell::from(f.gpu_usage.map_or("-".to_string(), |u| format!("{:.1}", u)))
+                }
+                Column::Threads => Cell::from(f.threads.map_or("-".to_string(), |n| n.to_string())),
+                Column::User => Cell::from(f.user.to_string()),
+                Column::Cgroup => {
+                    Cell::from(f.cgroup.as_ref().map_or("-".to_string(), Cgroup::label))
+                }
+                Column::Nice => Cell::from(f.nice.map_or("-".to_string(), |n| n.to_string())),
+                Column::State => Cell::from(status_label(f.status)),
+                Column::Age => Cell::from(format_duration(f.run_time)),
+                Column::Name => {
+                    // kernel threads have no command line, show their name in brackets like ps does
+                    let label = match (show_cmd, f.cmd.is_empty()) {
+                        // the processes of a name may all have been started differently
+                        _ if !f.members.is_empty() => format!("{} ×{}", f.name, f.members.len()),
+                        (true, false) => f.cmd.to_string(),
+                        (true, true) => format!("[{}]", f.name),
+                        (false, _) => f.name.to_string(),
+                    };
+                    // like ps, a zombie's name alone does not tell it is one
+                    let label = if f.status == ProcessStatus::Zombie {
+                        format!("{} <defunct>", label)
+                    } else {
+                        label
+                    };
+                    let label: String = label.chars().skip(name_scroll).collect();
+                    if tree_view || grouped {
+                        let closed = if grouped {
+                            !expanded.contains(&f.name)
+                        } else {
+                            collapsed.contains(&f.pid)
+                        };
+                        let marker = match (f.has_children, closed) {
+                            (false, _) => "  ",
+                            (true, true) => "▸ ",
+                            (true, false) => "▾ ",
+                        };
+                        Cell::from(format!("{}{}{}", "  ".repeat(f.depth), marker, label))
+                    } else {
+                        Cell::from(label)
+                    }
+                }
+            };
+
+            let row = Row::new(columns.iter().map(cell));
+            let is_marked = if f.members.is_empty() {
+                marked.contains(&f.pid)
+            } else {
+                f.members.iter().all(|pid| marked.contains(pid))
+            };
+            if is_marked {
+                row.style(theme.highlight)
+            } else if f.status == ProcessStatus::Zombie {
+                row.style(theme.warning)
+            } else if f.status == ProcessStatus::Stop {
+                row.style(Style::default().add_modifier(Modifier::DIM))
+            } else if f.spawned {
+                row.style(theme.status.add_modifier(Modifier::BOLD))
+            } else if f.pinned {
+                row.style(theme.accent)
+            } else {
+                row
+            }
+        });
+
+    let selected_style = theme.selection;
+
+    let header: Vec<Cell> = columns
+        .iter()
+        .map(|column| match (column, column.sort_column()) {
+            // how far the column is scrolled, the start of the names is out of view
+            (Column::Name, _) if app.name_scroll > 0 => Cell::from(format!(
+                "{} (+{})",
+                if app.show_cmd { "Command" } else { "Name" },
+                app.name_scroll
+            )),
+            (Column::Name, _) if app.show_cmd => Cell::from("Command"),
+            (_, Some(sort)) => Cell::from(app.column_header(sort)),
+            (_, None) => Cell::from(column.title()),
+        })
+        .collect();
+    let widths: Vec<Constraint> = columns.iter().map(Column::width).collect();
+
+    let table = Table::new(rows)
+        .header(Row::new(header).style(theme.header))
+        .block(main_block)
+        .highlight_style(selected_style)
+        .highlight_symbol(">> ")
+        .widths(&widths);
+
+    // only the essentials, the rest is in the help overlay
+    let keymap = &app.config.keymap;
+    let instructions: Vec<Span> = [
+        Action::Kill,
+        Action::KillMarked,
+        Action::SignalMenu,
+        Action::ToggleMark,
+        Action::Search,
+        Action::Help,
+    ]
+    .iter()
+    .map(|action| {
+        Span::from(format!(
+            "'{} {}' ",
+            keymap.keys(*action),
+            action.description()
+        ))
+    })
+    .collect();
+
+    let sort_name_text = Text::from(Spans::from(instructions));
+    let paragraph = Paragraph::new(sort_name_text).block(instruction_block);
+
+    let mut char_spans = vec![];
+    app.search_input
+        .chars()
+        .for_each(|c| char_spans.push(Span::from(c.to_string())));
+
+    let input_text = Text::from(Spans::from(char_spans));
+    let width = input_text.width() as u16;
+
+    let input_paragraph = match &app.input_mode {
+        InputMode::Editing => Paragraph::new(input_text)
+            .style(theme.highlight)
+            .block(input_block),
+        _ => Paragraph::new(app.search_input.as_ref()).block(input_block),
+    };
+
+    f.set_cursor(chunks[0].x + width + 1, chunks[0].y + 1);
+    f.render_widget(input_paragraph, chunks[0]);
+    // the rows are already sliced to the viewport, so select relative to it
+    let mut table_state = TableState::default();
+    table_state.select(app.state.selected().map(|i| i - app.scroll_offset));
+    f.render_stateful_widget(table, table_area, &mut table_state);
+
+    if let Some(area) = details_area {
+        let areas = Layout::default()
+            .direction(tui::layout::Direction::Vertical)
+            .constraints([Constraint::Length(6), Constraint::Min(0)])
+            .split(area);
+        render_cpu_history(f, app, areas[0]);
+        let details = Paragraph::new(details_text(app))
+            .block(Block::default().borders(Borders::ALL).title("Details"))
+            .wrap(Wrap { trim: false });
+        f.render_widget(details, areas[1]);
+    }
+    if show_instructions {
+        f.render_widget(paragraph, chunks[4]);
+    }
+    render_status_bar(f, app, &summary, chunks[5]);
+
+    if let InputMode::SignalMenu = app.input_mode {
+        let items: Vec<ListItem> = SIGNALS
+            .iter()
+            .map(|(_, name)| ListItem::new(*name))
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Send signal to {} process(es), # for any other",
+                app.targets().len()
+            )))
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ");
+
+        let area = centered_rect(40, SIGNALS.len() as u16 + 2, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_stateful_widget(list, area, &mut app.signal_menu_state);
+    }
+
+    if let InputMode::Filters = app.input_mode {
+        let name_width = app
+            .config
+            .filters
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .max()
+            .unwrap_or(0);
+        let items: Vec<ListItem> = app
+            .config
+            .filters
+            .iter()
+            .map(|(name, input)| {
+                ListItem::new(Spans::from(vec![
+                    Span::styled(
+                        format!("{:<width$}  ", name, width = name_width),
+                        theme.accent,
+                    ),
+                    Span::from(input.as_str()),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Saved filters (Enter to apply, Esc to close)"),
+            )
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ");
+
+        let area = centered_rect(60, app.config.filters.len() as u16 + 2, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_stateful_widget(list, area, &mut app.filters_state);
+    }
+
+    if let InputMode::Columns = app.input_mode {
+        let items: Vec<ListItem> = app
+            .columns
+            .iter()
+            .map(|(column, shown)| {
+                let check = if *shown { "[x]" } else { "[ ]" };
+                ListItem::new(format!("{} {}", check, column.title()))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Columns (Space show/hide, J/K move, Esc to save)"),
+            )
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ");
+
+        let area = centered_rect(50, app.columns.len() as u16 + 2, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_stateful_widget(list, area, &mut app.columns_state);
+    }
+
+    if let InputMode::Affinity = app.input_mode {
+        let title = match app.selected_process() {
+            Some(process) => format!("CPU affinity of {} {}", process.pid(), process.name()),
+            None => "CPU affinity".to_string(),
+        };
+        let popup = Paragraph::new(vec![
+            Spans::from(app.affinity_input.as_str()),
+            Spans::from(""),
+            Spans::from("CPUs like 0-3,6, Enter to apply, Esc to cancel"),
+        ])
+        .style(theme.highlight)
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+        let area = centered_rect(50, 5, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(popup, area);
+        f.set_cursor(area.x + app.affinity_input.len() as u16 + 1, area.y + 1);
+    }
+
+    if let InputMode::ScheduleKill = app.input_mode {
+        let title = match app.selected_process() {
+            Some(process) => format!("Kill {} {} in", process.pid(), process.name()),
+            None => "Kill in".to_string(),
+        };
+        let popup = Paragraph::new(vec![
+            Spans::from(app.schedule_input.as_str()),
+            Spans::from(""),
+            Spans::from("e.g. 90s, 30m or 2h, Enter to schedule, Esc to cancel"),
+        ])
+        .style(theme.highlight)
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+        let area = centered_rect(60, 5, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(popup, area);
+        f.set_cursor(area.x + app.schedule_input.len() as u16 + 1, area.y + 1);
+    }
+
+    if let InputMode::CoreDump = app.input_mode {
+        let title = match app.selected_process() {
+            Some(process) => format!("Dump {} {}", process.pid(), process.name()),
+            None => "Dump".to_string(),
+        };
+        let choice = |key: &'static str, text: &'static str| {
+            Spans::from(vec![Span::styled(key, theme.accent), Span::from(text)])
+        };
+        let popup = Paragraph::new(vec![
+            choice(
+                "q  ",
+                "SIGQUIT, a thread dump from the JVM, a core dump and exit from most others",
+            ),
+            choice("a  ", "SIGABRT, a core dump and exit"),
+            choice(
+                "g  ",
+                "gcore, a core dump to ~/.local/share/process_killer/cores while it keeps running",
+            ),
+            Spans::from(""),
+            Spans::from(
+                "The signals only leave a core dump when the core file size limit allows it (U)",
+            ),
+            Spans::from("Esc to cancel"),
+        ])
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: true });
+
+        let area = centered_rect(70, 10, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(popup, area);
+    }
+
+    if let InputMode::SignalInput = app.input_mode {
+        let popup = Paragraph::new(vec![
+            Spans::from(app.signal_input.as_str()),
+            Spans::from(""),
+            Spans::from("A name like USR1 or SIGHUP, or a number, Enter to send, Esc to cancel"),
+        ])
+        .style(theme.highlight)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Send signal to {} process(es)",
+            app.targets().len()
+        )));
+
+        let area = centered_rect(80, 5, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(popup, area);
+        f.set_cursor(area.x + app.signal_input.len() as u16 + 1, area.y + 1);
+    }
+
+    if let InputMode::Spawn = app.input_mode {
+        let popup = Paragraph::new(vec![
+            Spans::from(app.spawn_input.as_str()),
+            Spans::from(""),
+            Spans::from("Enter to start it through the shell, Esc to cancel"),
+        ])
+        .style(theme.highlight)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Start a command"),
+        );
+
+        let area = centered_rect(80, 5, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(popup, area);
+        f.set_cursor(area.x + app.spawn_input.len() as u16 + 1, area.y + 1);
+    }
+
+    if let InputMode::JumpToPid = app.input_mode {
+        let popup = Paragraph::new(vec![
+            Spans::from(app.jump_input.as_str()),
+            Spans::from(""),
+            Spans::from("Enter to jump, Esc to cancel"),
+        ])
+        .style(theme.highlight)
+        .block(Block::default().borders(Borders::ALL).title("Jump to PID"));
+
+        let area = centered_rect(40, 5, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(popup, area);
+        f.set_cursor(area.x + app.jump_input.len() as u16 + 1, area.y + 1);
+    }
+
+    if let InputMode::Help = app.input_mode {
+        let help = Paragraph::new(help_text(&app.config.keymap, &app.config.theme))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Help (j/k to scroll, Esc or ? to close)"),
+            )
+            .scroll((app.help_scroll, 0));
+
+        f.render_widget(tui::widgets::Clear, size);
+        f.render_widget(help, size);
+    }
+
+    if let InputMode::Services = app.input_mode {
+        let rows = app.services.iter().map(|service| {
+            let row = Row::new([
+                Cell::from(service.name.clone()),
+                Cell::from(service.display_name.clone()),
+                Cell::from(service.state.label()),
+                Cell::from(service.pid.map_or("-".to_string(), |pid| pid.to_string())),
+            ]);
+            match service.state {
+                ServiceState::Running => row,
+                _ => row.style(Style::default().add_modifier(Modifier::DIM)),
+            }
+        });
+        let table = Table::new(rows)
+            .header(Row::new(["Name", "Display name", "State", "PID"]).style(theme.header))
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "{} services (x stop, g start, r restart, Esc to close)",
+                app.services.len()
+            )))
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ")
+            .widths(&[
+                Constraint::Percentage(30),
+                Constraint::Percentage(50),
+                Constraint::Length(10),
+                Constraint::Length(8),
+            ]);
+
+        f.render_widget(tui::widgets::Clear, main_area);
+        f.render_stateful_widget(table, main_area, &mut app.services_state);
+    }
+
+    if let InputMode::OpenFiles = app.input_mode {
+        let process = app.pane_pid.and_then(|pid| app.manager.process(pid));
+        let title = match process {
+            Some(process) => format!(
+                "{} open files of {} {} (Esc to close)",
+                app.open_files.len(),
+                process.pid(),
+                process.name()
+            ),
+            None => format!("{} open files (Esc to close)", app.open_files.len()),
+        };
+        let rows = app.open_files.iter().map(|file| {
+            Row::new([
+                Cell::from(file.fd.to_string()),
+                Cell::from(file.kind.label()),
+                Cell::from(file.target.clone()),
+            ])
+        });
+        let table = Table::new(rows)
+            .header(Row::new(["FD", "Type", "Name"]).style(theme.header))
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ")
+            .widths(&[
+                Constraint::Length(6),
+                Constraint::Length(8),
+                Constraint::Min(40),
+            ]);
+
+        f.render_widget(tui::widgets::Clear, main_area);
+        f.render_stateful_widget(table, main_area, &mut app.open_files_state);
+    }
+
+    if let InputMode::Limits = app.input_mode {
+        let process = app.pane_pid.and_then(|pid| app.manager.process(pid));
+        let title = match process {
+            Some(process) => format!(
+                "Resource limits of {} {} (Esc to close)",
+                process.pid(),
+                process.name()
+            ),
+            None => "Resource limits (Esc to close)".to_string(),
+        };
+        let rows = app.limits.iter().map(|(limit, used)| {
+            // sizes read better with a unit
+            let value = |value: &str| match (limit.units.as_str(), value.parse()) {
+                ("bytes", Ok(bytes)) => format_bytes(bytes),
+                _ => value.to_string(),
+            };
+            let used_label = used.map_or("-".to_string(), |used| value(&used.to_string()));
+            // close to running out, e.g. of file descriptors
+            let style = match (used, limit.soft_value()) {
+                (Some(used), Some(soft)) if *used >= soft / 10 * 8 => theme.warning,
+                _ => Style::default(),
+            };
+            Row::new([
+                Cell::from(limit.name.clone()),
+                Cell::from(used_label),
+                Cell::from(value(&limit.soft)),
+                Cell::from(value(&limit.hard)),
+                Cell::from(limit.units.clone()),
+            ])
+            .style(style)
+        });
+        let table = Table::new(rows)
+            .header(Row::new(["Limit", "Used", "Soft", "Hard", "Units"]).style(theme.header))
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ")
+            .widths(&[
+                Constraint::Length(26),
+                Constraint::Length(12),
+                Constraint::Length(14),
+                Constraint::Length(14),
+                Constraint::Min(10),
+            ]);
+
+        f.render_widget(tui::widgets::Clear, main_area);
+        f.render_stateful_widget(table, main_area, &mut app.limits_state);
+    }
+
+    if let InputMode::Connections = app.input_mode {
+        let process = app.pane_pid.and_then(|pid| app.manager.process(pid));
+        let title = match process {
+            Some(process) => format!(
+                "{} connections of {} {} (Esc to close)",
+                app.connections.len(),
+                process.pid(),
+                process.name()
+            ),
+            None => format!("{} connections (Esc to close)", app.connections.len()),
+        };
+        let rows = app.connections.iter().map(|connection| {
+            let remote = if connection.remote.port() == 0 {
+                "-".to_string()
+            } else {
+                connection.remote.to_string()
+            };
+            Row::new([
+                Cell::from(connection.protocol.label()),
+                Cell::from(connection.local.to_string()),
+                Cell::from(remote),
+                Cell::from(connection.state),
+            ])
+        });
+        let table = Table::new(rows)
+            .header(Row::new(["Proto", "Local", "Remote", "State"]).style(theme.header))
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ")
+            .widths(&[
+                Constraint::Length(6),
+                Constraint::Percentage(35),
+                Constraint::Percentage(35),
+                Constraint::Length(12),
+            ]);
+
+        f.render_widget(tui::widgets::Clear, main_area);
+        f.render_stateful_widget(table, main_area, &mut app.connections_state);
+    }
+
+    if let InputMode::Threads = app.input_mode {
+        let process = app.pane_pid.and_then(|pid| app.manager.process(pid));
+        let title = match process {
+            Some(process) => format!(
+                "{} threads of {} {} (Esc to close)",
+                app.threads.len(),
+                process.pid(),
+                process.name()
+            ),
+            None => format!("{} threads (Esc to close)", app.threads.len()),
+        };
+        let rows = app.threads.iter().map(|thread| {
+            Row::new([
+                Cell::from(thread.tid.to_string()),
+                Cell::from(thread.name.clone()),
+                Cell::from(format!("{:.1}", thread.cpu_usage)),
+                Cell::from(status_label(thread.status)),
+            ])
+        });
+        let table = Table::new(rows)
+            .header(Row::new(["TID", "Name", "CPU%", "State"]).style(theme.header))
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ")
+            .widths(&[
+                Constraint::Length(10),
+                Constraint::Min(30),
+                Constraint::Length(8),
+                Constraint::Length(10),
+            ]);
+
+        f.render_widget(tui::widgets::Clear, main_area);
+        f.render_stateful_widget(table, main_area, &mut app.threads_state);
+    }
+
+    if let InputMode::Children = app.input_mode {
+        let process = app.pane_pid.and_then(|pid| app.manager.process(pid));
+        let direct = app.children.iter().filter(|child| child.depth == 1).count();
+        let counts = format!("{} children, {} in total", direct, app.children.len());
+        let title = match process {
+            Some(process) => format!(
+                "{} of {} {} (Esc to close)",
+                counts,
+                process.pid(),
+                process.name()
+            ),
+            None => format!("{} (Esc to close)", counts),
+        };
+        let rows = app.children.iter().map(|child| {
+            Row::new([
+                Cell::from(child.pid.to_string()),
+                Cell::from(format!("{}{}", "  ".repeat(child.depth - 1), child.name)),
+                Cell::from(format!("{:.1}", child.cpu_usage)),
+                Cell::from(format_bytes(child.memory)),
+            ])
+        });
+        let table = Table::new(rows)
+            .header(Row::new(["PID", "Name", "CPU%", "Memory"]).style(theme.header))
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ")
+            .widths(&[
+                Constraint::Length(10),
+                Constraint::Min(30),
+                Constraint::Length(8),
+                Constraint::Length(12),
+            ]);
+
+        f.render_widget(tui::widgets::Clear, main_area);
+        f.render_stateful_widget(table, main_area, &mut app.children_state);
+    }
+
+    if let InputMode::History = app.input_mode {
+        let title = format!(
+            "{} actions this session, newest first (Esc to close)",
+            app.history.len()
+        );
+        let rows = app.history.iter().rev().map(|entry| {
+            let (result, style) = match &entry.result {
+                Ok(()) => ("ok".to_string(), Style::default()),
+                Err(err) => (err.clone(), theme.warning),
+            };
+            Row::new([
+                Cell::from(format_timestamp(entry.at)),
+                Cell::from(entry.action.clone()),
+                Cell::from(entry.target.clone()),
+                Cell::from(result),
+            ])
+            .style(style)
+        });
+        let table = Table::new(rows)
+            .header(Row::new(["Time", "Action", "Target", "Result"]).style(theme.header))
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ")
+            .widths(&[
+                Constraint::Length(20),
+                Constraint::Length(20),
+                Constraint::Length(30),
+                Constraint::Min(30),
+            ]);
+
+        f.render_widget(tui::widgets::Clear, main_area);
+        f.render_stateful_widget(table, main_area, &mut app.history_state);
+    }
+
+    if let InputMode::ScheduledKills = app.input_mode {
+        let title = format!(
+            "{} scheduled kills, soonest first (d to cancel, Esc to close)",
+            app.scheduled_kills.len()
+        );
+        let rows = app.scheduled_kills.iter().map(|kill| {
+            let left = kill.at.saturating_duration_since(Instant::now());
+            Row::new([
+                Cell::from(format_duration(left.as_secs())),
+                Cell::from(kill.pid.to_string()),
+                Cell::from(kill.name.clone()),
+            ])
+        });
+        let table = Table::new(rows)
+            .header(Row::new(["In", "PID", "Name"]).style(theme.header))
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ")
+            .widths(&[
+                Constraint::Length(12),
+                Constraint::Length(10),
+                Constraint::Min(20),
+            ]);
+
+        f.render_widget(tui::widgets::Clear, main_area);
+        f.render_stateful_widget(table, main_area, &mut app.scheduled_state);
+    }
+
+    if let InputMode::Alerts = app.input_mode {
+        let title = format!(
+            "{} processes over an alert's limit (Enter to select, Esc to dismiss)",
+            app.alerts.len()
+        );
+        let rows = app.alerts.iter().map(|alert| {
+            Row::new([
+                Cell::from(format_timestamp(alert.at)),
+                Cell::from(alert.pid.to_string()),
+                Cell::from(alert.name.clone()),
+                Cell::from(format!("{}: {}", alert.alert, alert.limits)),
+            ])
+        });
+        let table = Table::new(rows)
+            .header(Row::new(["Time", "PID", "Name", "Alert"]).style(theme.header))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .style(theme.warning),
+            )
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ")
+            .widths(&[
+                Constraint::Length(20),
+                Constraint::Length(10),
+                Constraint::Length(20),
+                Constraint::Min(30),
+            ]);
+
+        let height = app.alerts.len().min(MAX_LISTED) as u16 + 3;
+        let area = centered_rect(90, height, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_stateful_widget(table, area, &mut app.alerts_state);
+    }
+
+    if let InputMode::Exited = app.input_mode {
+        let title = format!(
+            "{} processes exited over the last {} minutes (Enter to start a killed one again, Esc to close)",
+            app.exited.len(),
+            EXITED_KEEP.as_secs() / 60
+        );
+        let rows = app.exited.iter().map(|exited| {
+            let how = match exited.signal {
+                Some(signal) => format!("after {}", signal_name(signal)),
+                None => "on its own".to_string(),
+            };
+            Row::new([
+                Cell::from(exited.pid.to_string()),
+                Cell::from(exited.name.clone()),
+                Cell::from(how),
+                Cell::from(format!(
+                    "{} ago",
+                    format_duration(exited.exited_at.elapsed().as_secs())
+                )),
+                Cell::from(exited.cmd.clone()),
+            ])
+        });
+        let table = Table::new(rows)
+            .header(Row::new(["PID", "Name", "Exited", "When", "Command"]).style(theme.header))
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ")
+            .widths(&[
+                Constraint::Length(10),
+                Constraint::Length(20),
+                Constraint::Length(16),
+                Constraint::Length(12),
+                Constraint::Min(30),
+            ]);
+
+        f.render_widget(tui::widgets::Clear, main_area);
+        f.render_stateful_widget(table, main_area, &mut app.exited_state);
+    }
+
+    if let Some(pending) = &app.pending_signal {
+        let mut lines = vec![];
+        for pid in pending.pids.iter().take(MAX_LISTED) {
+            let line = match app.manager.process(*pid) {
+                Some(process) => {
+                    format!("{} {} | {}", pid, process.name(), process.cmd().join(" "))
+                }
+                None => format!("{} (already exited)", pid),
+            };
+            lines.push(Spans::from(line));
+        }
+        if pending.pids.len() > MAX_LISTED {
+            lines.push(Spans::from(format!(
+                "... and {} more",
+                pending.pids.len() - MAX_LISTED
+            )));
+        }
+        if let Some(pending) = &app.pending_container {
+            let container = &pending.container;
+            let name = match &pending.name {
+                Some(name) => format!("{} ({})", name, container.short_id()),
+                None => container.short_id().to_string(),
+            };
+            lines.push(Spans::from(""));
+            lines.push(Spans::from(format!(
+                "This is the main process of {0} container {1}, S to {0} stop it instead, K to {0} kill it",
+                container.runtime, name
+            )));
+        }
+        if let Some(unit) = &app.pending_unit {
+            lines.push(Spans::from(""));
+            lines.push(Spans::from(format!(
+                "This is the main process of {}, systemd may restart it. S to stop the unit instead, R to restart it",
+                unit.name
+            )));
+        }
+        lines.push(Spans::from(""));
+        lines.push(Spans::from("Press Y to confirm, N or Esc to cancel"));
+
+        let height = lines.len() as u16 + 2;
+        let title = if pending.force {
+            format!(
+                "Send {}, then SIGKILL after {}s?",
+                signal_name(pending.signal),
+                app.config.polite_kill_timeout.as_secs()
+            )
+        } else {
+            format!("Send {}?", signal_name(pending.signal))
+        };
+        let popup = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .style(theme.warning),
+            )
+            .wrap(Wrap { trim: true });
+
+        let area = centered_rect(80, height, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(popup, area);
+    }
+
+    if let Some((pid, dir)) = &app.pending_core_dump {
+        let target = match app.manager.process(*pid) {
+            Some(process) => format!("{} {} | {}", pid, process.name(), process.cmd().join(" ")),
+            None => format!("{} (already exited)", pid),
+        };
+        let lines = vec![
+            Spans::from(target),
+            Spans::from(""),
+            Spans::from(format!(
+                "gcore stops it while reading its memory and writes {}",
+                dir.join(format!("core.{}", pid)).display()
+            )),
+            Spans::from(""),
+            Spans::from("Press Y to confirm, N or Esc to cancel"),
+        ];
+        let popup = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Dump the core with gcore?")
+                    .style(theme.warning),
+            )
+            .wrap(Wrap { trim: true });
+
+        let area = centered_rect(80, 9, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(popup, area);
+    }
+
+    if let Some(zombies) = &app.zombies {
+        let mut lines = vec![];
+        for pid in zombies.iter().take(MAX_LISTED) {
+            let Some(process) = app.manager.process(*pid) else {
+                continue;
+            };
+            let parent = match process.parent().and_then(|ppid| app.manager.process(ppid)) {
+                Some(parent) => format!("{} {}", parent.pid(), parent.name()),
+                None => "none".to_string(),
+            };
+            lines.push(Spans::from(format!(
+                "{} {} (parent {})",
+                pid,
+                process.name(),
+                parent
+            )));
+        }
+        if zombies.len() > MAX_LISTED {
+            lines.push(Spans::from(format!(
+                "... and {} more",
+                zombies.len() - MAX_LISTED
+            )));
+        }
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(
+            "A zombie has exited already and can't be killed, it stays in the table until its parent reaps it. \
+             SIGCHLD reminds the parent to, once the parent is gone init adopts and reaps it.",
+        ));
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(
+            "Press C to send SIGCHLD to the parent, K to kill the parent, N or Esc to cancel",
+        ));
+
+        // room for the explanation to wrap
+        let height = lines.len() as u16 + 4;
+        let popup = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Zombie")
+                    .style(theme.warning),
+            )
+            .wrap(Wrap { trim: true });
+
+        let area = centered_rect(60, height, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(popup, area);
+    }
+
+    if let Some(survivors) = &app.survivors {
+        let mut lines = vec![Spans::from(format!(
+            "Still running after {}:",
+            signal_name(survivors.signal)
+        ))];
+        for pid in survivors.pids.iter().take(MAX_LISTED) {
+            if let Some(process) = app.manager.process(*pid) {
+                lines.push(Spans::from(format!(
+                    "{} {} ({})",
+                    pid,
+                    process.name(),
+                    status_label(process.status())
+                )));
+            }
+        }
+        if survivors.pids.len() > MAX_LISTED {
+            lines.push(Spans::from(format!(
+                "... and {} more",
+                survivors.pids.len() - MAX_LISTED
+            )));
+        }
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(
+            "Press K to send SIGKILL, N or Esc to leave them",
+        ));
+
+        let height = lines.len() as u16 + 2;
+        let popup = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Not dead yet")
+                    .style(theme.warning),
+            )
+            .wrap(Wrap { trim: true });
+
+        let area = centered_rect(60, height, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(popup, area);
+    }
+
+    if let (Some(denied), Some(tool)) = (&app.denied, app.elevation_tool) {
+        let lines = vec![
+            Spans::from(format!(
+                "Permission denied sending {} to {} process(es) owned by another user.",
+                signal_name(denied.signal),
+                denied.pids.len()
+            )),
+            Spans::from(""),
+            Spans::from(format!(
+                "Press S to retry with {}, N or Esc to cancel",
+                tool
+            )),
+        ];
+        let popup = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Permission denied")
+                    .style(theme.warning),
+            )
+            .wrap(Wrap { trim: true });
+
+        let area = centered_rect(60, 6, size);
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(popup, area);
+    }
+
+    // toasts stack upwards from the bottom right corner, newest at the bottom
+    let mut bottom = chunks[4].y;
+    for toast in app.toasts.iter().rev() {
+        let width = (toast.message.chars().count() as u16 + 4)
+            .min(size.width / 2)
+            .max(10);
+        let lines = (toast.message.chars().count() as u16)
+            .div_ceil(width - 2)
+            .max(1);
+        let height = lines + 2;
+        if bottom < height {
+            break;
+        }
+        bottom -= height;
+
+        let area = Rect::new(size.width - width, bottom, width, height);
+        let popup = Paragraph::new(toast.message.as_str())
+            .block(Block::default().borders(Borders::ALL).style(theme.warning))
+            .wrap(Wrap { trim: true });
+        f.render_widget(tui::widgets::Clear, area);
+        f.render_widget(popup, area);
+    }
+}
+
+fn render_too_small<B: Backend>(f: &mut Frame<B>, app: &App, size: Rect) {
+    let lines = vec![
+        Spans::from("Terminal too small"),
+        Spans::from(format!(
+            "{}x{}, at least {}x{} needed",
+            size.width, size.height, MIN_WIDTH, MIN_HEIGHT
+        )),
+    ];
+    let paragraph = Paragraph::new(lines)
+        .style(app.config.theme.warning)
+        .alignment(tui::layout::Alignment::Center)
+        .wrap(Wrap { trim: true });
+    let height = 2.min(size.height);
+    let area = Rect::new(
+        size.x,
+        size.y + (size.height - height) / 2,
+        size.width,
+        height,
+    );
+    f.render_widget(paragraph, area);
+}
+
+// every binding grouped by category, followed by the fixed keys of the popups
+fn help_text(keymap: &KeyMap, theme: &Theme) -> Vec<Spans<'static>> {
+    let heading = theme.accent;
+    let binding = |keys: String, description: &str| {
+        Spans::from(vec![
+            Span::styled(format!("  {:<16}", keys), theme.highlight),
+            Span::from(description.to_string()),
+        ])
+    };
+
+    let mut lines = vec![];
+    let mut category = "";
+    for action in Action::ALL {
+        if action.category() != category {
+            category = action.category();
+            if !lines.is_empty() {
+                lines.push(Spans::from(""));
+            }
+            lines.push(Spans::from(Span::styled(category, heading)));
+        }
+        lines.push(binding(keymap.keys(action), action.description()));
+    }
+
+    lines.push(Spans::from(""));
+    lines.push(Spans::from(Span::styled("Vim keys", heading)));
+    lines.push(binding(
+        "gg".to_string(),
+        "Jump to the first process, <n>gg to row n",
+    ));
+    lines.push(binding("<n>G".to_string(), "Jump to row n"));
+    lines.push(binding(
+        "Ctrl-d/Ctrl-u".to_string(),
+        "Move half a page down or up",
+    ));
+    lines.push(binding(
+        "<n>j/<n>k".to_string(),
+        "Move n rows, a count also repeats the page and scroll keys",
+    ));
+
+    lines.push(Spans::from(""));
+    lines.push(Spans::from(Span::styled("Tabs", heading)));
+    lines.push(binding(
+        "Ctrl-t".to_string(),
+        "Open a tab with its own search, filters and sort",
+    ));
+    lines.push(binding("Ctrl-w".to_string(), "Close the tab"));
+    lines.push(binding("Alt-1..9".to_string(), "Switch to tab n"));
+
+    lines.push(Spans::from(""));
+    lines.push(Spans::from(Span::styled("Search input", heading)));
+    lines.push(binding("Enter".to_string(), "Apply the search"));
+    lines.push(binding("Esc".to_string(), "Leave the search input"));
+    lines.push(binding(
+        "Up/Down".to_string(),
+        "Recall the previous searches",
+    ));
+    lines.push(binding(
+        "/<regex>".to_string(),
+        "Match the name and command line against a regex",
+    ));
+    lines.push(binding(
+        "u:<user>".to_string(),
+        "Only show processes of a user",
+    ));
+    lines.push(binding(
+        ":<port>".to_string(),
+        "Only show the processes listening on a port",
+    ));
+
+    lines.push(Spans::from(""));
+    lines.push(Spans::from(Span::styled("Popups", heading)));
+    lines.push(binding("Up/Down/j/k".to_string(), "Choose a signal"));
+    lines.push(binding("Enter".to_string(), "Send the chosen signal"));
+    lines.push(binding("y/n".to_string(), "Confirm or cancel a kill"));
+    lines.push(binding("Esc".to_string(), "Close the popup"));
+
+    lines.push(Spans::from(""));
+    lines.push(Spans::from(Span::styled("Services", heading)));
+    lines.push(binding(
+        "x/g/r".to_string(),
+        "Stop, start or restart the selected service",
+    ));
+
+    lines
+}
+
+// everything sysinfo knows about the selected process
+// the whole machine at a glance, like the top of `top`
+// the counts, the sort, the refresh interval and how the last action went
+fn render_status_bar<B: Backend>(f: &mut Frame<B>, app: &App, summary: &SystemSummary, area: Rect) {
+    let theme = app.config.theme;
+    let mut parts = vec![
+        format!("{} processes", summary.processes),
+        format!("{} shown", app.processes.len()),
+        format!("{} marked", app.marked.len()),
+    ];
+    parts.push(match app.sort_order {
+        // the fuzzy search ranks its matches until a column is chosen
+        SortOrder::None if app.search_filter.is_ranked() => "sorted by match".to_string(),
+        SortOrder::None => "sorted by name".to_string(),
+        _ => format!("sorted by {}", app.column_header(app.sort_column)),
+    });
+    let refreshing = if app.is_refreshing() {
+        format!(" {}", SPINNER[app.spinner % SPINNER.len()])
+    } else {
+        String::new()
+    };
+    parts.push(format!(
+        "every {}s{}",
+        app.refresh_interval.as_secs(),
+        refreshing
+    ));
+
+    let mut spans = vec![];
+    // only once there is more than one
+    let labels = app.tab_labels();
+    if labels.len() > 1 {
+        for (i, label) in labels.iter().enumerate() {
+            let style = if i == app.tab {
+                theme.selection
+            } else {
+                theme.status
+            };
+            spans.push(Span::styled(format!(" {} {} ", i + 1, label), style));
+        }
+        spans.push(Span::styled("|", theme.status));
+    }
+    if app.config.read_only {
+        spans.push(Span::styled(" READ ONLY ", theme.warning));
+    } else if app.config.dry_run {
+        spans.push(Span::styled(" DRY RUN ", theme.warning));
+    }
+    spans.push(Span::styled(
+        format!(" {} ", parts.join(" | ")),
+        theme.status,
+    ));
+    if let Some(entry) = app.history.last() {
+        let (result, style) = match &entry.result {
+            Ok(()) => ("ok".to_string(), theme.status),
+            Err(err) => (err.clone(), theme.warning),
+        };
+        spans.push(Span::styled("| last: ", theme.status));
+        spans.push(Span::styled(
+            format!("{} {} {}", entry.action, entry.target, result),
+            style,
+        ));
+    }
+    f.render_widget(Paragraph::new(Spans::from(spans)), area);
+}
+
+fn render_summary<B: Backend>(f: &mut Frame<B>, app: &App, summary: &SystemSummary, area: Rect) {
+    let theme = app.config.theme;
+    let percent = |used: u64, total: u64| {
+        if total == 0 {
+            0.0
+        } else {
+            used as f64 / total as f64 * 100.0
+        }
+    };
+    // above this much a resource is shown as running out
+    let pressure = |percent: f64| {
+        if percent >= 90.0 {
+            theme.warning
+        } else {
+            Style::default()
+        }
+    };
+
+    let memory = percent(summary.memory_used, summary.memory_total);
+    let swap = percent(summary.swap_used, summary.swap_total);
+    let mut spans = vec![
+        Span::styled("CPU ", theme.accent),
+        Span::styled(
+            format!(
+                "{:.1}% of {} {}",
+                summary.cpu_usage,
+                summary.cores.len(),
+                if summary.cores.len() == 1 {
+                    "core"
+                } else {
+                    "cores"
+                }
+            ),
+            pressure(summary.cpu_usage as f64),
+        ),
+        Span::styled("  Mem ", theme.accent),
+        Span::styled(
+            format!(
+                "{} / {} ({:.0}%)",
+                format_bytes(summary.memory_used),
+                format_bytes(summary.memory_total),
+                memory
+            ),
+            pressure(memory),
+        ),
+        Span::styled("  Swap ", theme.accent),
+        Span::styled(
+            format!(
+                "{} / {} ({:.0}%)",
+                format_bytes(summary.swap_used),
+                format_bytes(summary.swap_total),
+                swap
+            ),
+            pressure(swap),
+        ),
+    ];
+    if let Some([one, five, fifteen]) = summary.load {
+        spans.push(Span::styled("  Load ", theme.accent));
+        spans.push(Span::from(format!("{:.2} {:.2} {:.2}", one, five, fifteen)));
+    }
+    spans.push(Span::styled("  Processes ", theme.accent));
+    spans.push(Span::from(summary.processes.to_string()));
+
+    let paragraph = Paragraph::new(Spans::from(spans))
+        .block(Block::default().borders(Borders::ALL).title("System"));
+    f.render_widget(paragraph, area);
+}
+
+// up to 4 columns of at most 8 cores
+fn meter_rows(cores: usize) -> usize {
+    let columns = cores.div_ceil(8).clamp(1, 4);
+    cores.div_ceil(columns)
+}
+
+// htop style bars, a single threaded process shows up as one full core
+fn render_cpu_meters<B: Backend>(f: &mut Frame<B>, app: &App, cores: &[f32], area: Rect) {
+    let theme = app.config.theme;
+    let block = Block::default().borders(Borders::ALL).title("Cores");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = meter_rows(cores.len()).max(1);
+    let columns = cores.len().div_ceil(rows).max(1);
+    let areas = Layout::default()
+        .direction(tui::layout::Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, columns as u32); columns])
+        .split(inner);
+
+    let label_width = cores.len().saturating_sub(1).to_string().len();
+    for (c, (column, area)) in cores.chunks(rows).zip(areas.iter()).enumerate() {
+        // the label, the brackets, the percentage and a gap to the next column
+        let bar_width = (area.width as usize).saturating_sub(label_width + 12);
+        let lines: Vec<Spans> = column
+            .iter()
+            .enumerate()
+            .map(|(i, usage)| {
+                let core = c * rows + i;
+                let filled = ((usage / 100.0) * bar_width as f32).round() as usize;
+                let filled = filled.min(bar_width);
+                let style = if *usage >= 90.0 {
+                    theme.warning
+                } else {
+                    theme.status
+                };
+                Spans::from(vec![
+                    Span::styled(
+                        format!("{:>width$} [", core, width = label_width),
+                        theme.accent,
+                    ),
+                    Span::styled("|".repeat(filled), style),
+                    Span::from(" ".repeat(bar_width - filled)),
+                    Span::from(format!("] {:5.1}%", usage)),
+                ])
+            })
+            .collect();
+        f.render_widget(Paragraph::new(lines), *area);
+    }
+}
+
+// a spike is a few bars, a hog fills the whole width
+fn render_cpu_history<B: Backend>(f: &mut Frame<B>, app: &App, area: Rect) {
+    let history = app
+        .selected_process()
+        .and_then(|process| app.cpu_history.get(&process.pid()));
+    let Some(history) = history.filter(|history| !history.is_empty()) else {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("CPU history (collecting)");
+        f.render_widget(block, area);
+        return;
+    };
+
+    // the newest samples that fit, in tenths of a percent
+    let width = area.width.saturating_sub(2) as usize;
+    let data: Vec<u64> = history
+        .iter()
+        .skip(history.len().saturating_sub(width))
+        .map(|cpu| (cpu * 10.0) as u64)
+        .collect();
+    let peak = history.iter().copied().fold(0.0, f32::max);
+    let average = history.iter().sum::<f32>() / history.len() as f32;
+    let title = format!(
+        "CPU history: now {:.1}%, avg {:.1}%, peak {:.1}%",
+        history.back().copied().unwrap_or(0.0),
+        average,
+        peak
+    );
+    // more than one core's worth stretches the scale
+    let max = (peak.max(100.0) * 10.0) as u64;
+    let sparkline = Sparkline::default()
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .data(&data)
+        .max(max)
+        .style(app.config.theme.accent);
+    f.render_widget(sparkline, area);
+}
+
+fn details_text(app: &App) -> Vec<Spans<'static>> {
+    let Some(process) = app.selected_process() else {
+        return vec![Spans::from("No process selected")];
+    };
+
+    let user = process
+        .user_id()
+        .map(|uid| match app.manager.system().get_user_by_id(uid) {
+            Some(user) => format!("{} ({})", user.name(), **uid),
+            None => (**uid).to_string(),
+        })
+        .unwrap_or_else(|| "-".to_string());
+    let parent = process
+        .parent()
+        .map(|ppid| match app.manager.process(ppid) {
+            Some(parent) => format!("{} ({})", ppid, parent.name()),
+            None => ppid.to_string(),
+        })
+        .unwrap_or_else(|| "-".to_string());
+    let disk_usage = process.disk_usage();
+    // with the keys that act on it
+    let path = |path: &Path, actions: &[(Action, &str)]| {
+        if path.as_os_str().is_empty() {
+            return "-".to_string();
+        }
+        let keys: Vec<String> = actions
+            .iter()
+            .map(|(action, what)| format!("{} {}", app.config.keymap.keys(*action), what))
+            .collect();
+        format!("{}  ({})", path.display(), keys.join(", "))
+    };
+
+    let fields = [
+        ("PID", process.pid().to_string()),
+        ("Name", process.name().to_string()),
+        ("Parent", parent),
+        ("Status", process.status().to_string()),
+        (
+            "Nice",
+            nice_value(process.pid()).map_or("-".to_string(), |n| n.to_string()),
+        ),
+        (
+            "Affinity",
+            cpu_affinity(process.pid()).map_or("-".to_string(), |cpus| format_cpu_list(&cpus)),
+        ),
+        ("User", user),
+        (
+            "Group",
+            process
+                .group_id()
+                .map_or("-".to_string(), |gid| (*gid).to_string()),
+        ),
+        (
+            "Session",
+            process
+                .session_id()
+                .map_or("-".to_string(), |sid| sid.to_string()),
+        ),
+        ("Command", process.cmd().join(" ")),
+        (
+            "Exe",
+            path(
+                process.exe(),
+                &[(Action::CopyExe, "to copy"), (Action::RevealExe, "to open")],
+            ),
+        ),
+        ("Cwd", path(process.cwd(), &[(Action::CopyCwd, "to copy")])),
+        ("Root", process.root().display().to_string()),
+        (
+            "Unit",
+            process_cgroup(process.pid())
+                .ok()
+                .and_then(|cgroup| cgroup.unit())
+                .map_or("-".to_string(), |unit| {
+                    if unit.user {
+                        format!("{} (user)", unit.name)
+                    } else {
+                        unit.name
+                    }
+                }),
+        ),
+        (
+            "Cgroup",
+            process_cgroup(process.pid()).map_or("-".to_string(), |cgroup| cgroup.path),
+        ),
+        ("Started", format_timestamp(process.start_time())),
+        ("Running for", format_duration(process.run_time())),
+        ("CPU", format!("{:.1}%", process.cpu_usage())),
+        ("Memory", format_bytes(process.memory())),
+        ("Virtual memory", format_bytes(process.virtual_memory())),
+        ("Disk read", format_bytes(disk_usage.total_read_bytes)),
+        ("Disk written", format_bytes(disk_usage.total_written_bytes)),
+        (
+            "Environment",
+            format!("{} variables", process.environ().len()),
+        ),
+    ];
+
+    fields
+        .into_iter()
+        .map(|(label, value)| {
+            Spans::from(vec![
+                Span::styled(format!("{}: ", label), app.config.theme.accent),
+                Span::from(value),
+            ])
+        })
+        .collect()
+}
+
+// a rect of the given width (in percent) and height (in lines) centered inside `area`
+fn centered_rect(percent_x: u16, height: u16, area: Rect) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = height.min(area.height);
+
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}